@@ -1,10 +1,18 @@
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 use crate::apple_intelligence;
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
-use crate::managers::audio::AudioRecordingManager;
+use crate::audio_toolkit::{
+    apply_replacements, apply_voice_commands, convert_chinese_variant, voice_commands,
+    ReplacementRule,
+};
+use crate::helpers::focused_app;
+use crate::managers::audio::{pad_short_recording, AudioRecordingManager};
 use crate::managers::history::HistoryManager;
-use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID};
+use crate::managers::transcription::{Transcription, TranscriptionManager};
+use crate::settings::{
+    get_replacement_rules, get_settings, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID,
+    CUSTOM_TRANSCRIBE_BINDING_PREFIX, HANDS_FREE_BINDING_ID,
+};
 use crate::shortcut;
 use crate::tray::{change_tray_icon, TrayIconState};
 use crate::utils::{self, show_recording_overlay, show_transcribing_overlay};
@@ -12,14 +20,23 @@ use async_openai::types::{
     ChatCompletionRequestMessage, ChatCompletionRequestUserMessageArgs,
     CreateChatCompletionRequestArgs,
 };
-use ferrous_opencc::{config::BuiltinConfig, OpenCC};
-use log::{debug, error};
+use log::{debug, error, warn};
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc, Mutex};
 use std::time::Instant;
 use tauri::AppHandle;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
+
+/// Emitted once a recording has been transcribed and post-processed, before
+/// the text is pasted
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptionCompletedEvent {
+    pub binding_id: String,
+    pub text: String,
+    pub final_text: String,
+}
 
 // Shortcut Action Trait
 pub trait ShortcutAction: Send + Sync {
@@ -30,6 +47,49 @@ pub trait ShortcutAction: Send + Sync {
 // Transcribe Action
 struct TranscribeAction;
 
+/// Settings resolved when a recording starts, keyed by binding id, so the stop
+/// handler uses the same profile even if focus moved in the meantime.
+static SESSION_SETTINGS: Lazy<Mutex<HashMap<String, AppSettings>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// `settings` with the focused application's profile applied, if any, and
+/// then the binding's own transcribe options on top.
+fn resolve_session_settings(
+    mut settings: AppSettings,
+    binding_id: &str,
+    focused: Option<focused_app::FocusedApp>,
+) -> AppSettings {
+    if let Some(focused) = focused {
+        let identifiers = focused.identifiers();
+        if let Some(profile) = settings.find_app_profile(&identifiers) {
+            debug!(
+                "Using app profile '{}' for focused app {:?}",
+                profile.name, identifiers
+            );
+            settings = settings.with_profile(profile);
+        }
+    }
+
+    if let Some(options) = settings
+        .bindings
+        .get(binding_id)
+        .and_then(|binding| binding.transcribe_options.clone())
+    {
+        debug!("Applying transcribe options of binding '{}'", binding_id);
+        settings = settings.with_transcribe_options(&options);
+    }
+
+    settings
+}
+
+fn take_session_settings(app: &AppHandle, binding_id: &str) -> AppSettings {
+    SESSION_SETTINGS
+        .lock()
+        .unwrap()
+        .remove(binding_id)
+        .unwrap_or_else(|| get_settings(app))
+}
+
 async fn maybe_post_process_transcription(
     settings: &AppSettings,
     transcription: &str,
@@ -203,42 +263,160 @@ async fn maybe_convert_chinese_variant(
     settings: &AppSettings,
     transcription: &str,
 ) -> Option<String> {
-    // Check if language is set to Simplified or Traditional Chinese
-    let is_simplified = settings.selected_language == "zh-Hans";
-    let is_traditional = settings.selected_language == "zh-Hant";
-
-    if !is_simplified && !is_traditional {
-        debug!("selected_language is not Simplified or Traditional Chinese; skipping translation");
-        return None;
-    }
+    convert_chinese_variant(transcription, &settings.selected_language)
+}
 
-    debug!(
-        "Starting Chinese translation using OpenCC for language: {}",
-        settings.selected_language
-    );
+/// The output of running a raw transcription through the post-processing pipeline
+pub struct ProcessedTranscription {
+    /// The text to deliver to the user
+    pub final_text: String,
+    /// The output of Chinese variant conversion or LLM post-processing. Voice
+    /// commands and replacement rules only change `final_text`.
+    pub post_processed_text: Option<String>,
+    pub post_process_prompt: Option<String>,
+}
 
-    // Use OpenCC to convert based on selected language
-    let config = if is_simplified {
-        // Convert Traditional Chinese to Simplified Chinese
-        BuiltinConfig::Tw2sp
-    } else {
-        // Convert Simplified Chinese to Traditional Chinese
-        BuiltinConfig::S2twp
-    };
+fn maybe_apply_voice_commands(settings: &AppSettings, transcription: &str) -> String {
+    if !settings.voice_commands_enabled {
+        return transcription.to_string();
+    }
 
-    match OpenCC::from_config(config) {
-        Ok(converter) => {
-            let converted = converter.convert(transcription);
+    match voice_commands::resolve_language(
+        &settings.voice_commands_language,
+        &settings.selected_language,
+    ) {
+        Some(language) => {
+            apply_voice_commands(transcription, language, &settings.voice_commands_disabled)
+        }
+        None => {
             debug!(
-                "OpenCC translation completed. Input length: {}, Output length: {}",
-                transcription.len(),
-                converted.len()
+                "No voice commands for language '{}', skipping",
+                settings.selected_language
             );
-            Some(converted)
+            transcription.to_string()
         }
-        Err(e) => {
-            error!("Failed to initialize OpenCC converter: {}. Falling back to original transcription.", e);
-            None
+    }
+}
+
+/// Applies spoken voice commands, then Chinese variant conversion or, failing
+/// that, LLM post-processing according to `settings`, then the replacement
+/// rules.
+pub async fn process_transcription(
+    settings: &AppSettings,
+    replacement_rules: &[ReplacementRule],
+    original: &str,
+) -> ProcessedTranscription {
+    let mut post_processed_text: Option<String> = None;
+    let mut post_process_prompt: Option<String> = None;
+
+    // Spoken commands run first so "scratch that" also removes the sentence
+    // from what gets post-processed
+    let transcription = maybe_apply_voice_commands(settings, original);
+    let mut final_text = transcription.clone();
+
+    // Then check if Chinese variant conversion is needed
+    if let Some(converted_text) = maybe_convert_chinese_variant(settings, &transcription).await {
+        final_text = converted_text.clone();
+        post_processed_text = Some(converted_text);
+    }
+    // Then apply regular post-processing if enabled
+    else if let Some(processed_text) =
+        maybe_post_process_transcription(settings, &transcription).await
+    {
+        final_text = processed_text.clone();
+        post_processed_text = Some(processed_text);
+
+        // Get the prompt that was used
+        if let Some(prompt_id) = &settings.post_process_selected_prompt_id {
+            if let Some(prompt) = settings
+                .post_process_prompts
+                .iter()
+                .find(|p| &p.id == prompt_id)
+            {
+                post_process_prompt = Some(prompt.prompt.clone());
+            }
+        }
+    }
+
+    // Rules run last so spoken commands and snippets survive post-processing
+    let final_text = apply_replacements(&final_text, replacement_rules);
+
+    ProcessedTranscription {
+        final_text,
+        post_processed_text,
+        post_process_prompt,
+    }
+}
+
+/// Post-processes a finished transcription, saves it to history and pastes
+/// it, then resets the overlay and tray icon
+async fn deliver_transcription(
+    ah: AppHandle,
+    binding_id: String,
+    settings: AppSettings,
+    samples: Vec<f32>,
+    transcription_result: anyhow::Result<Transcription>,
+) {
+    let hm = Arc::clone(&ah.state::<Arc<HistoryManager>>());
+    match transcription_result {
+        Ok(Transcription {
+            text: transcription,
+            segments,
+        }) => {
+            debug!("Transcription completed: '{}'", transcription);
+            if !transcription.is_empty() {
+                let processed =
+                    process_transcription(&settings, &get_replacement_rules(&ah), &transcription)
+                        .await;
+                let final_text = processed.final_text.clone();
+
+                let _ = ah.emit(
+                    "transcription-completed",
+                    TranscriptionCompletedEvent {
+                        binding_id: binding_id.clone(),
+                        text: transcription.clone(),
+                        final_text: final_text.clone(),
+                    },
+                );
+
+                // Save to history with post-processed text and prompt
+                let hm_clone = Arc::clone(&hm);
+                let transcription_for_history = transcription.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = hm_clone
+                        .save_transcription(samples, transcription_for_history, segments, processed)
+                        .await
+                    {
+                        error!("Failed to save transcription to history: {}", e);
+                    }
+                });
+
+                // Paste the final text (either processed or original)
+                let ah_clone = ah.clone();
+                let paste_time = Instant::now();
+                ah.run_on_main_thread(move || {
+                    match utils::paste(final_text, ah_clone.clone(), &settings) {
+                        Ok(()) => debug!("Text pasted successfully in {:?}", paste_time.elapsed()),
+                        Err(e) => error!("Failed to paste transcription: {}", e),
+                    }
+                    // Hide the overlay after transcription is complete
+                    utils::hide_recording_overlay(&ah_clone);
+                    change_tray_icon(&ah_clone, TrayIconState::Idle);
+                })
+                .unwrap_or_else(|e| {
+                    error!("Failed to run paste on main thread: {:?}", e);
+                    utils::hide_recording_overlay(&ah);
+                    change_tray_icon(&ah, TrayIconState::Idle);
+                });
+            } else {
+                utils::hide_recording_overlay(&ah);
+                change_tray_icon(&ah, TrayIconState::Idle);
+            }
+        }
+        Err(err) => {
+            debug!("Global Shortcut Transcription error: {}", err);
+            utils::hide_recording_overlay(&ah);
+            change_tray_icon(&ah, TrayIconState::Idle);
         }
     }
 }
@@ -248,19 +426,26 @@ impl ShortcutAction for TranscribeAction {
         let start_time = Instant::now();
         debug!("TranscribeAction::start called for binding: {}", binding_id);
 
-        // Load model in the background
-        let tm = app.state::<Arc<TranscriptionManager>>();
-        tm.initiate_model_load();
+        // The focused app is looked up while the microphone starts and only
+        // waited for afterwards; profiles don't change the microphone mode
+        let global_settings = get_settings(app);
+        let focused_lookup =
+            (!global_settings.app_profiles.is_empty()).then(focused_app::spawn_lookup);
 
+        // Start loading the binding's model before the microphone opens; a
+        // profile picking another model switches the load once it is known
+        let tm = app.state::<Arc<TranscriptionManager>>();
         let binding_id = binding_id.to_string();
+        let binding_model =
+            resolve_session_settings(global_settings.clone(), &binding_id, None).selected_model;
+        tm.initiate_model_load_for(&binding_model);
         change_tray_icon(app, TrayIconState::Recording);
         show_recording_overlay(app);
 
         let rm = app.state::<Arc<AudioRecordingManager>>();
 
         // Get the microphone mode to determine audio feedback timing
-        let settings = get_settings(app);
-        let is_always_on = settings.always_on_microphone;
+        let is_always_on = global_settings.always_on_microphone;
         debug!("Microphone mode - always_on: {}", is_always_on);
 
         let mut recording_started = false;
@@ -302,9 +487,30 @@ impl ShortcutAction for TranscribeAction {
             }
         }
 
+        let focused = focused_lookup.and_then(|lookup| {
+            lookup.join().unwrap_or_else(|_| {
+                warn!("Focused app lookup panicked");
+                None
+            })
+        });
+        let settings = resolve_session_settings(global_settings, &binding_id, focused);
+        if settings.selected_model != binding_model {
+            tm.initiate_model_load_for(&settings.selected_model);
+        }
+
         if recording_started {
+            // Transcribe speech segments while the user is still talking
+            if settings.streaming_transcription {
+                tm.start_streaming_session(settings.clone());
+            }
+
             // Dynamically register the cancel shortcut in a separate task to avoid deadlock
             shortcut::register_cancel_shortcut(app);
+
+            SESSION_SETTINGS
+                .lock()
+                .unwrap()
+                .insert(binding_id.clone(), settings);
         }
 
         debug!(
@@ -314,6 +520,14 @@ impl ShortcutAction for TranscribeAction {
     }
 
     fn stop(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+        // An auto-stop rule may have stopped the recording before the key
+        // was released
+        if !rm.begin_stop(binding_id) {
+            debug!("Recording for binding {} was already stopped", binding_id);
+            return;
+        }
+
         // Unregister the cancel shortcut when transcription stops
         shortcut::unregister_cancel_shortcut(app);
 
@@ -321,9 +535,8 @@ impl ShortcutAction for TranscribeAction {
         debug!("TranscribeAction::stop called for binding: {}", binding_id);
 
         let ah = app.clone();
-        let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
         let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
-        let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+        let settings = take_session_settings(app, binding_id);
 
         change_tray_icon(app, TrayIconState::Transcribing);
         show_transcribing_overlay(app);
@@ -352,96 +565,21 @@ impl ShortcutAction for TranscribeAction {
                 );
 
                 let transcription_time = Instant::now();
-                let samples_clone = samples.clone(); // Clone for history saving
-                match tm.transcribe(samples) {
-                    Ok(transcription) => {
-                        debug!(
-                            "Transcription completed in {:?}: '{}'",
-                            transcription_time.elapsed(),
-                            transcription
-                        );
-                        if !transcription.is_empty() {
-                            let settings = get_settings(&ah);
-                            let mut final_text = transcription.clone();
-                            let mut post_processed_text: Option<String> = None;
-                            let mut post_process_prompt: Option<String> = None;
-
-                            // First, check if Chinese variant conversion is needed
-                            if let Some(converted_text) =
-                                maybe_convert_chinese_variant(&settings, &transcription).await
-                            {
-                                final_text = converted_text.clone();
-                                post_processed_text = Some(converted_text);
-                            }
-                            // Then apply regular post-processing if enabled
-                            else if let Some(processed_text) =
-                                maybe_post_process_transcription(&settings, &transcription).await
-                            {
-                                final_text = processed_text.clone();
-                                post_processed_text = Some(processed_text);
-
-                                // Get the prompt that was used
-                                if let Some(prompt_id) = &settings.post_process_selected_prompt_id {
-                                    if let Some(prompt) = settings
-                                        .post_process_prompts
-                                        .iter()
-                                        .find(|p| &p.id == prompt_id)
-                                    {
-                                        post_process_prompt = Some(prompt.prompt.clone());
-                                    }
-                                }
-                            }
-
-                            // Save to history with post-processed text and prompt
-                            let hm_clone = Arc::clone(&hm);
-                            let transcription_for_history = transcription.clone();
-                            tauri::async_runtime::spawn(async move {
-                                if let Err(e) = hm_clone
-                                    .save_transcription(
-                                        samples_clone,
-                                        transcription_for_history,
-                                        post_processed_text,
-                                        post_process_prompt,
-                                    )
-                                    .await
-                                {
-                                    error!("Failed to save transcription to history: {}", e);
-                                }
-                            });
-
-                            // Paste the final text (either processed or original)
-                            let ah_clone = ah.clone();
-                            let paste_time = Instant::now();
-                            ah.run_on_main_thread(move || {
-                                match utils::paste(final_text, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => error!("Failed to paste transcription: {}", e),
-                                }
-                                // Hide the overlay after transcription is complete
-                                utils::hide_recording_overlay(&ah_clone);
-                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                            })
-                            .unwrap_or_else(|e| {
-                                error!("Failed to run paste on main thread: {:?}", e);
-                                utils::hide_recording_overlay(&ah);
-                                change_tray_icon(&ah, TrayIconState::Idle);
-                            });
-                        } else {
-                            utils::hide_recording_overlay(&ah);
-                            change_tray_icon(&ah, TrayIconState::Idle);
-                        }
-                    }
-                    Err(err) => {
-                        debug!("Global Shortcut Transcription error: {}", err);
-                        utils::hide_recording_overlay(&ah);
-                        change_tray_icon(&ah, TrayIconState::Idle);
-                    }
-                }
+                let transcription_result = if tm.is_streaming() {
+                    // Only the trailing segment is left to transcribe
+                    tm.finish_streaming_session()
+                } else {
+                    tm.transcribe_with_settings(samples.clone(), &settings)
+                };
+                debug!(
+                    "Transcription finished in {:?}",
+                    transcription_time.elapsed()
+                );
+                deliver_transcription(ah, binding_id, settings, samples, transcription_result)
+                    .await;
             } else {
                 debug!("No samples retrieved from recording stop");
+                tm.cancel_streaming_session();
                 utils::hide_recording_overlay(&ah);
                 change_tray_icon(&ah, TrayIconState::Idle);
             }
@@ -454,6 +592,87 @@ impl ShortcutAction for TranscribeAction {
     }
 }
 
+/// Utterances heard in hands-free mode, transcribed one at a time so they are
+/// pasted in the order they were spoken
+static UTTERANCE_QUEUE: Lazy<Mutex<mpsc::Sender<(AppHandle, Vec<f32>)>>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel::<(AppHandle, Vec<f32>)>();
+    std::thread::spawn(move || {
+        for (app, samples) in rx {
+            tauri::async_runtime::block_on(transcribe_utterance(app, samples));
+        }
+    });
+    Mutex::new(tx)
+});
+
+/// Queues an utterance heard in hands-free mode to be transcribed and pasted
+/// like a recording of the transcribe shortcut
+pub fn queue_utterance(app: &AppHandle, samples: Vec<f32>) {
+    if let Err(e) = UTTERANCE_QUEUE.lock().unwrap().send((app.clone(), samples)) {
+        error!("Failed to queue hands-free utterance: {}", e);
+    }
+}
+
+async fn transcribe_utterance(app: AppHandle, samples: Vec<f32>) {
+    let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+    if !rm.hands_free_state().is_listening() {
+        debug!("Hands-free listening stopped, dropping queued utterance");
+        return;
+    }
+    debug!(
+        "Transcribing hands-free utterance, sample count: {}",
+        samples.len()
+    );
+
+    let global_settings = get_settings(&app);
+    let focused = if global_settings.app_profiles.is_empty() {
+        None
+    } else {
+        focused_app::focused_app()
+    };
+    let settings = resolve_session_settings(global_settings, "transcribe", focused);
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    tm.initiate_model_load_for(&settings.selected_model);
+
+    change_tray_icon(&app, TrayIconState::Transcribing);
+    show_transcribing_overlay(&app);
+
+    let samples = pad_short_recording(samples);
+    let transcription_time = Instant::now();
+    let transcription_result = tm.transcribe_with_settings(samples.clone(), &settings);
+    debug!(
+        "Transcription finished in {:?}",
+        transcription_time.elapsed()
+    );
+    deliver_transcription(
+        app,
+        HANDS_FREE_BINDING_ID.to_string(),
+        settings,
+        samples,
+        transcription_result,
+    )
+    .await;
+}
+
+// Hands-free Action
+struct HandsFreeAction;
+
+impl ShortcutAction for HandsFreeAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        match rm.toggle_hands_free_paused() {
+            Ok(paused) => debug!(
+                "Hands-free listening {}",
+                if paused { "paused" } else { "resumed" }
+            ),
+            Err(e) => warn!("Failed to toggle hands-free listening: {}", e),
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Pausing and resuming happens on press
+    }
+}
+
 // Cancel Action
 struct CancelAction;
 
@@ -490,6 +709,15 @@ impl ShortcutAction for TestAction {
     }
 }
 
+/// The action for a binding id. User-created transcribe bindings share the
+/// transcribe action and are told apart by their options.
+pub fn action_for_binding(binding_id: &str) -> Option<Arc<dyn ShortcutAction>> {
+    if binding_id.starts_with(CUSTOM_TRANSCRIBE_BINDING_PREFIX) {
+        return ACTION_MAP.get("transcribe").cloned();
+    }
+    ACTION_MAP.get(binding_id).cloned()
+}
+
 // Static Action Map
 pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::new(|| {
     let mut map = HashMap::new();
@@ -501,6 +729,10 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        HANDS_FREE_BINDING_ID.to_string(),
+        Arc::new(HandsFreeAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
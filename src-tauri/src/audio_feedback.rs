@@ -2,13 +2,18 @@ use crate::settings::SoundTheme;
 use crate::settings::{self, AppSettings};
 use cpal::traits::{DeviceTrait, HostTrait};
 use log::{debug, error, warn};
-use rodio::OutputStreamBuilder;
+use rodio::source::{SineWave, Source};
+use rodio::{OutputStream, OutputStreamBuilder, Sink};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::thread;
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
+const WARNING_TONE_HZ: f32 = 880.0;
+const WARNING_BEEP: Duration = Duration::from_millis(150);
+
 pub enum SoundType {
     Start,
     Stop,
@@ -67,6 +72,19 @@ pub fn play_test_sound(app: &AppHandle, sound_type: SoundType) {
     }
 }
 
+/// Plays two short beeps to warn that the recording is about to be stopped.
+/// They are synthesized, so they sound the same with every theme, and play
+/// even when audio feedback is off.
+pub fn play_warning_sound_blocking(app: &AppHandle) {
+    let settings = settings::get_settings(app);
+    if let Err(e) = play_warning_tone(
+        settings.selected_output_device,
+        settings.audio_feedback_volume,
+    ) {
+        error!("Failed to play warning sound: {}", e);
+    }
+}
+
 fn play_sound_async(app: &AppHandle, path: PathBuf) {
     let app_handle = app.clone();
     thread::spawn(move || {
@@ -89,11 +107,9 @@ fn play_sound_at_path(app: &AppHandle, path: &Path) -> Result<(), Box<dyn std::e
     play_audio_file(path, selected_device, volume)
 }
 
-fn play_audio_file(
-    path: &std::path::Path,
+fn open_output_stream(
     selected_device: Option<String>,
-    volume: f32,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<OutputStream, Box<dyn std::error::Error>> {
     let stream_builder = if let Some(device_name) = selected_device {
         if device_name == "Default" {
             debug!("Using default device");
@@ -123,7 +139,15 @@ fn play_audio_file(
         OutputStreamBuilder::from_default_device()?
     };
 
-    let stream_handle = stream_builder.open_stream()?;
+    Ok(stream_builder.open_stream()?)
+}
+
+fn play_audio_file(
+    path: &std::path::Path,
+    selected_device: Option<String>,
+    volume: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stream_handle = open_output_stream(selected_device)?;
     let mixer = stream_handle.mixer();
 
     let file = File::open(path)?;
@@ -135,3 +159,23 @@ fn play_audio_file(
 
     Ok(())
 }
+
+fn play_warning_tone(
+    selected_device: Option<String>,
+    volume: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stream_handle = open_output_stream(selected_device)?;
+    let sink = Sink::connect_new(stream_handle.mixer());
+
+    let beep = || {
+        SineWave::new(WARNING_TONE_HZ)
+            .take_duration(WARNING_BEEP)
+            .amplify(0.3)
+    };
+    sink.append(beep());
+    sink.append(beep().delay(WARNING_BEEP));
+    sink.set_volume(volume);
+    sink.sleep_until_end();
+
+    Ok(())
+}
@@ -6,7 +6,9 @@ mod utils;
 mod visualizer;
 
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
-pub use recorder::AudioRecorder;
+pub use recorder::{AudioRecorder, RecorderEvent, UtteranceConfig};
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use utils::{
+    decode_audio_bytes, decode_pcm, encode_wav, read_audio_file, save_wav_file, PcmFormat,
+};
 pub use visualizer::AudioVisualiser;
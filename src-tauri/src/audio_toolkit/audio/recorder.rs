@@ -19,15 +19,56 @@ use crate::audio_toolkit::{
 enum Cmd {
     Start,
     Stop(mpsc::Sender<Vec<f32>>),
+    Listen(Option<UtteranceConfig>),
     Shutdown,
 }
 
+/// Events emitted from the consumer thread while a recording is in progress.
+pub enum RecorderEvent {
+    /// A VAD-delimited chunk of speech. Chunks are contiguous slices of the
+    /// recording, so concatenating them yields the samples returned by `stop`.
+    SpeechSegment(Vec<f32>),
+    /// An utterance heard while listening hands-free, from the onset of
+    /// speech until the configured silence after it.
+    Utterance(Vec<f32>),
+    /// Emitted for every frame of a recording with its length so far, the
+    /// stretch of non-speech at its end, and whether any speech was heard.
+    /// Before the first speech, the silence is the whole recording.
+    Progress {
+        elapsed: Duration,
+        silence: Duration,
+        heard_speech: bool,
+    },
+}
+
+/// How hands-free listening cuts the microphone input into utterances
+#[derive(Clone, Copy, Debug)]
+pub struct UtteranceConfig {
+    /// Non-speech after speech that ends an utterance
+    pub end_silence: Duration,
+    /// Utterances holding less speech than this are dropped as noise
+    pub min_length: Duration,
+}
+
+type EventCallback = Arc<dyn Fn(RecorderEvent) + Send + Sync + 'static>;
+
+// Speech segments are only cut at a pause once they hold at least this much
+// audio, and are force-cut when they grow past the upper bound.
+const MIN_SEGMENT_SAMPLES: usize = constants::WHISPER_SAMPLE_RATE as usize * 3;
+const MAX_SEGMENT_SAMPLES: usize = constants::WHISPER_SAMPLE_RATE as usize * 25;
+
+// Hands-free utterances are cut here even if the speaker never pauses.
+const MAX_UTTERANCE_SAMPLES: usize = constants::WHISPER_SAMPLE_RATE as usize * 60;
+
+const FRAME_DURATION: Duration = Duration::from_millis(30);
+
 pub struct AudioRecorder {
     device: Option<Device>,
     cmd_tx: Option<mpsc::Sender<Cmd>>,
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    event_cb: Option<EventCallback>,
 }
 
 impl AudioRecorder {
@@ -38,6 +79,7 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            event_cb: None,
         })
     }
 
@@ -54,6 +96,14 @@ impl AudioRecorder {
         self
     }
 
+    pub fn with_event_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(RecorderEvent) + Send + Sync + 'static,
+    {
+        self.event_cb = Some(Arc::new(cb));
+        self
+    }
+
     pub fn open(&mut self, device: Option<Device>) -> Result<(), Box<dyn std::error::Error>> {
         if self.worker_handle.is_some() {
             return Ok(()); // already open
@@ -74,6 +124,7 @@ impl AudioRecorder {
         let vad = self.vad.clone();
         // Move the optional level callback into the worker thread
         let level_cb = self.level_cb.clone();
+        let event_cb = self.event_cb.clone();
 
         let worker = std::thread::spawn(move || {
             let config = AudioRecorder::get_preferred_config(&thread_device)
@@ -117,7 +168,7 @@ impl AudioRecorder {
             stream.play().expect("failed to start stream");
 
             // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
+            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb, event_cb);
             // stream is dropped here, after run_consumer returns
         });
 
@@ -143,6 +194,19 @@ impl AudioRecorder {
         Ok(resp_rx.recv()?) // wait for the samples
     }
 
+    /// Listens hands-free, emitting every utterance as a
+    /// [`RecorderEvent::Utterance`] while no recording is in progress.
+    /// `None` stops listening.
+    pub fn listen(
+        &self,
+        config: Option<UtteranceConfig>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Listen(config))?;
+        }
+        Ok(())
+    }
+
     pub fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = self.cmd_tx.take() {
             let _ = tx.send(Cmd::Shutdown);
@@ -239,21 +303,132 @@ impl AudioRecorder {
     }
 }
 
+/// Splits the samples captured during a recording into speech segments.
+/// A segment is closed at the first pause after it reaches
+/// `MIN_SEGMENT_SAMPLES`, or unconditionally at `MAX_SEGMENT_SAMPLES`.
+struct SegmentTracker {
+    segment_start: usize,
+    in_speech: bool,
+}
+
+impl SegmentTracker {
+    fn new() -> Self {
+        Self {
+            segment_start: 0,
+            in_speech: false,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.segment_start = 0;
+        self.in_speech = false;
+    }
+
+    fn observe(&mut self, is_speech: bool, samples: &[f32], event_cb: &Option<EventCallback>) {
+        let pending = samples.len().saturating_sub(self.segment_start);
+        let pause_after_speech = self.in_speech && !is_speech;
+        self.in_speech = is_speech;
+
+        if (pause_after_speech && pending >= MIN_SEGMENT_SAMPLES) || pending >= MAX_SEGMENT_SAMPLES
+        {
+            self.flush(samples, event_cb);
+        }
+    }
+
+    fn flush(&mut self, samples: &[f32], event_cb: &Option<EventCallback>) {
+        if samples.len() > self.segment_start {
+            if let Some(cb) = event_cb {
+                cb(RecorderEvent::SpeechSegment(
+                    samples[self.segment_start..].to_vec(),
+                ));
+            }
+            self.segment_start = samples.len();
+        }
+    }
+}
+
+/// Collects the speech heard while listening hands-free into utterances,
+/// each ended by a stretch of non-speech.
+struct UtteranceTracker {
+    end_silence_frames: usize,
+    min_samples: usize,
+    samples: Vec<f32>,
+    silent_frames: usize,
+}
+
+impl UtteranceTracker {
+    fn new(config: UtteranceConfig) -> Self {
+        let end_silence_frames = config.end_silence.as_millis() / FRAME_DURATION.as_millis();
+        Self {
+            end_silence_frames: (end_silence_frames as usize).max(1),
+            min_samples: (config.min_length.as_secs_f32() * constants::WHISPER_SAMPLE_RATE as f32)
+                as usize,
+            samples: Vec::new(),
+            silent_frames: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+        self.silent_frames = 0;
+    }
+
+    fn observe(&mut self, frame: VadFrame, event_cb: &Option<EventCallback>) {
+        match frame {
+            VadFrame::Speech(buf) => {
+                self.samples.extend_from_slice(buf);
+                self.silent_frames = 0;
+                if self.samples.len() >= MAX_UTTERANCE_SAMPLES {
+                    self.finish(event_cb);
+                }
+            }
+            VadFrame::Noise if !self.samples.is_empty() => {
+                self.silent_frames += 1;
+                if self.silent_frames >= self.end_silence_frames {
+                    self.finish(event_cb);
+                }
+            }
+            VadFrame::Noise => {}
+        }
+    }
+
+    fn finish(&mut self, event_cb: &Option<EventCallback>) {
+        let samples = std::mem::take(&mut self.samples);
+        self.silent_frames = 0;
+        if samples.len() < self.min_samples {
+            log::debug!("Dropping utterance of {} samples", samples.len());
+            return;
+        }
+        if let Some(cb) = event_cb {
+            cb(RecorderEvent::Utterance(samples));
+        }
+    }
+}
+
 fn run_consumer(
     in_sample_rate: u32,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     sample_rx: mpsc::Receiver<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    event_cb: Option<EventCallback>,
 ) {
     let mut frame_resampler = FrameResampler::new(
         in_sample_rate as usize,
         constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
+        FRAME_DURATION,
     );
 
     let mut processed_samples = Vec::<f32>::new();
     let mut recording = false;
+    let mut segments = SegmentTracker::new();
+    // Frames in the current recording, and how many of the latest were not
+    // speech
+    let mut frames: u32 = 0;
+    let mut silent_frames: u32 = 0;
+    let mut heard_speech = false;
+    // Set while listening hands-free
+    let mut utterances: Option<UtteranceTracker> = None;
 
     // ---------- spectrum visualisation setup ---------------------------- //
     const BUCKETS: usize = 16;
@@ -271,19 +446,23 @@ fn run_consumer(
         recording: bool,
         vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
         out_buf: &mut Vec<f32>,
-    ) {
+    ) -> bool {
         if !recording {
-            return;
+            return false;
         }
 
         if let Some(vad_arc) = vad {
             let mut det = vad_arc.lock().unwrap();
             match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
-                VadFrame::Speech(buf) => out_buf.extend_from_slice(buf),
-                VadFrame::Noise => {}
+                VadFrame::Speech(buf) => {
+                    out_buf.extend_from_slice(buf);
+                    true
+                }
+                VadFrame::Noise => false,
             }
         } else {
             out_buf.extend_from_slice(samples);
+            true
         }
     }
 
@@ -302,7 +481,29 @@ fn run_consumer(
 
         // ---------- existing pipeline ------------------------------------ //
         frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples)
+            if recording {
+                let is_speech = handle_frame(frame, recording, &vad, &mut processed_samples);
+                segments.observe(is_speech, &processed_samples, &event_cb);
+
+                frames += 1;
+                heard_speech |= is_speech;
+                if !is_speech {
+                    silent_frames += 1;
+                } else {
+                    silent_frames = 0;
+                }
+                if let Some(cb) = &event_cb {
+                    cb(RecorderEvent::Progress {
+                        elapsed: FRAME_DURATION * frames,
+                        silence: FRAME_DURATION * silent_frames,
+                        heard_speech,
+                    });
+                }
+            } else if let (Some(tracker), Some(vad_arc)) = (utterances.as_mut(), &vad) {
+                let mut det = vad_arc.lock().unwrap();
+                let frame = det.push_frame(frame).unwrap_or(VadFrame::Noise);
+                tracker.observe(frame, &event_cb);
+            }
         });
 
         // non-blocking check for a command
@@ -310,6 +511,14 @@ fn run_consumer(
             match cmd {
                 Cmd::Start => {
                     processed_samples.clear();
+                    segments.reset();
+                    frames = 0;
+                    silent_frames = 0;
+                    heard_speech = false;
+                    // A recording takes over from hands-free listening
+                    if let Some(tracker) = utterances.as_mut() {
+                        tracker.reset();
+                    }
                     recording = true;
                     visualizer.reset(); // Reset visualization buffer
                     if let Some(v) = &vad {
@@ -321,10 +530,32 @@ fn run_consumer(
 
                     frame_resampler.finish(&mut |frame: &[f32]| {
                         // we still want to process the last few frames
-                        handle_frame(frame, true, &vad, &mut processed_samples)
+                        handle_frame(frame, true, &vad, &mut processed_samples);
                     });
 
+                    // Hand over the trailing segment before replying so listeners
+                    // have received every chunk by the time `stop` returns.
+                    segments.flush(&processed_samples, &event_cb);
+
                     let _ = reply_tx.send(std::mem::take(&mut processed_samples));
+
+                    if utterances.is_some() {
+                        if let Some(v) = &vad {
+                            v.lock().unwrap().reset();
+                        }
+                    }
+                }
+                Cmd::Listen(config) => {
+                    if config.is_some() && vad.is_none() {
+                        log::warn!("Hands-free listening needs a VAD");
+                        continue;
+                    }
+                    utterances = config.map(UtteranceTracker::new);
+                    if !recording {
+                        if let Some(v) = &vad {
+                            v.lock().unwrap().reset();
+                        }
+                    }
                 }
                 Cmd::Shutdown => return,
             }
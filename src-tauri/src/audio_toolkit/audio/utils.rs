@@ -1,7 +1,20 @@
-use anyhow::Result;
-use hound::{WavSpec, WavWriter};
+use super::FrameResampler;
+use crate::audio_toolkit::constants;
+use anyhow::{anyhow, Result};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
 use log::debug;
+use rodio::Source;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
 use std::path::Path;
+use std::time::Duration;
+
+/// Raw PCM sample encodings accepted by [`decode_pcm`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcmFormat {
+    S16Le,
+    F32Le,
+}
 
 /// Save audio samples as a WAV file
 pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
@@ -24,3 +37,153 @@ pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Res
     debug!("Saved WAV file: {:?}", file_path.as_ref());
     Ok(())
 }
+
+/// Encode mono samples at the model sample rate as an in-memory 16-bit WAV file
+pub fn encode_wav(samples: &[f32]) -> Result<Vec<u8>> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: constants::WHISPER_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut buffer = Cursor::new(Vec::with_capacity(44 + samples.len() * 2));
+    {
+        let mut writer = WavWriter::new(&mut buffer, spec)?;
+        for sample in samples {
+            let sample_i16 = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            writer.write_sample(sample_i16)?;
+        }
+        writer.finalize()?;
+    }
+    Ok(buffer.into_inner())
+}
+
+/// Read an audio file and return mono samples at the model sample rate.
+///
+/// WAV files are read with hound; anything else (FLAC, MP3, OGG, ...) is
+/// decoded with rodio.
+pub fn read_audio_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<f32>> {
+    let path = file_path.as_ref();
+    let is_wav = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("wav"))
+        .unwrap_or(false);
+
+    let (samples, channels, sample_rate) = if is_wav {
+        read_wav(WavReader::open(path)?)?
+    } else {
+        let file = BufReader::new(File::open(path)?);
+        let decoder =
+            rodio::Decoder::new(file).map_err(|e| anyhow!("Failed to decode {:?}: {}", path, e))?;
+        let channels = decoder.channels() as usize;
+        let sample_rate = decoder.sample_rate();
+        (decoder.collect(), channels, sample_rate)
+    };
+
+    debug!(
+        "Read audio file {:?}: {} samples, {} channel(s), {} Hz",
+        path,
+        samples.len(),
+        channels,
+        sample_rate
+    );
+    Ok(to_model_input(&samples, channels, sample_rate))
+}
+
+/// Decode an in-memory audio file (WAV, FLAC, MP3, OGG, ...) into mono samples
+/// at the model sample rate. The container is detected from the data itself.
+pub fn decode_audio_bytes(bytes: Vec<u8>) -> Result<Vec<f32>> {
+    let is_wav = bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE";
+
+    let (samples, channels, sample_rate) = if is_wav {
+        read_wav(WavReader::new(Cursor::new(bytes))?)?
+    } else {
+        let decoder = rodio::Decoder::new(Cursor::new(bytes))
+            .map_err(|e| anyhow!("Failed to decode audio: {}", e))?;
+        let channels = decoder.channels() as usize;
+        let sample_rate = decoder.sample_rate();
+        (decoder.collect(), channels, sample_rate)
+    };
+
+    debug!(
+        "Decoded audio: {} samples, {} channel(s), {} Hz",
+        samples.len(),
+        channels,
+        sample_rate
+    );
+    Ok(to_model_input(&samples, channels, sample_rate))
+}
+
+/// Decode raw little-endian PCM bytes into mono samples at the model sample rate
+pub fn decode_pcm(
+    bytes: &[u8],
+    format: PcmFormat,
+    channels: usize,
+    sample_rate: u32,
+) -> Result<Vec<f32>> {
+    if channels == 0 || sample_rate == 0 {
+        return Err(anyhow!(
+            "Channels and sample rate must be greater than zero"
+        ));
+    }
+
+    let samples: Vec<f32> = match format {
+        PcmFormat::S16Le => bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+            .collect(),
+        PcmFormat::F32Le => bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    };
+
+    Ok(to_model_input(&samples, channels, sample_rate))
+}
+
+fn read_wav<R: Read>(mut reader: WavReader<R>) -> Result<(Vec<f32>, usize, u32)> {
+    let spec = reader.spec();
+
+    let samples = match spec.sample_format {
+        SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?,
+        SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    Ok((samples, spec.channels as usize, spec.sample_rate))
+}
+
+/// Downmix interleaved samples to mono and resample to the model sample rate
+fn to_model_input(samples: &[f32], channels: usize, sample_rate: u32) -> Vec<f32> {
+    let mono: Vec<f32> = if channels > 1 {
+        samples
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect()
+    } else {
+        samples.to_vec()
+    };
+
+    if sample_rate == constants::WHISPER_SAMPLE_RATE {
+        return mono;
+    }
+
+    let mut resampler = FrameResampler::new(
+        sample_rate as usize,
+        constants::WHISPER_SAMPLE_RATE as usize,
+        Duration::from_millis(30),
+    );
+    let mut out = Vec::with_capacity(
+        mono.len() * constants::WHISPER_SAMPLE_RATE as usize / sample_rate as usize + 1,
+    );
+    resampler.push(&mono, |frame| out.extend_from_slice(frame));
+    resampler.finish(|frame| out.extend_from_slice(frame));
+    out
+}
@@ -0,0 +1,394 @@
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use handy_app_lib::audio_toolkit::{
+    convert_chinese_variant, decode_pcm, read_audio_file,
+    subtitles::{to_srt, SubtitleCue},
+    PcmFormat,
+};
+use handy_app_lib::{
+    builtin_models, correct_transcription, get_default_settings, load_engine_at,
+    read_custom_models, AppSettings, EngineType, ModelInfo, SETTINGS_STORE_PATH,
+};
+
+// Must match the identifier in tauri.conf.json so models downloaded by the app are found
+const APP_IDENTIFIER: &str = "com.pais.handy";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Srt,
+}
+
+/// Command-line options. Transcription options left unset fall back to the
+/// Handy app's settings.
+#[derive(Debug)]
+struct Options {
+    input: Option<String>,
+    model: Option<String>,
+    models_dir: Option<PathBuf>,
+    language: Option<String>,
+    translate: bool,
+    custom_words: Option<Vec<String>>,
+    threshold: Option<f64>,
+    prompt: Option<String>,
+    format: OutputFormat,
+    pcm_format: PcmFormat,
+    pcm_sample_rate: u32,
+    pcm_channels: usize,
+    list_models: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            input: None,
+            model: None,
+            models_dir: None,
+            language: None,
+            translate: false,
+            custom_words: None,
+            threshold: None,
+            prompt: None,
+            format: OutputFormat::Text,
+            pcm_format: PcmFormat::S16Le,
+            pcm_sample_rate: 16000,
+            pcm_channels: 1,
+            list_models: false,
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print_help();
+            return;
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("Run `handy-cli --help` for usage.");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(options) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(options: Options) -> Result<(), Box<dyn std::error::Error>> {
+    let app_dir = app_data_dir();
+    let models_dir = match (&options.models_dir, &app_dir) {
+        (Some(dir), _) => dir.clone(),
+        (None, Some(app_dir)) => app_dir.join("models"),
+        (None, None) => {
+            return Err("Could not determine the app data directory; use --models-dir".into())
+        }
+    };
+
+    let mut models: Vec<ModelInfo> = builtin_models().into_values().collect();
+    for custom in read_custom_models(&models_dir)? {
+        if !models.iter().any(|m| m.id == custom.id) {
+            models.push(custom.to_model_info());
+        }
+    }
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+
+    if options.list_models {
+        print_models(&models, &models_dir);
+        return Ok(());
+    }
+
+    let model_id = options
+        .model
+        .as_deref()
+        .ok_or("No model given. Use --model <id> (see --list-models).")?;
+    let model = models
+        .iter()
+        .find(|m| m.id == model_id)
+        .ok_or_else(|| format!("Unknown model '{}'. See --list-models.", model_id))?;
+    let model_path = models_dir.join(&model.filename);
+    if !matches!(model.engine_type, EngineType::Remote) && !model_path.exists() {
+        return Err(format!(
+            "Model '{}' is not downloaded (expected at {}). Download it from the Handy app first.",
+            model.id,
+            model_path.display()
+        )
+        .into());
+    }
+
+    let input = options
+        .input
+        .as_deref()
+        .ok_or("No input given. Pass an audio file, or '-' to read raw PCM from stdin.")?;
+    let samples = if input == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        decode_pcm(
+            &bytes,
+            options.pcm_format,
+            options.pcm_channels,
+            options.pcm_sample_rate,
+        )?
+    } else {
+        read_audio_file(input)?
+    };
+
+    if samples.is_empty() {
+        return Err("No audio samples to transcribe".into());
+    }
+
+    let settings = settings_for(&options, app_dir.as_deref());
+    let mut engine = load_engine_at(model, &model_path)?;
+    let result = engine.transcribe(samples.clone(), &settings);
+    let supports_prompt = engine.supports_prompt();
+    engine.unload();
+    let result = correct_transcription(result?, &settings, supports_prompt);
+
+    let convert = |text: &str| {
+        convert_chinese_variant(text, &settings.selected_language)
+            .unwrap_or_else(|| text.to_string())
+    };
+    let text = convert(&result.text);
+    let cues: Vec<SubtitleCue> = result
+        .segments
+        .iter()
+        .map(|segment| SubtitleCue {
+            start: segment.start,
+            end: segment.end,
+            text: convert(&segment.text),
+        })
+        .collect();
+
+    let output = match options.format {
+        OutputFormat::Text => format!("{}\n", text),
+        OutputFormat::Json => {
+            let segments: Vec<serde_json::Value> = cues
+                .iter()
+                .map(|cue| {
+                    serde_json::json!({
+                        "start": cue.start,
+                        "end": cue.end,
+                        "text": cue.text,
+                    })
+                })
+                .collect();
+            let value = serde_json::json!({
+                "model": model.id,
+                "language": settings.selected_language,
+                "duration": samples.len() as f64 / 16000.0,
+                "text": text,
+                "segments": segments,
+            });
+            format!("{}\n", serde_json::to_string_pretty(&value)?)
+        }
+        OutputFormat::Srt => {
+            if cues.is_empty() {
+                // Fall back to a single cue spanning the whole input
+                to_srt(&[SubtitleCue {
+                    start: 0.0,
+                    end: samples.len() as f32 / 16000.0,
+                    text,
+                }])
+            } else {
+                to_srt(&cues)
+            }
+        }
+    };
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(output.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// The app's saved settings with the command-line options applied on top.
+/// The app's settings supply the remote provider, context prompt and custom
+/// word mode; defaults are used when Handy has never been run.
+fn settings_for(options: &Options, app_dir: Option<&Path>) -> AppSettings {
+    let mut settings = app_dir
+        .and_then(|dir| read_app_settings(&dir.join(SETTINGS_STORE_PATH)))
+        .unwrap_or_else(get_default_settings);
+
+    if let Some(language) = &options.language {
+        settings.selected_language = language.clone();
+    }
+    if options.translate {
+        settings.translate_to_english = true;
+    }
+    if let Some(custom_words) = &options.custom_words {
+        settings.custom_words = custom_words.clone();
+    }
+    if let Some(threshold) = options.threshold {
+        settings.word_correction_threshold = threshold;
+    }
+    if let Some(prompt) = &options.prompt {
+        settings.transcription_context_prompt = prompt.clone();
+    }
+    settings
+}
+
+/// Reads the settings the app keeps in its store file
+fn read_app_settings(path: &Path) -> Option<AppSettings> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let mut store: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    match serde_json::from_value(store.get_mut("settings")?.take()) {
+        Ok(settings) => Some(settings),
+        Err(e) => {
+            eprintln!(
+                "warning: ignoring unreadable settings in {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let mut options = Options::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-m" | "--model" => options.model = Some(value(arg)?),
+            "--models-dir" => options.models_dir = Some(PathBuf::from(value(arg)?)),
+            "-l" | "--language" => options.language = Some(value(arg)?),
+            "--translate" => options.translate = true,
+            "-p" | "--prompt" => options.prompt = Some(value(arg)?),
+            "--custom-words" => {
+                options.custom_words = Some(
+                    value(arg)?
+                        .split(',')
+                        .map(|w| w.trim().to_string())
+                        .filter(|w| !w.is_empty())
+                        .collect(),
+                );
+            }
+            "--threshold" => {
+                options.threshold = Some(
+                    value(arg)?
+                        .parse()
+                        .map_err(|_| "--threshold must be a number".to_string())?,
+                );
+            }
+            "-f" | "--format" => {
+                options.format = match value(arg)?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    "srt" => OutputFormat::Srt,
+                    other => return Err(format!("Unknown output format: {}", other)),
+                };
+            }
+            "--pcm-format" => {
+                options.pcm_format = match value(arg)?.as_str() {
+                    "s16le" => PcmFormat::S16Le,
+                    "f32le" => PcmFormat::F32Le,
+                    other => return Err(format!("Unknown PCM format: {}", other)),
+                };
+            }
+            "--sample-rate" => {
+                options.pcm_sample_rate = value(arg)?
+                    .parse()
+                    .map_err(|_| "--sample-rate must be a positive integer".to_string())?;
+            }
+            "--channels" => {
+                options.pcm_channels = value(arg)?
+                    .parse()
+                    .map_err(|_| "--channels must be a positive integer".to_string())?;
+            }
+            "--list-models" => options.list_models = true,
+            other if other.starts_with('-') && other != "-" => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            other => {
+                if options.input.is_some() {
+                    return Err("Only one input file can be given".to_string());
+                }
+                options.input = Some(other.to_string());
+            }
+        }
+    }
+
+    Ok(Some(options))
+}
+
+/// Mirrors Tauri's `app_data_dir()` so the CLI shares the app's models and settings
+fn app_data_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let base = std::env::var_os("HOME")
+        .map(|h| PathBuf::from(h).join("Library").join("Application Support"));
+
+    #[cfg(target_os = "windows")]
+    let base = std::env::var_os("APPDATA").map(PathBuf::from);
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local").join("share"))
+        });
+
+    base.map(|base| base.join(APP_IDENTIFIER))
+}
+
+fn print_models(models: &[ModelInfo], models_dir: &Path) {
+    println!("Models in {}:", models_dir.display());
+    for model in models {
+        let downloaded = models_dir.join(&model.filename).exists()
+            && !models_dir
+                .join(format!("{}.partial", &model.filename))
+                .exists();
+        println!(
+            "  {:<22} {:<16} {}",
+            model.id,
+            model.name,
+            if matches!(model.engine_type, EngineType::Remote) {
+                "remote (uses the app's provider settings)"
+            } else if downloaded {
+                "downloaded"
+            } else {
+                "not downloaded"
+            }
+        );
+    }
+}
+
+fn print_help() {
+    println!("handy-cli - transcribe audio files with Handy's models");
+    println!();
+    println!("Usage:");
+    println!("  handy-cli --model <id> [options] <file.wav|file.flac>");
+    println!("  cat audio.raw | handy-cli --model <id> [options] -");
+    println!();
+    println!("Options:");
+    println!("  -m, --model <id>          Model to use (see --list-models)");
+    println!("      --models-dir <path>   Directory containing downloaded models");
+    println!("  -l, --language <code>     Language code, or 'auto'");
+    println!("      --translate           Translate to English (Whisper and remote models)");
+    println!("  -p, --prompt <text>       Context prompt given to Whisper and remote models");
+    println!("      --custom-words <list> Comma-separated custom words to correct towards");
+    println!("      --threshold <n>       Custom word correction threshold");
+    println!("  -f, --format <fmt>        Output format: text, json or srt (default: text)");
+    println!("      --pcm-format <fmt>    Raw stdin format: s16le or f32le (default: s16le)");
+    println!("      --sample-rate <hz>    Raw stdin sample rate (default: 16000)");
+    println!("      --channels <n>        Raw stdin channel count (default: 1)");
+    println!("      --list-models         List known models and whether they are downloaded");
+    println!("  -h, --help                Show this help message");
+    println!();
+    println!("Options not given are taken from the Handy app's settings, or its defaults.");
+    println!("Raw PCM is only read from stdin when the input is '-'.");
+}
@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::text::{apply_custom_words, parse_custom_word};
+
+/// Longest phrase, in words, on either side of a correction. Longer changes
+/// are rewrites rather than misheard words.
+const MAX_PHRASE_WORDS: usize = 4;
+
+/// Changed regions longer than this are not diffed, which keeps the
+/// comparison cheap for texts that were rewritten wholesale
+const MAX_DIFF_WORDS: usize = 500;
+
+/// A word or phrase the user retyped in a transcription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction {
+    /// What the transcription said, lowercase
+    pub heard: String,
+    /// What the user typed instead
+    pub corrected: String,
+}
+
+/// How a correction can be learned
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrectionKind {
+    /// Adds the corrected word to the custom words, so fuzzy matching
+    /// substitutes it for anything that sounds like it
+    CustomWord,
+    /// Adds an exact replacement rule from the heard phrase to the corrected one
+    Replacement,
+}
+
+struct Token<'a> {
+    raw: &'a str,
+    /// The token without surrounding punctuation
+    word: &'a str,
+}
+
+/// Finds the words and short phrases the user replaced when editing a
+/// transcription. Punctuation changes, added or removed words and
+/// capitalization at the start of a sentence are not corrections.
+///
+/// # Arguments
+/// * `original` - The text as it was transcribed
+/// * `edited` - The text after the user's edit
+///
+/// # Returns
+/// Each replaced word or phrase, in text order
+pub fn extract_corrections(original: &str, edited: &str) -> Vec<Correction> {
+    let original = tokenize(original);
+    let edited = tokenize(edited);
+
+    // Words shared at both ends can't be part of a correction
+    let prefix = original
+        .iter()
+        .zip(&edited)
+        .take_while(|(a, b)| a.word == b.word)
+        .count();
+    let suffix = original[prefix..]
+        .iter()
+        .rev()
+        .zip(edited[prefix..].iter().rev())
+        .take_while(|(a, b)| a.word == b.word)
+        .count();
+    let removed = &original[prefix..original.len() - suffix];
+    let inserted = &edited[prefix..edited.len() - suffix];
+    if removed.len() > MAX_DIFF_WORDS || inserted.len() > MAX_DIFF_WORDS {
+        return Vec::new();
+    }
+
+    let (runs, common) = changed_runs(removed, inserted);
+    // When most of the text changed, it was rewritten rather than corrected
+    if 2 * (prefix + suffix + common) < original.len() {
+        return Vec::new();
+    }
+
+    let mut corrections = Vec::new();
+    for (heard, typed) in runs {
+        if heard.is_empty()
+            || typed.is_empty()
+            || heard.len() > MAX_PHRASE_WORDS
+            || typed.len() > MAX_PHRASE_WORDS
+        {
+            continue;
+        }
+
+        let heard_text = join_words(&removed[heard]).to_lowercase();
+        let corrected = join_words(&inserted[typed.clone()]);
+        if heard_text == corrected.to_lowercase() {
+            // Lowercasing a word or capitalizing a sentence teaches nothing
+            let at = prefix + typed.start;
+            let sentence_start = at == 0 || edited[at - 1].raw.ends_with(['.', '!', '?']);
+            if sentence_start || !corrected.chars().any(char::is_uppercase) {
+                continue;
+            }
+        }
+
+        corrections.push(Correction {
+            heard: heard_text,
+            corrected,
+        });
+    }
+    corrections
+}
+
+/// Picks how to learn a correction: as a custom word when fuzzy matching
+/// would turn what was heard into the correction, and as a replacement rule
+/// when it wouldn't or when the custom word is already there.
+pub fn suggest_kind(
+    heard: &str,
+    corrected: &str,
+    custom_words: &[String],
+    threshold: f64,
+    language: &str,
+) -> CorrectionKind {
+    let corrected_lower = corrected.to_lowercase();
+    let known = custom_words
+        .iter()
+        .any(|entry| parse_custom_word(entry).1.to_lowercase() == corrected_lower);
+    if known {
+        return CorrectionKind::Replacement;
+    }
+
+    let candidate = [corrected.to_string()];
+    if apply_custom_words(heard, &candidate, threshold, language) == corrected {
+        CorrectionKind::CustomWord
+    } else {
+        CorrectionKind::Replacement
+    }
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    text.split_whitespace()
+        .filter_map(|raw| {
+            let word = raw.trim_matches(|c: char| !c.is_alphanumeric());
+            (!word.is_empty()).then_some(Token { raw, word })
+        })
+        .collect()
+}
+
+fn join_words(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| token.word)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+type Range = std::ops::Range<usize>;
+
+/// Diffs two word sequences by their longest common subsequence, returning
+/// each run of words that differs as a range into both, and the number of
+/// words in common
+fn changed_runs(a: &[Token], b: &[Token]) -> (Vec<(Range, Range)>, usize) {
+    let (n, m) = (a.len(), b.len());
+    // lcs[i][j] is the common subsequence length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i].word == b[j].word {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut runs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut run_i, mut run_j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i].word == b[j].word {
+            if run_i < i || run_j < j {
+                runs.push((run_i..i, run_j..j));
+            }
+            i += 1;
+            j += 1;
+            (run_i, run_j) = (i, j);
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if run_i < n || run_j < m {
+        runs.push((run_i..n, run_j..m));
+    }
+    (runs, lcs[0][0] as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn correction(heard: &str, corrected: &str) -> Correction {
+        Correction {
+            heard: heard.to_string(),
+            corrected: corrected.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_single_word_correction() {
+        let result = extract_corrections(
+            "We store it in post grass today.",
+            "We store it in Postgres today.",
+        );
+        assert_eq!(result, vec![correction("post grass", "Postgres")]);
+    }
+
+    #[test]
+    fn test_several_corrections() {
+        let result = extract_corrections(
+            "Deploy with cube control, then check get hub.",
+            "Deploy with kubectl, then check GitHub.",
+        );
+        assert_eq!(
+            result,
+            vec![
+                correction("cube control", "kubectl"),
+                correction("get hub", "GitHub")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_punctuation_and_sentence_case() {
+        assert!(
+            extract_corrections("hello world how are you", "Hello world. How are you?").is_empty()
+        );
+        assert!(extract_corrections("I use GitHub", "I use github").is_empty());
+        assert_eq!(
+            extract_corrections("I use kubernetes daily", "I use Kubernetes daily"),
+            vec![correction("kubernetes", "Kubernetes")]
+        );
+    }
+
+    #[test]
+    fn test_ignores_insertions_deletions_and_rewrites() {
+        assert!(extract_corrections("send the report", "send the final report").is_empty());
+        assert!(extract_corrections("send um the report", "send the report").is_empty());
+        assert!(extract_corrections(
+            "please could you send the report",
+            "I would really like you to email me that document",
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_identical_or_empty_texts() {
+        assert!(extract_corrections("same text", "same text").is_empty());
+        assert!(extract_corrections("", "").is_empty());
+        assert!(extract_corrections("", "new text").is_empty());
+    }
+
+    #[test]
+    fn test_suggest_kind() {
+        assert_eq!(
+            suggest_kind("post grass", "Postgres", &[], 0.18, "en"),
+            CorrectionKind::CustomWord
+        );
+        assert_eq!(
+            suggest_kind("my mail", "jane@example.com", &[], 0.18, "en"),
+            CorrectionKind::Replacement
+        );
+        // The word list already has it, so fuzzy matching isn't enough
+        let words = vec!["Postgres".to_string()];
+        assert_eq!(
+            suggest_kind("post grass", "Postgres", &words, 0.18, "en"),
+            CorrectionKind::Replacement
+        );
+    }
+}
@@ -1,12 +1,20 @@
 pub mod audio;
 pub mod constants;
+pub mod corrections;
+pub mod replacements;
+pub mod subtitles;
 pub mod text;
 pub mod utils;
 pub mod vad;
+pub mod voice_commands;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    decode_audio_bytes, decode_pcm, encode_wav, list_input_devices, list_output_devices,
+    read_audio_file, save_wav_file, AudioRecorder, CpalDeviceInfo, PcmFormat, RecorderEvent,
+    UtteranceConfig,
 };
-pub use text::apply_custom_words;
+pub use replacements::{apply_replacements, ReplacementKind, ReplacementRule};
+pub use text::{apply_custom_words, convert_chinese_variant, inverse_normalize};
 pub use utils::get_cpal_host;
 pub use vad::{SileroVad, VoiceActivityDetector};
+pub use voice_commands::apply_voice_commands;
@@ -0,0 +1,330 @@
+use log::warn;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+// Replacements made only of these characters attach to the neighbouring word
+// instead of keeping the spaces the transcript had around the spoken phrase.
+const OPENING_PUNCTUATION: &str = "([{¿¡";
+const CLOSING_PUNCTUATION: &str = ")]}.,;:!?";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplacementKind {
+    /// Matches a word or phrase literally. The words of a phrase may be
+    /// separated by any whitespace.
+    Exact,
+    /// Matches a regular expression. The replacement can refer to capture
+    /// groups as `$1` or `${name}`.
+    Regex,
+}
+
+/// A rewrite applied to transcriptions. Rules run in list order, each on the
+/// output of the ones before it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct ReplacementRule {
+    pub id: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub kind: ReplacementKind,
+    pub pattern: String,
+    /// The inserted text, which may span several lines (for snippets)
+    pub replacement: String,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Compiled rules, ready to be applied to any number of transcriptions
+pub struct Replacer {
+    rules: Vec<CompiledRule>,
+}
+
+struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+    /// Exact rules insert the replacement as is, without expanding `$` groups
+    literal: bool,
+}
+
+impl Replacer {
+    /// Compiles the enabled rules, failing on the first invalid one
+    pub fn new(rules: &[ReplacementRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(compile)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        self.rules.iter().fold(text.to_string(), |text, rule| {
+            if rule.literal {
+                rule.regex
+                    .replace_all(&text, NoExpand(&rule.replacement))
+                    .into_owned()
+            } else {
+                rule.regex
+                    .replace_all(&text, rule.replacement.as_str())
+                    .into_owned()
+            }
+        })
+    }
+}
+
+/// Applies the enabled rules to `text` in order. Invalid rules are skipped
+/// with a warning so one bad rule doesn't disable the rest.
+///
+/// # Arguments
+/// * `text` - The transcribed text to rewrite
+/// * `rules` - The rules to apply, in order
+///
+/// # Returns
+/// The text with every matching rule applied
+pub fn apply_replacements(text: &str, rules: &[ReplacementRule]) -> String {
+    let valid: Vec<ReplacementRule> = rules
+        .iter()
+        .filter(|rule| match validate_rule(rule) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Skipping replacement rule '{}': {}", rule.id, e);
+                false
+            }
+        })
+        .cloned()
+        .collect();
+
+    match Replacer::new(&valid) {
+        Ok(replacer) => replacer.apply(text),
+        Err(_) => text.to_string(),
+    }
+}
+
+/// Checks that a rule has a pattern that compiles
+pub fn validate_rule(rule: &ReplacementRule) -> Result<(), String> {
+    compile(rule).map(|_| ())
+}
+
+/// Parses rules exported with [`export_rules`], rejecting the whole file if
+/// any rule is invalid or an id is repeated
+pub fn import_rules(json: &str) -> Result<Vec<ReplacementRule>, String> {
+    let rules: Vec<ReplacementRule> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid replacement rules: {}", e))?;
+    validate_rules(&rules)?;
+    Ok(rules)
+}
+
+pub fn export_rules(rules: &[ReplacementRule]) -> String {
+    serde_json::to_string_pretty(rules).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Checks every rule and that their ids are present and unique
+pub fn validate_rules(rules: &[ReplacementRule]) -> Result<(), String> {
+    for (i, rule) in rules.iter().enumerate() {
+        if rule.id.trim().is_empty() {
+            return Err(format!("Replacement rule {} has no id", i + 1));
+        }
+        if rules[..i].iter().any(|other| other.id == rule.id) {
+            return Err(format!("Duplicate replacement rule id '{}'", rule.id));
+        }
+        validate_rule(rule).map_err(|e| format!("Replacement rule '{}': {}", rule.id, e))?;
+    }
+    Ok(())
+}
+
+fn compile(rule: &ReplacementRule) -> Result<CompiledRule, String> {
+    let pattern = match rule.kind {
+        ReplacementKind::Exact => exact_pattern(&rule.pattern, &rule.replacement)
+            .ok_or_else(|| "Pattern cannot be empty".to_string())?,
+        ReplacementKind::Regex => {
+            if rule.pattern.is_empty() {
+                return Err("Pattern cannot be empty".to_string());
+            }
+            rule.pattern.clone()
+        }
+    };
+
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!rule.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))?;
+
+    Ok(CompiledRule {
+        regex,
+        replacement: rule.replacement.clone(),
+        literal: rule.kind == ReplacementKind::Exact,
+    })
+}
+
+/// Builds the regex for an exact word or phrase, including the spacing to
+/// absorb around it
+fn exact_pattern(phrase: &str, replacement: &str) -> Option<String> {
+    let words: Vec<String> = phrase.split_whitespace().map(regex::escape).collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut pattern = words.join(r"\s+");
+    // Word boundaries only make sense next to word characters
+    if phrase.trim_start().starts_with(is_word_char) {
+        pattern = format!(r"\b{}", pattern);
+    }
+    if phrase.trim_end().ends_with(is_word_char) {
+        pattern.push_str(r"\b");
+    }
+
+    let pattern = if is_all(replacement, char::is_whitespace) {
+        // Spoken line breaks take the spaces around them along, and the
+        // comma or period transcription often adds after them
+        format!(r"[ \t]*{}[,.]?[ \t]*", pattern)
+    } else if is_all(replacement, |c| OPENING_PUNCTUATION.contains(c)) {
+        format!(r"{}[ \t]*", pattern)
+    } else if is_all(replacement, |c| CLOSING_PUNCTUATION.contains(c)) {
+        format!(r"[ \t]*{}", pattern)
+    } else {
+        pattern
+    };
+    Some(pattern)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_all(text: &str, predicate: impl Fn(char) -> bool) -> bool {
+    !text.is_empty() && text.chars().all(predicate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: ReplacementKind, pattern: &str, replacement: &str) -> ReplacementRule {
+        ReplacementRule {
+            id: pattern.to_string(),
+            enabled: true,
+            kind,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            case_sensitive: false,
+        }
+    }
+
+    fn exact(pattern: &str, replacement: &str) -> ReplacementRule {
+        rule(ReplacementKind::Exact, pattern, replacement)
+    }
+
+    #[test]
+    fn test_exact_word_replacement() {
+        let rules = vec![exact("gonna", "going to")];
+        let result = apply_replacements("I'm gonna go, Gonna!", &rules);
+        assert_eq!(result, "I'm going to go, going to!");
+    }
+
+    #[test]
+    fn test_exact_matches_whole_words_only() {
+        let rules = vec![exact("cat", "dog")];
+        let result = apply_replacements("cat category concat", &rules);
+        assert_eq!(result, "dog category concat");
+    }
+
+    #[test]
+    fn test_case_sensitive_rule() {
+        let mut rule = exact("Rust", "Rust™");
+        rule.case_sensitive = true;
+        let result = apply_replacements("Rust is not rust", &[rule]);
+        assert_eq!(result, "Rust™ is not rust");
+    }
+
+    #[test]
+    fn test_multi_word_phrase_with_any_whitespace() {
+        let rules = vec![exact("thank you", "thanks")];
+        let result = apply_replacements("well thank   you all", &rules);
+        assert_eq!(result, "well thanks all");
+    }
+
+    #[test]
+    fn test_new_line_absorbs_spaces_and_punctuation() {
+        let rules = vec![exact("new line", "\n")];
+        let result = apply_replacements("Dear Sam, new line. Thanks for writing", &rules);
+        assert_eq!(result, "Dear Sam,\nThanks for writing");
+    }
+
+    #[test]
+    fn test_parentheses_attach_to_neighbouring_words() {
+        let rules = vec![exact("open paren", "("), exact("close paren", ")")];
+        let result = apply_replacements("call open paren x close paren now", &rules);
+        assert_eq!(result, "call (x) now");
+    }
+
+    #[test]
+    fn test_snippet_expansion() {
+        let rules = vec![exact("my address", "1 Main Street\nSpringfield")];
+        let result = apply_replacements("Send it to my address.", &rules);
+        assert_eq!(result, "Send it to 1 Main Street\nSpringfield.");
+    }
+
+    #[test]
+    fn test_regex_with_capture_groups() {
+        let rules = vec![rule(ReplacementKind::Regex, r"(\d+) percent", "$1%")];
+        let result = apply_replacements("about 50 percent done", &rules);
+        assert_eq!(result, "about 50% done");
+    }
+
+    #[test]
+    fn test_exact_replacement_is_literal() {
+        let rules = vec![exact("price", "$1")];
+        let result = apply_replacements("the price", &rules);
+        assert_eq!(result, "the $1");
+    }
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let rules = vec![exact("colour", "color"), exact("color", "hue")];
+        assert_eq!(apply_replacements("colour", &rules), "hue");
+
+        let reversed = vec![exact("color", "hue"), exact("colour", "color")];
+        assert_eq!(apply_replacements("colour", &reversed), "color");
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_rules_are_skipped() {
+        let mut disabled = exact("hello", "bye");
+        disabled.enabled = false;
+        let invalid = rule(ReplacementKind::Regex, "(unclosed", "x");
+        let rules = vec![disabled, invalid, exact("world", "earth")];
+        assert_eq!(apply_replacements("hello world", &rules), "hello earth");
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        assert!(validate_rule(&exact("  ", "x")).is_err());
+        assert!(validate_rule(&rule(ReplacementKind::Regex, "(", "x")).is_err());
+        assert!(validate_rules(&[exact("a", "b"), exact("a", "c")]).is_err());
+        assert!(validate_rules(&[exact("a", "b"), exact("c", "d")]).is_ok());
+    }
+
+    #[test]
+    fn test_import_export_round_trip() {
+        let rules = vec![
+            exact("new line", "\n"),
+            rule(ReplacementKind::Regex, "a+", "b"),
+        ];
+        let imported = import_rules(&export_rules(&rules)).unwrap();
+        assert_eq!(imported, rules);
+        assert!(import_rules("not json").is_err());
+    }
+
+    #[test]
+    fn test_import_defaults() {
+        let json =
+            r#"[{"id": "r1", "kind": "exact", "pattern": "btw", "replacement": "by the way"}]"#;
+        let imported = import_rules(json).unwrap();
+        assert!(imported[0].enabled);
+        assert!(!imported[0].case_sensitive);
+    }
+}
@@ -0,0 +1,94 @@
+/// A single timed line of transcribed text, with times in seconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// Formats cues as a SubRip (.srt) document
+pub fn to_srt(cues: &[SubtitleCue]) -> String {
+    let mut out = String::new();
+    for (index, cue) in cues.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(cue.start, ','),
+            format_timestamp(cue.end, ','),
+            cue.text.trim()
+        ));
+    }
+    out
+}
+
+/// Formats cues as a WebVTT (.vtt) document
+pub fn to_vtt(cues: &[SubtitleCue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(cue.start, '.'),
+            format_timestamp(cue.end, '.'),
+            cue.text.trim()
+        ));
+    }
+    out
+}
+
+/// Formats seconds as `HH:MM:SS<sep>mmm`
+fn format_timestamp(seconds: f32, millis_separator: char) -> String {
+    let total_ms = (seconds.max(0.0) as f64 * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms % 3_600_000) / 60_000;
+    let secs = (total_ms % 60_000) / 1000;
+    let millis = total_ms % 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, secs, millis_separator, millis
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0.0, ','), "00:00:00,000");
+        assert_eq!(format_timestamp(3661.5, ','), "01:01:01,500");
+        assert_eq!(format_timestamp(-1.0, ','), "00:00:00,000");
+    }
+
+    #[test]
+    fn test_to_srt() {
+        let cues = vec![
+            SubtitleCue {
+                start: 0.0,
+                end: 1.25,
+                text: " Hello there.".to_string(),
+            },
+            SubtitleCue {
+                start: 1.25,
+                end: 2.0,
+                text: "General Kenobi.".to_string(),
+            },
+        ];
+        assert_eq!(
+            to_srt(&cues),
+            "1\n00:00:00,000 --> 00:00:01,250\nHello there.\n\n2\n00:00:01,250 --> 00:00:02,000\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_to_vtt() {
+        let cues = vec![SubtitleCue {
+            start: 61.0,
+            end: 62.5,
+            text: "Hello there.".to_string(),
+        }];
+        assert_eq!(
+            to_vtt(&cues),
+            "WEBVTT\n\n00:01:01.000 --> 00:01:02.500\nHello there.\n\n"
+        );
+    }
+}
@@ -1,101 +1,259 @@
-use natural::phonetics::soundex;
-use strsim::levenshtein;
+use ferrous_opencc::{config::BuiltinConfig, OpenCC};
+use log::{debug, error};
+use strsim::generic_levenshtein;
+use unicode_segmentation::UnicodeSegmentation;
+
+mod itn;
+pub mod phonetics;
+
+pub use itn::inverse_normalize;
+use phonetics::PhoneticMatcher;
+
+/// Separates the spoken form of a custom word from how it is written, as in
+/// "cube control -> kubectl"
+const SPOKEN_FORM_SEPARATOR: &str = "->";
+
+/// A custom word or phrase, prepared for matching
+struct CustomWord<'a> {
+    written: &'a str,
+    /// The spoken form, lowercase and without spaces
+    spoken: String,
+    graphemes: usize,
+    /// Number of words in the spoken form
+    words: usize,
+}
+
+/// A word of the text, or a single grapheme of a script written without
+/// spaces between words, such as Chinese, Japanese or Thai. Custom words are
+/// matched against runs of tokens.
+struct Token<'a> {
+    text: &'a str,
+    /// Whether the token follows whitespace in the original text
+    spaced: bool,
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        if !word.chars().any(is_unspaced_script) {
+            tokens.push(Token {
+                text: word,
+                spaced: true,
+            });
+            continue;
+        }
+
+        // Other characters in the word, like Latin letters or punctuation,
+        // stay together between the graphemes
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        for (index, grapheme) in word.grapheme_indices(true) {
+            if grapheme.chars().any(is_unspaced_script) {
+                pieces.push(&word[start..index]);
+                pieces.push(grapheme);
+                start = index + grapheme.len();
+            }
+        }
+        pieces.push(&word[start..]);
+
+        let first = tokens.len();
+        tokens.extend(
+            pieces
+                .into_iter()
+                .filter(|piece| !piece.is_empty())
+                .map(|text| Token {
+                    text,
+                    spaced: false,
+                }),
+        );
+        tokens[first].spaced = true;
+    }
+    tokens
+}
+
+/// Characters of scripts that do not separate words with spaces
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}' // Thai, Lao
+        | '\u{1000}'..='\u{109F}' // Myanmar
+        | '\u{1780}'..='\u{17FF}' // Khmer
+        | '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2FA1F}' // CJK Extensions B and later
+    )
+}
+
+/// Splits a custom words entry into how it is said and how it is written.
+/// A plain entry is both; "cube control -> kubectl" is said "cube control"
+/// and written "kubectl".
+pub fn parse_custom_word(entry: &str) -> (&str, &str) {
+    match entry.split_once(SPOKEN_FORM_SEPARATOR) {
+        Some((spoken, written)) if !spoken.trim().is_empty() && !written.trim().is_empty() => {
+            (spoken.trim(), written.trim())
+        }
+        _ => (entry.trim(), entry.trim()),
+    }
+}
 
 /// Applies custom word corrections to transcribed text using fuzzy matching
 ///
-/// This function corrects words in the input text by finding the best matches
-/// from a list of custom words using a combination of:
-/// - Levenshtein distance for string similarity
-/// - Soundex phonetic matching for pronunciation similarity
+/// This function corrects words and phrases in the input text by finding the
+/// best matches from a list of custom words using a combination of:
+/// - Levenshtein distance over graphemes for string similarity
+/// - A per-language phonetic matcher for pronunciation similarity
+///
+/// Custom words may be phrases, and a phrase or word may match a run of
+/// adjacent words in the text, so "java script" can become "JavaScript". In
+/// scripts written without spaces every grapheme counts as a word, so
+/// "北京大学" can match inside "我在北京大字读书". An entry written as
+/// "spoken -> written" matches the spoken form and inserts the written one.
 ///
 /// # Arguments
 /// * `text` - The input text to correct
 /// * `custom_words` - List of custom words to match against
 /// * `threshold` - Maximum similarity score to accept (0.0 = exact match, 1.0 = any match)
+/// * `language` - The transcription language, which picks the phonetic matcher
 ///
 /// # Returns
 /// The corrected text with custom words applied
-pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -> String {
+pub fn apply_custom_words(
+    text: &str,
+    custom_words: &[String],
+    threshold: f64,
+    language: &str,
+) -> String {
     if custom_words.is_empty() {
         return text.to_string();
     }
 
-    // Pre-compute lowercase versions to avoid repeated allocations
-    let custom_words_lower: Vec<String> = custom_words.iter().map(|w| w.to_lowercase()).collect();
+    let phonetics = phonetics::for_language(language);
+    let custom_words: Vec<CustomWord> = custom_words
+        .iter()
+        .filter_map(|entry| {
+            let (spoken, written) = parse_custom_word(entry);
+            let words = tokenize(spoken).len();
+            let spoken: String = spoken.split_whitespace().collect::<String>().to_lowercase();
+            (!spoken.is_empty()).then(|| CustomWord {
+                written,
+                graphemes: spoken.graphemes(true).count(),
+                spoken,
+                words,
+            })
+        })
+        .collect();
+    // A word may be split in two by the engine, so look one word further
+    let max_span = custom_words.iter().map(|w| w.words + 1).max().unwrap_or(1);
+
+    let tokens = tokenize(text);
+    let words: Vec<&str> = tokens.iter().map(|token| token.text).collect();
+    let mut corrected = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < words.len() {
+        if tokens[i].spaced && !corrected.is_empty() {
+            corrected.push(' ');
+        }
+        match best_match(&words[i..], &custom_words, max_span, threshold, phonetics) {
+            Some((span, replacement)) => {
+                let (prefix, _) = extract_punctuation(words[i]);
+                let (_, suffix) = extract_punctuation(words[i + span - 1]);
+                // Preserve the original case pattern as much as possible
+                let first = words[i].trim_matches(|c: char| !c.is_alphabetic());
+                corrected.push_str(prefix);
+                corrected.push_str(&preserve_case_pattern(first, replacement));
+                corrected.push_str(suffix);
+                i += span;
+            }
+            None => {
+                corrected.push_str(words[i]);
+                i += 1;
+            }
+        }
+    }
+
+    corrected
+}
+
+/// Finds the custom word that best matches the words at the start of
+/// `words`, returning how many words it replaces
+fn best_match<'a>(
+    words: &[&str],
+    custom_words: &[CustomWord<'a>],
+    max_span: usize,
+    threshold: f64,
+    phonetics: &dyn PhoneticMatcher,
+) -> Option<(usize, &'a str)> {
+    let mut best: Option<(usize, &'a str)> = None;
+    let mut best_score = f64::MAX;
+    let mut candidate = String::new();
 
-    let words: Vec<&str> = text.split_whitespace().collect();
-    let mut corrected_words = Vec::new();
+    for span in 1..=max_span.min(words.len()) {
+        let word = words[span - 1];
+        // Phrases don't continue across punctuation
+        if span > 1
+            && (!extract_punctuation(words[span - 2]).1.is_empty()
+                || !extract_punctuation(word).0.is_empty())
+        {
+            break;
+        }
 
-    for word in words {
         let cleaned_word = word
             .trim_matches(|c: char| !c.is_alphabetic())
             .to_lowercase();
-
         if cleaned_word.is_empty() {
-            corrected_words.push(word.to_string());
-            continue;
+            break;
         }
+        candidate.push_str(&cleaned_word);
 
-        // Skip extremely long words to avoid performance issues
-        if cleaned_word.len() > 50 {
-            corrected_words.push(word.to_string());
-            continue;
+        let candidate_graphemes: Vec<&str> = candidate.graphemes(true).collect();
+        // Skip extremely long candidates to avoid performance issues
+        if candidate_graphemes.len() > 50 {
+            break;
         }
 
-        let mut best_match: Option<&String> = None;
-        let mut best_score = f64::MAX;
+        for custom_word in custom_words {
+            let word_gap = span.abs_diff(custom_word.words);
+            if word_gap > 1 {
+                continue;
+            }
 
-        for (i, custom_word_lower) in custom_words_lower.iter().enumerate() {
             // Skip if lengths are too different (optimization)
-            let len_diff = (cleaned_word.len() as i32 - custom_word_lower.len() as i32).abs();
-            if len_diff > 5 {
+            if candidate_graphemes.len().abs_diff(custom_word.graphemes) > 5 {
                 continue;
             }
 
-            // Calculate Levenshtein distance (normalized by length)
-            let levenshtein_dist = levenshtein(&cleaned_word, custom_word_lower);
-            let max_len = cleaned_word.len().max(custom_word_lower.len()) as f64;
-            let levenshtein_score = if max_len > 0.0 {
-                levenshtein_dist as f64 / max_len
-            } else {
-                1.0
-            };
-
-            // Calculate phonetic similarity using Soundex
-            let phonetic_match = soundex(&cleaned_word, custom_word_lower);
+            // Calculate Levenshtein distance over graphemes (normalized by length)
+            let custom_graphemes: Vec<&str> = custom_word.spoken.graphemes(true).collect();
+            let levenshtein_dist = generic_levenshtein(&candidate_graphemes, &custom_graphemes);
+            let max_len = candidate_graphemes.len().max(custom_graphemes.len()) as f64;
+            let levenshtein_score = levenshtein_dist as f64 / max_len;
 
             // Combine scores: favor phonetic matches, but also consider string similarity
-            let combined_score = if phonetic_match {
+            let mut combined_score = if phonetics.sounds_alike(&candidate, &custom_word.spoken) {
                 levenshtein_score * 0.3 // Give significant boost to phonetic matches
             } else {
                 levenshtein_score
             };
+            // Matching a different number of words than were spoken is less likely
+            combined_score *= 1.0 + 0.5 * word_gap as f64;
 
             // Accept if the score is good enough (configurable threshold)
             if combined_score < threshold && combined_score < best_score {
-                best_match = Some(&custom_words[i]);
+                best = Some((span, custom_word.written));
                 best_score = combined_score;
             }
         }
-
-        if let Some(replacement) = best_match {
-            // Preserve the original case pattern as much as possible
-            let corrected = preserve_case_pattern(word, replacement);
-
-            // Preserve punctuation from original word
-            let (prefix, suffix) = extract_punctuation(word);
-            corrected_words.push(format!("{}{}{}", prefix, corrected, suffix));
-        } else {
-            corrected_words.push(word.to_string());
-        }
     }
 
-    corrected_words.join(" ")
+    best
 }
 
 /// Preserves the case pattern of the original word when applying a replacement
 fn preserve_case_pattern(original: &str, replacement: &str) -> String {
-    if original.chars().all(|c| c.is_uppercase()) {
+    // A single capital, like "I", says nothing about the rest of the word
+    if original.chars().count() > 1 && original.chars().all(|c| c.is_uppercase()) {
         replacement.to_uppercase()
     } else if original.chars().next().map_or(false, |c| c.is_uppercase()) {
         let mut chars: Vec<char> = replacement.chars().collect();
@@ -110,26 +268,67 @@ fn preserve_case_pattern(original: &str, replacement: &str) -> String {
 
 /// Extracts punctuation prefix and suffix from a word
 fn extract_punctuation(word: &str) -> (&str, &str) {
-    let prefix_end = word.chars().take_while(|c| !c.is_alphabetic()).count();
+    let prefix_end = word
+        .char_indices()
+        .find(|(_, c)| c.is_alphabetic())
+        .map_or(word.len(), |(i, _)| i);
     let suffix_start = word
         .char_indices()
         .rev()
-        .take_while(|(_, c)| !c.is_alphabetic())
-        .count();
+        .find(|(_, c)| c.is_alphabetic())
+        .map_or(word.len(), |(i, c)| i + c.len_utf8())
+        .max(prefix_end);
 
-    let prefix = if prefix_end > 0 {
-        &word[..prefix_end]
-    } else {
-        ""
-    };
+    (&word[..prefix_end], &word[suffix_start..])
+}
+
+/// Converts between Simplified and Traditional Chinese using OpenCC
+///
+/// # Arguments
+/// * `text` - The transcribed text to convert
+/// * `language` - The selected language code; only `zh-Hans` and `zh-Hant` trigger a conversion
+///
+/// # Returns
+/// The converted text, or `None` if no conversion applies or OpenCC failed to initialize
+pub fn convert_chinese_variant(text: &str, language: &str) -> Option<String> {
+    // Check if language is set to Simplified or Traditional Chinese
+    let is_simplified = language == "zh-Hans";
+    let is_traditional = language == "zh-Hant";
+
+    if !is_simplified && !is_traditional {
+        debug!("selected_language is not Simplified or Traditional Chinese; skipping translation");
+        return None;
+    }
+
+    debug!(
+        "Starting Chinese translation using OpenCC for language: {}",
+        language
+    );
 
-    let suffix = if suffix_start > 0 {
-        &word[word.len() - suffix_start..]
+    // Use OpenCC to convert based on selected language
+    let config = if is_simplified {
+        // Convert Traditional Chinese to Simplified Chinese
+        BuiltinConfig::Tw2sp
     } else {
-        ""
+        // Convert Simplified Chinese to Traditional Chinese
+        BuiltinConfig::S2twp
     };
 
-    (prefix, suffix)
+    match OpenCC::from_config(config) {
+        Ok(converter) => {
+            let converted = converter.convert(text);
+            debug!(
+                "OpenCC translation completed. Input length: {}, Output length: {}",
+                text.len(),
+                converted.len()
+            );
+            Some(converted)
+        }
+        Err(e) => {
+            error!("Failed to initialize OpenCC converter: {}. Falling back to original transcription.", e);
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -140,7 +339,7 @@ mod tests {
     fn test_apply_custom_words_exact_match() {
         let text = "hello world";
         let custom_words = vec!["Hello".to_string(), "World".to_string()];
-        let result = apply_custom_words(text, &custom_words, 0.5);
+        let result = apply_custom_words(text, &custom_words, 0.5, "en");
         assert_eq!(result, "Hello World");
     }
 
@@ -148,10 +347,119 @@ mod tests {
     fn test_apply_custom_words_fuzzy_match() {
         let text = "helo wrold";
         let custom_words = vec!["hello".to_string(), "world".to_string()];
-        let result = apply_custom_words(text, &custom_words, 0.5);
+        let result = apply_custom_words(text, &custom_words, 0.5, "en");
         assert_eq!(result, "hello world");
     }
 
+    #[test]
+    fn test_apply_custom_words_multi_word_phrase() {
+        let text = "open visual studio coad now";
+        let custom_words = vec!["Visual Studio Code".to_string()];
+        let result = apply_custom_words(text, &custom_words, 0.18, "en");
+        assert_eq!(result, "open Visual Studio Code now");
+    }
+
+    #[test]
+    fn test_apply_custom_words_joins_split_word() {
+        let text = "I like java script.";
+        let custom_words = vec!["JavaScript".to_string()];
+        let result = apply_custom_words(text, &custom_words, 0.18, "en");
+        assert_eq!(result, "I like JavaScript.");
+    }
+
+    #[test]
+    fn test_apply_custom_words_spoken_form() {
+        let text = "run cube control get pods";
+        let custom_words = vec!["cube control -> kubectl".to_string()];
+        let result = apply_custom_words(text, &custom_words, 0.18, "en");
+        assert_eq!(result, "run kubectl get pods");
+        assert_eq!(
+            parse_custom_word("cube control -> kubectl"),
+            ("cube control", "kubectl")
+        );
+        assert_eq!(parse_custom_word("kubectl"), ("kubectl", "kubectl"));
+    }
+
+    #[test]
+    fn test_apply_custom_words_does_not_cross_punctuation() {
+        let text = "java, script";
+        let custom_words = vec!["JavaScript".to_string()];
+        let result = apply_custom_words(text, &custom_words, 0.18, "en");
+        assert_eq!(result, "java, script");
+    }
+
+    #[test]
+    fn test_apply_custom_words_counts_graphemes() {
+        // One of four characters differs; by bytes it would look like one of twelve
+        let custom_words = vec!["北京大学".to_string()];
+        let text = "北京大字";
+        assert_eq!(
+            apply_custom_words(text, &custom_words, 0.3, "zh"),
+            "北京大学"
+        );
+        assert_eq!(
+            apply_custom_words(text, &custom_words, 0.2, "zh"),
+            "北京大字"
+        );
+
+        let text = "¿hablaste con jose?";
+        let custom_words = vec!["José".to_string()];
+        let result = apply_custom_words(text, &custom_words, 0.3, "es");
+        assert_eq!(result, "¿hablaste con José?");
+    }
+
+    #[test]
+    fn test_apply_custom_words_without_spaces() {
+        let custom_words = vec!["北京大学".to_string(), "Python".to_string()];
+        assert_eq!(
+            apply_custom_words("我在北京大字读书。", &custom_words, 0.3, "zh"),
+            "我在北京大学读书。"
+        );
+        assert_eq!(
+            apply_custom_words("我用Pyton写代码", &custom_words, 0.3, "zh"),
+            "我用Python写代码"
+        );
+        // Spaces around the run are kept as they were
+        assert_eq!(
+            apply_custom_words("hello 北京大字 world", &custom_words, 0.3, "zh"),
+            "hello 北京大学 world"
+        );
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens: Vec<_> = tokenize("say 用Python写， ok")
+            .into_iter()
+            .map(|token| (token.text, token.spaced))
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                ("say", true),
+                ("用", true),
+                ("Python", false),
+                ("写", false),
+                ("，", false),
+                ("ok", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_phonetic_matchers() {
+        let english = phonetics::for_language("en-US");
+        assert!(english.sounds_alike("kat", "cat"));
+        assert!(english.sounds_alike("fone", "phone"));
+        assert!(english.sounds_alike("nite", "knight"));
+        assert!(!english.sounds_alike("cat", "dog"));
+
+        let german = phonetics::for_language("de");
+        assert!(german.sounds_alike("müller", "mueller"));
+
+        let japanese = phonetics::for_language("ja");
+        assert!(!japanese.sounds_alike("とうきょう", "とうきょう"));
+    }
+
     #[test]
     fn test_preserve_case_pattern() {
         assert_eq!(preserve_case_pattern("HELLO", "world"), "WORLD");
@@ -164,13 +472,15 @@ mod tests {
         assert_eq!(extract_punctuation("hello"), ("", ""));
         assert_eq!(extract_punctuation("!hello?"), ("!", "?"));
         assert_eq!(extract_punctuation("...hello..."), ("...", "..."));
+        assert_eq!(extract_punctuation("¿qué?"), ("¿", "?"));
+        assert_eq!(extract_punctuation("«…»"), ("«…»", ""));
     }
 
     #[test]
     fn test_empty_custom_words() {
         let text = "hello world";
         let custom_words = vec![];
-        let result = apply_custom_words(text, &custom_words, 0.5);
+        let result = apply_custom_words(text, &custom_words, 0.5, "en");
         assert_eq!(result, "hello world");
     }
 }
@@ -0,0 +1,1487 @@
+//! Inverse text normalization: rewrites spoken forms such as "twenty five
+//! dollars" or "march third" into written ones ("$25", "March 3").
+//!
+//! Small standalone numbers ("one question") and ordinals ("the first time")
+//! are left as words, as in ordinary prose. Anything with a unit, currency,
+//! percent sign or date around it is converted regardless of size.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Term {
+    /// 0-9
+    Unit(u64),
+    /// A complete two-digit number that takes no unit after it: 10-19, and
+    /// single-word forms such as "veinticinco"
+    Pair(u64),
+    /// 20, 30, ..., 90
+    Tens(u64),
+    /// Multiplies the preceding number by 100
+    Hundred,
+    /// A word worth a whole number of hundreds, such as "doscientos"
+    Hundreds(u64),
+    Scale(u64),
+    /// "and" / "y" joining parts of one number
+    Conjunction,
+    /// A German compound such as "zweihundertfünfundzwanzig"
+    Compound(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    English,
+    German,
+    Spanish,
+}
+
+struct Currency {
+    words: &'static [&'static str],
+    symbol: &'static str,
+    subunits: &'static [&'static str],
+}
+
+struct Rules {
+    language: Language,
+    decimal_word: &'static str,
+    decimal_separator: char,
+    group_separator: char,
+    conjunction: &'static str,
+    /// Display names, January first
+    months: [&'static str; 12],
+    currencies: &'static [Currency],
+    /// Joins the main unit and the subunit amount
+    currency_conjunctions: &'static [&'static str],
+    percent: &'static [&'static str],
+    percent_sign: &'static str,
+    /// Unit phrases and their symbols, longest phrase first
+    units: &'static [(&'static str, &'static str)],
+}
+
+const ENGLISH: Rules = Rules {
+    language: Language::English,
+    decimal_word: "point",
+    decimal_separator: '.',
+    group_separator: ',',
+    conjunction: "and",
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    currencies: &[
+        Currency {
+            words: &["dollar", "dollars"],
+            symbol: "$",
+            subunits: &["cent", "cents"],
+        },
+        Currency {
+            words: &["euro", "euros"],
+            symbol: "€",
+            subunits: &["cent", "cents"],
+        },
+    ],
+    currency_conjunctions: &["and"],
+    percent: &["percent", "per cent"],
+    percent_sign: "%",
+    units: &[
+        ("kilometers per hour", "km/h"),
+        ("kilometres per hour", "km/h"),
+        ("miles per hour", "mph"),
+        ("degrees celsius", "°C"),
+        ("degrees fahrenheit", "°F"),
+        ("degree celsius", "°C"),
+        ("degree fahrenheit", "°F"),
+        ("kilometers", "km"),
+        ("kilometer", "km"),
+        ("kilometres", "km"),
+        ("kilometre", "km"),
+        ("meters", "m"),
+        ("meter", "m"),
+        ("metres", "m"),
+        ("metre", "m"),
+        ("centimeters", "cm"),
+        ("centimeter", "cm"),
+        ("centimetres", "cm"),
+        ("centimetre", "cm"),
+        ("millimeters", "mm"),
+        ("millimeter", "mm"),
+        ("millimetres", "mm"),
+        ("millimetre", "mm"),
+        ("kilograms", "kg"),
+        ("kilogram", "kg"),
+        ("kilos", "kg"),
+        ("kilo", "kg"),
+        ("grams", "g"),
+        ("gram", "g"),
+        ("liters", "L"),
+        ("liter", "L"),
+        ("litres", "L"),
+        ("litre", "L"),
+        ("milliliters", "mL"),
+        ("milliliter", "mL"),
+        ("millilitres", "mL"),
+        ("millilitre", "mL"),
+        ("feet", "ft"),
+        ("foot", "ft"),
+        ("terabytes", "TB"),
+        ("terabyte", "TB"),
+        ("gigabytes", "GB"),
+        ("gigabyte", "GB"),
+        ("megabytes", "MB"),
+        ("megabyte", "MB"),
+        ("kilobytes", "KB"),
+        ("kilobyte", "KB"),
+        ("degrees", "°"),
+        ("degree", "°"),
+    ],
+};
+
+const GERMAN: Rules = Rules {
+    language: Language::German,
+    decimal_word: "komma",
+    decimal_separator: ',',
+    group_separator: '.',
+    conjunction: "",
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    currencies: &[
+        Currency {
+            words: &["euro"],
+            symbol: "€",
+            subunits: &["cent"],
+        },
+        Currency {
+            words: &["dollar"],
+            symbol: "$",
+            subunits: &["cent"],
+        },
+    ],
+    currency_conjunctions: &["und"],
+    percent: &["prozent"],
+    percent_sign: " %",
+    units: &[
+        ("kilometer pro stunde", "km/h"),
+        ("grad celsius", "°C"),
+        ("kilometer", "km"),
+        ("zentimeter", "cm"),
+        ("millimeter", "mm"),
+        ("meter", "m"),
+        ("kilogramm", "kg"),
+        ("kilo", "kg"),
+        ("gramm", "g"),
+        ("milliliter", "ml"),
+        ("liter", "l"),
+        ("terabyte", "TB"),
+        ("gigabyte", "GB"),
+        ("megabyte", "MB"),
+        ("kilobyte", "KB"),
+        ("grad", "°"),
+    ],
+};
+
+const SPANISH: Rules = Rules {
+    language: Language::Spanish,
+    decimal_word: "coma",
+    decimal_separator: ',',
+    group_separator: '.',
+    conjunction: "y",
+    months: [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    currencies: &[
+        Currency {
+            words: &["euro", "euros"],
+            symbol: "€",
+            subunits: &["céntimo", "céntimos", "centavo", "centavos"],
+        },
+        Currency {
+            words: &["dólar", "dólares"],
+            symbol: "$",
+            subunits: &["centavo", "centavos"],
+        },
+    ],
+    currency_conjunctions: &["con", "y"],
+    percent: &["por ciento"],
+    percent_sign: " %",
+    units: &[
+        ("kilómetros por hora", "km/h"),
+        ("grados centígrados", "°C"),
+        ("grados celsius", "°C"),
+        ("kilómetros", "km"),
+        ("kilómetro", "km"),
+        ("centímetros", "cm"),
+        ("centímetro", "cm"),
+        ("milímetros", "mm"),
+        ("milímetro", "mm"),
+        ("metros", "m"),
+        ("metro", "m"),
+        ("kilogramos", "kg"),
+        ("kilogramo", "kg"),
+        ("kilos", "kg"),
+        ("kilo", "kg"),
+        ("gramos", "g"),
+        ("gramo", "g"),
+        ("mililitros", "ml"),
+        ("mililitro", "ml"),
+        ("litros", "l"),
+        ("litro", "l"),
+        ("terabytes", "TB"),
+        ("gigabytes", "GB"),
+        ("megabytes", "MB"),
+        ("kilobytes", "KB"),
+        ("grados", "°"),
+        ("grado", "°"),
+    ],
+};
+
+const ENGLISH_NUMBERS: &[(&str, Term)] = &[
+    ("zero", Term::Unit(0)),
+    ("one", Term::Unit(1)),
+    ("two", Term::Unit(2)),
+    ("three", Term::Unit(3)),
+    ("four", Term::Unit(4)),
+    ("five", Term::Unit(5)),
+    ("six", Term::Unit(6)),
+    ("seven", Term::Unit(7)),
+    ("eight", Term::Unit(8)),
+    ("nine", Term::Unit(9)),
+    ("ten", Term::Pair(10)),
+    ("eleven", Term::Pair(11)),
+    ("twelve", Term::Pair(12)),
+    ("thirteen", Term::Pair(13)),
+    ("fourteen", Term::Pair(14)),
+    ("fifteen", Term::Pair(15)),
+    ("sixteen", Term::Pair(16)),
+    ("seventeen", Term::Pair(17)),
+    ("eighteen", Term::Pair(18)),
+    ("nineteen", Term::Pair(19)),
+    ("twenty", Term::Tens(20)),
+    ("thirty", Term::Tens(30)),
+    ("forty", Term::Tens(40)),
+    ("fifty", Term::Tens(50)),
+    ("sixty", Term::Tens(60)),
+    ("seventy", Term::Tens(70)),
+    ("eighty", Term::Tens(80)),
+    ("ninety", Term::Tens(90)),
+    ("hundred", Term::Hundred),
+    ("thousand", Term::Scale(1_000)),
+    ("million", Term::Scale(1_000_000)),
+    ("billion", Term::Scale(1_000_000_000)),
+    ("and", Term::Conjunction),
+];
+
+const ENGLISH_ORDINALS: &[(&str, Term)] = &[
+    ("first", Term::Unit(1)),
+    ("second", Term::Unit(2)),
+    ("third", Term::Unit(3)),
+    ("fourth", Term::Unit(4)),
+    ("fifth", Term::Unit(5)),
+    ("sixth", Term::Unit(6)),
+    ("seventh", Term::Unit(7)),
+    ("eighth", Term::Unit(8)),
+    ("ninth", Term::Unit(9)),
+    ("tenth", Term::Pair(10)),
+    ("eleventh", Term::Pair(11)),
+    ("twelfth", Term::Pair(12)),
+    ("thirteenth", Term::Pair(13)),
+    ("fourteenth", Term::Pair(14)),
+    ("fifteenth", Term::Pair(15)),
+    ("sixteenth", Term::Pair(16)),
+    ("seventeenth", Term::Pair(17)),
+    ("eighteenth", Term::Pair(18)),
+    ("nineteenth", Term::Pair(19)),
+    ("twentieth", Term::Tens(20)),
+    ("thirtieth", Term::Tens(30)),
+    ("fortieth", Term::Tens(40)),
+    ("fiftieth", Term::Tens(50)),
+    ("sixtieth", Term::Tens(60)),
+    ("seventieth", Term::Tens(70)),
+    ("eightieth", Term::Tens(80)),
+    ("ninetieth", Term::Tens(90)),
+    ("hundredth", Term::Hundred),
+    ("thousandth", Term::Scale(1_000)),
+    ("millionth", Term::Scale(1_000_000)),
+];
+
+const GERMAN_SCALES: &[(&str, Term)] = &[
+    ("million", Term::Scale(1_000_000)),
+    ("millionen", Term::Scale(1_000_000)),
+    ("milliarde", Term::Scale(1_000_000_000)),
+    ("milliarden", Term::Scale(1_000_000_000)),
+];
+
+// Parts of German compound numbers, longest first where one is a prefix of
+// another
+const GERMAN_PARTS: &[(&str, u64)] = &[
+    ("dreizehn", 13),
+    ("vierzehn", 14),
+    ("fünfzehn", 15),
+    ("sechzehn", 16),
+    ("siebzehn", 17),
+    ("achtzehn", 18),
+    ("neunzehn", 19),
+    ("zwanzig", 20),
+    ("dreißig", 30),
+    ("vierzig", 40),
+    ("fünfzig", 50),
+    ("sechzig", 60),
+    ("siebzig", 70),
+    ("achtzig", 80),
+    ("neunzig", 90),
+    ("null", 0),
+    ("eins", 1),
+    ("ein", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+    ("zehn", 10),
+    ("elf", 11),
+    ("zwölf", 12),
+];
+
+const SPANISH_NUMBERS: &[(&str, Term)] = &[
+    ("cero", Term::Unit(0)),
+    ("uno", Term::Unit(1)),
+    ("una", Term::Unit(1)),
+    ("dos", Term::Unit(2)),
+    ("tres", Term::Unit(3)),
+    ("cuatro", Term::Unit(4)),
+    ("cinco", Term::Unit(5)),
+    ("seis", Term::Unit(6)),
+    ("siete", Term::Unit(7)),
+    ("ocho", Term::Unit(8)),
+    ("nueve", Term::Unit(9)),
+    ("diez", Term::Pair(10)),
+    ("once", Term::Pair(11)),
+    ("doce", Term::Pair(12)),
+    ("trece", Term::Pair(13)),
+    ("catorce", Term::Pair(14)),
+    ("quince", Term::Pair(15)),
+    ("dieciséis", Term::Pair(16)),
+    ("diecisiete", Term::Pair(17)),
+    ("dieciocho", Term::Pair(18)),
+    ("diecinueve", Term::Pair(19)),
+    ("veinte", Term::Pair(20)),
+    ("veintiuno", Term::Pair(21)),
+    ("veintiún", Term::Pair(21)),
+    ("veintidós", Term::Pair(22)),
+    ("veintitrés", Term::Pair(23)),
+    ("veinticuatro", Term::Pair(24)),
+    ("veinticinco", Term::Pair(25)),
+    ("veintiséis", Term::Pair(26)),
+    ("veintisiete", Term::Pair(27)),
+    ("veintiocho", Term::Pair(28)),
+    ("veintinueve", Term::Pair(29)),
+    ("treinta", Term::Tens(30)),
+    ("cuarenta", Term::Tens(40)),
+    ("cincuenta", Term::Tens(50)),
+    ("sesenta", Term::Tens(60)),
+    ("setenta", Term::Tens(70)),
+    ("ochenta", Term::Tens(80)),
+    ("noventa", Term::Tens(90)),
+    ("cien", Term::Hundreds(100)),
+    ("ciento", Term::Hundreds(100)),
+    ("doscientos", Term::Hundreds(200)),
+    ("doscientas", Term::Hundreds(200)),
+    ("trescientos", Term::Hundreds(300)),
+    ("trescientas", Term::Hundreds(300)),
+    ("cuatrocientos", Term::Hundreds(400)),
+    ("cuatrocientas", Term::Hundreds(400)),
+    ("quinientos", Term::Hundreds(500)),
+    ("quinientas", Term::Hundreds(500)),
+    ("seiscientos", Term::Hundreds(600)),
+    ("seiscientas", Term::Hundreds(600)),
+    ("setecientos", Term::Hundreds(700)),
+    ("setecientas", Term::Hundreds(700)),
+    ("ochocientos", Term::Hundreds(800)),
+    ("ochocientas", Term::Hundreds(800)),
+    ("novecientos", Term::Hundreds(900)),
+    ("novecientas", Term::Hundreds(900)),
+    ("mil", Term::Scale(1_000)),
+    ("millón", Term::Scale(1_000_000)),
+    ("millones", Term::Scale(1_000_000)),
+    ("y", Term::Conjunction),
+];
+
+/// Rewrites spoken numbers, ordinals, dates, times, percentages, currency,
+/// phone numbers and units in `text` into written form
+///
+/// # Arguments
+/// * `text` - The transcribed text
+/// * `language` - The transcription language, such as "en" or "de-AT". "auto" is treated as English; languages without rules leave the text unchanged.
+///
+/// # Returns
+/// The text with spoken forms replaced
+pub fn inverse_normalize(text: &str, language: &str) -> String {
+    let base = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let rules = match base.as_str() {
+        "en" | "auto" => &ENGLISH,
+        "de" => &GERMAN,
+        "es" => &SPANISH,
+        _ => return text.to_string(),
+    };
+
+    Normalizer::new(text, rules).run()
+}
+
+struct Word {
+    /// Whitespace (or hyphen) before the word
+    separator: String,
+    lead: String,
+    core: String,
+    lower: String,
+    trail: String,
+}
+
+#[derive(Debug, Clone)]
+struct Number {
+    value: u64,
+    end: usize,
+    words: usize,
+    ordinal: bool,
+    decimals: Option<String>,
+    /// Written with digits in the transcript already
+    digits: bool,
+}
+
+struct Normalizer<'a> {
+    rules: &'a Rules,
+    words: Vec<Word>,
+    /// Whitespace after the last word
+    tail: String,
+}
+
+impl<'a> Normalizer<'a> {
+    fn new(text: &str, rules: &'a Rules) -> Self {
+        let mut normalizer = Self {
+            rules,
+            words: Vec::new(),
+            tail: String::new(),
+        };
+
+        let mut separator = String::new();
+        let mut token = String::new();
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !token.is_empty() {
+                    normalizer.push_token(std::mem::take(&mut separator), &token);
+                    token.clear();
+                }
+                separator.push(c);
+            } else {
+                token.push(c);
+            }
+        }
+        if !token.is_empty() {
+            normalizer.push_token(std::mem::take(&mut separator), &token);
+        }
+        normalizer.tail = separator;
+        normalizer
+    }
+
+    fn push_token(&mut self, separator: String, token: &str) {
+        let core_start = token
+            .find(|c: char| c.is_alphanumeric())
+            .unwrap_or(token.len());
+        let core_end = token
+            .rfind(|c: char| c.is_alphanumeric())
+            .map_or(core_start, |i| {
+                i + token[i..].chars().next().unwrap().len_utf8()
+            });
+        let lead = &token[..core_start];
+        let core = &token[core_start..core_end.max(core_start)];
+        let trail = &token[core_end.max(core_start)..];
+
+        // "twenty-five" is read as two number words
+        let parts: Vec<&str> = core.split('-').collect();
+        let split = parts.len() > 1
+            && parts
+                .iter()
+                .all(|part| !part.is_empty() && self.term(&part.to_lowercase()).is_some());
+        if !split {
+            self.words.push(Word {
+                separator,
+                lead: lead.to_string(),
+                core: core.to_string(),
+                lower: core.to_lowercase().replace('’', "'"),
+                trail: trail.to_string(),
+            });
+            return;
+        }
+
+        let last = parts.len() - 1;
+        for (i, part) in parts.iter().enumerate() {
+            self.words.push(Word {
+                separator: if i == 0 {
+                    separator.clone()
+                } else {
+                    "-".to_string()
+                },
+                lead: if i == 0 {
+                    lead.to_string()
+                } else {
+                    String::new()
+                },
+                core: part.to_string(),
+                lower: part.to_lowercase(),
+                trail: if i == last {
+                    trail.to_string()
+                } else {
+                    String::new()
+                },
+            });
+        }
+    }
+
+    fn run(&self) -> String {
+        let mut output = String::new();
+        let mut i = 0;
+        while i < self.words.len() {
+            let word = &self.words[i];
+            match self.convert(i) {
+                Some((end, written)) => {
+                    output.push_str(&word.separator);
+                    output.push_str(&word.lead);
+                    output.push_str(&written);
+                    // "p.m." or "3." already ends with the period after it
+                    let trail = &self.words[end - 1].trail;
+                    match trail.strip_prefix('.') {
+                        Some(rest) if written.ends_with('.') => output.push_str(rest),
+                        _ => output.push_str(trail),
+                    }
+                    i = end;
+                }
+                None => {
+                    output.push_str(&word.separator);
+                    output.push_str(&word.lead);
+                    output.push_str(&word.core);
+                    output.push_str(&word.trail);
+                    i += 1;
+                }
+            }
+        }
+        output.push_str(&self.tail);
+        output
+    }
+
+    /// Tries every pattern at word `i`, returning where the match ends and
+    /// its written form
+    fn convert(&self, i: usize) -> Option<(usize, String)> {
+        self.phone_number(i)
+            .or_else(|| self.date(i))
+            .or_else(|| self.time(i))
+            .or_else(|| self.measure(i))
+            .or_else(|| self.year(i).map(|(value, end)| (end, value.to_string())))
+            .or_else(|| self.plain_number(i))
+    }
+
+    /// Whether word `j` can continue a phrase that started before it
+    fn joins(&self, j: usize) -> bool {
+        j > 0
+            && j < self.words.len()
+            && self.words[j].lead.is_empty()
+            && self.words[j - 1].trail.is_empty()
+    }
+
+    fn is_word(&self, j: usize, expected: &str) -> bool {
+        self.words.get(j).is_some_and(|word| word.lower == expected)
+    }
+
+    /// Matches a possibly multi-word phrase continuing at `j`
+    fn phrase(&self, j: usize, phrase: &str) -> Option<usize> {
+        let mut end = j;
+        for expected in phrase.split_whitespace() {
+            if !self.joins(end) || !self.is_word(end, expected) {
+                return None;
+            }
+            end += 1;
+        }
+        Some(end)
+    }
+
+    fn term(&self, lower: &str) -> Option<Term> {
+        self.lookup(lower).map(|(term, _)| term)
+    }
+
+    /// Looks up a number word, returning whether it is an ordinal
+    fn lookup(&self, lower: &str) -> Option<(Term, bool)> {
+        let find = |table: &[(&str, Term)]| {
+            table
+                .iter()
+                .find(|(word, _)| *word == lower)
+                .map(|(_, term)| *term)
+        };
+
+        match self.rules.language {
+            Language::English => find(ENGLISH_NUMBERS)
+                .map(|term| (term, false))
+                .or_else(|| find(ENGLISH_ORDINALS).map(|term| (term, true))),
+            Language::German => find(GERMAN_SCALES)
+                .map(|term| (term, false))
+                .or_else(|| german_compound(lower).map(|value| (Term::Compound(value), false)))
+                .or_else(|| german_ordinal(lower).map(|value| (Term::Compound(value), true))),
+            Language::Spanish => find(SPANISH_NUMBERS).map(|term| (term, false)).or_else(|| {
+                matches!(lower, "primero" | "primera" | "primer").then_some((Term::Unit(1), true))
+            }),
+        }
+    }
+
+    /// A single spoken digit, for phone numbers and decimals
+    fn digit(&self, j: usize) -> Option<u64> {
+        match self.term(&self.words.get(j)?.lower)? {
+            Term::Unit(value) => Some(value),
+            Term::Compound(value) if value < 10 => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Parses a number starting at word `i`
+    fn number(&self, i: usize) -> Option<Number> {
+        let first = self.words.get(i)?;
+        if first.core.chars().all(|c| c.is_ascii_digit()) && !first.core.is_empty() {
+            return Some(Number {
+                value: first.core.parse().ok()?,
+                end: i + 1,
+                words: 1,
+                ordinal: false,
+                decimals: None,
+                digits: true,
+            });
+        }
+        if self.rules.language == Language::English {
+            if let Some(value) = english_numeric_ordinal(&first.lower) {
+                return Some(Number {
+                    value,
+                    end: i + 1,
+                    words: 1,
+                    ordinal: true,
+                    decimals: None,
+                    digits: true,
+                });
+            }
+        }
+
+        let mut total = 0u64;
+        let mut current = 0u64;
+        let mut last: Option<Term> = None;
+        let mut last_scale = u64::MAX;
+        let mut ordinal = false;
+        let mut end = i;
+        let mut j = i;
+
+        while j < self.words.len() && (j == i || self.joins(j)) {
+            let Some((term, is_ordinal)) = self.lookup(&self.words[j].lower) else {
+                break;
+            };
+            let after_group = matches!(
+                last,
+                None | Some(Term::Hundred | Term::Hundreds(_) | Term::Scale(_) | Term::Conjunction)
+            );
+            let allowed = match term {
+                Term::Unit(_) => after_group || matches!(last, Some(Term::Tens(_))),
+                Term::Pair(_) | Term::Tens(_) => after_group,
+                Term::Hundred => {
+                    matches!(last, Some(Term::Unit(_) | Term::Pair(_) | Term::Tens(_)))
+                        && !current.is_multiple_of(100)
+                        && current < 100
+                }
+                Term::Hundreds(_) => matches!(last, None | Some(Term::Scale(_))),
+                Term::Scale(scale) => {
+                    (current > 0 && scale < last_scale && last != Some(Term::Conjunction))
+                        || (last.is_none() && self.words[j].lower == "mil")
+                }
+                Term::Conjunction => self.conjunction_continues(last, j),
+                Term::Compound(value) => {
+                    last.is_none() || (matches!(last, Some(Term::Scale(_))) && value < last_scale)
+                }
+            };
+            if !allowed {
+                break;
+            }
+
+            match term {
+                Term::Unit(value)
+                | Term::Pair(value)
+                | Term::Tens(value)
+                | Term::Hundreds(value)
+                | Term::Compound(value) => current += value,
+                Term::Hundred => current *= 100,
+                Term::Scale(scale) => {
+                    total += current.max(1) * scale;
+                    current = 0;
+                    last_scale = scale;
+                }
+                Term::Conjunction => {}
+            }
+            last = Some(term);
+            j += 1;
+            if term != Term::Conjunction {
+                end = j;
+            }
+            if is_ordinal {
+                ordinal = true;
+                break;
+            }
+        }
+
+        if end == i {
+            return None;
+        }
+
+        let mut number = Number {
+            value: total + current,
+            end,
+            words: end - i,
+            ordinal,
+            decimals: None,
+            digits: false,
+        };
+
+        if !ordinal && self.is_word(end, self.rules.decimal_word) && self.joins(end) {
+            let mut k = end + 1;
+            let mut decimals = String::new();
+            while self.joins(k) {
+                match self.digit(k) {
+                    Some(digit) => decimals.push_str(&digit.to_string()),
+                    None => break,
+                }
+                k += 1;
+            }
+            if !decimals.is_empty() {
+                number.decimals = Some(decimals);
+                number.words = k - i;
+                number.end = k;
+            }
+        }
+
+        Some(number)
+    }
+
+    fn conjunction_continues(&self, last: Option<Term>, j: usize) -> bool {
+        if self.rules.conjunction.is_empty() || self.words[j].lower != self.rules.conjunction {
+            return false;
+        }
+        let next = self
+            .words
+            .get(j + 1)
+            .filter(|_| self.joins(j + 1))
+            .and_then(|word| self.term(&word.lower));
+        match self.rules.language {
+            // "one hundred and five"
+            Language::English => {
+                matches!(last, Some(Term::Hundred | Term::Scale(_)))
+                    && matches!(next, Some(Term::Unit(_) | Term::Pair(_) | Term::Tens(_)))
+            }
+            // "treinta y cinco"
+            _ => matches!(last, Some(Term::Tens(_))) && matches!(next, Some(Term::Unit(_))),
+        }
+    }
+
+    /// A number of at most two words between 10 and 99, the halves of an
+    /// English year such as "nineteen ninety"
+    fn pair(&self, i: usize) -> Option<(u64, usize)> {
+        match self.term(&self.words.get(i)?.lower)? {
+            Term::Pair(value) => Some((value, i + 1)),
+            Term::Tens(value) => match self.words.get(i + 1) {
+                Some(word) if self.joins(i + 1) => match self.term(&word.lower) {
+                    Some(Term::Unit(unit)) if unit > 0 => Some((value + unit, i + 2)),
+                    _ => Some((value, i + 1)),
+                },
+                _ => Some((value, i + 1)),
+            },
+            _ => None,
+        }
+    }
+
+    /// An English year said in two halves: "nineteen ninety", "twenty oh
+    /// five", "nineteen hundred"
+    fn year(&self, i: usize) -> Option<(u64, usize)> {
+        if self.rules.language != Language::English {
+            return None;
+        }
+        let (century, end) = self.pair(i)?;
+        if !(10..=20).contains(&century) || !self.joins(end) {
+            return None;
+        }
+
+        let (rest, year_end) = if self.is_word(end, "hundred") {
+            (0, end + 1)
+        } else if self.is_word(end, "oh") && self.joins(end + 1) {
+            let digit = self.digit(end + 1).filter(|d| *d > 0)?;
+            (digit, end + 2)
+        } else {
+            let (rest, rest_end) = self.pair(end)?;
+            (rest, rest_end)
+        };
+
+        // "twenty twenty five thousand" is not a year
+        if self.joins(year_end) && self.term(&self.words[year_end].lower).is_some() {
+            return None;
+        }
+        Some((century * 100 + rest, year_end))
+    }
+
+    /// A year following a date, either spoken in halves or as a cardinal
+    fn year_at(&self, i: usize) -> Option<(u64, usize)> {
+        if let Some(year) = self.year(i) {
+            return Some(year);
+        }
+        self.number(i)
+            .filter(|n| !n.ordinal && n.decimals.is_none() && (1000..=2999).contains(&n.value))
+            .map(|n| (n.value, n.end))
+    }
+
+    fn month(&self, j: usize) -> Option<usize> {
+        let word = self.words.get(j)?;
+        self.rules
+            .months
+            .iter()
+            .position(|month| month.to_lowercase() == word.lower)
+    }
+
+    fn date(&self, i: usize) -> Option<(usize, String)> {
+        let months = &self.rules.months;
+        match self.rules.language {
+            Language::English => {
+                // "the third of march"
+                if self.is_word(i, "the") {
+                    let day = self.number(i + 1).filter(|_| self.joins(i + 1))?;
+                    if !day.ordinal || !(1..=31).contains(&day.value) {
+                        return None;
+                    }
+                    let of = self.phrase(day.end, "of")?;
+                    let month = self.month(of).filter(|_| self.joins(of))?;
+                    let mut end = of + 1;
+                    let mut written = format!("{} {}", months[month], day.value);
+                    if let Some((year, year_end)) =
+                        self.joins(end).then(|| self.year_at(end)).flatten()
+                    {
+                        written = format!("{}, {}", written, year);
+                        end = year_end;
+                    }
+                    return Some((end, written));
+                }
+
+                // "march third", "march third twenty twenty six"
+                let month = self.month(i)?;
+                let day = self.number(i + 1).filter(|_| self.joins(i + 1))?;
+                if !day.ordinal || !(1..=31).contains(&day.value) {
+                    return None;
+                }
+                let mut written = format!("{} {}", months[month], day.value);
+                let mut end = day.end;
+                if let Some((year, year_end)) = self.joins(end).then(|| self.year_at(end)).flatten()
+                {
+                    written = format!("{}, {}", written, year);
+                    end = year_end;
+                }
+                Some((end, written))
+            }
+            Language::German => {
+                // "dritter März zweitausendsechsundzwanzig"
+                let day = self.number(i)?;
+                if !day.ordinal || !(1..=31).contains(&day.value) || !self.joins(day.end) {
+                    return None;
+                }
+                let month = self.month(day.end)?;
+                let mut end = day.end + 1;
+                let mut written = format!("{}. {}", day.value, months[month]);
+                if let Some((year, year_end)) = self.joins(end).then(|| self.year_at(end)).flatten()
+                {
+                    written = format!("{} {}", written, year);
+                    end = year_end;
+                }
+                Some((end, written))
+            }
+            Language::Spanish => {
+                // "tres de marzo de dos mil veintiséis"
+                let day = self.number(i)?;
+                if !(1..=31).contains(&day.value) || day.decimals.is_some() {
+                    return None;
+                }
+                let of = self.phrase(day.end, "de")?;
+                let month = self.month(of).filter(|_| self.joins(of))?;
+                let mut end = of + 1;
+                let mut written = format!("{} de {}", day.value, months[month]);
+                if let Some(year_start) = self.phrase(end, "de") {
+                    if let Some((year, year_end)) = self.year_at(year_start) {
+                        written = format!("{} de {}", written, year);
+                        end = year_end;
+                    }
+                }
+                Some((end, written))
+            }
+        }
+    }
+
+    fn time(&self, i: usize) -> Option<(usize, String)> {
+        match self.rules.language {
+            Language::English => {
+                let hour = self.number(i).filter(|n| {
+                    !n.ordinal
+                        && n.decimals.is_none()
+                        && n.words == 1
+                        && (1..=12).contains(&n.value)
+                })?;
+                let mut end = hour.end;
+
+                if let Some(after) = self.phrase(end, "o'clock") {
+                    let mut written = format!("{}:00", hour.value);
+                    end = after;
+                    if let Some((meridiem, after)) = self.meridiem(end) {
+                        written = format!("{} {}", written, meridiem);
+                        end = after;
+                    }
+                    return Some((end, written));
+                }
+
+                let minutes = if self.is_word(end, "oh") && self.joins(end) && self.joins(end + 1) {
+                    let digit = self.digit(end + 1).filter(|d| *d > 0)?;
+                    end += 2;
+                    Some(digit)
+                } else if self.joins(end) {
+                    match self.pair(end) {
+                        Some((minutes, after)) if minutes < 60 => {
+                            end = after;
+                            Some(minutes)
+                        }
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+
+                let (meridiem, end) = self.meridiem(end)?;
+                Some(match minutes {
+                    Some(minutes) => (end, format!("{}:{:02} {}", hour.value, minutes, meridiem)),
+                    None => (end, format!("{} {}", hour.value, meridiem)),
+                })
+            }
+            Language::German => {
+                // "fünfzehn Uhr dreißig"
+                let hour = self
+                    .number(i)
+                    .filter(|n| !n.ordinal && n.decimals.is_none() && n.value <= 24)?;
+                let uhr = self.phrase(hour.end, "uhr")?;
+                let minutes = self
+                    .number(uhr)
+                    .filter(|n| self.joins(uhr) && !n.ordinal && n.decimals.is_none())
+                    .filter(|n| (1..60).contains(&n.value));
+                Some(match minutes {
+                    Some(minutes) => (
+                        minutes.end,
+                        format!("{}:{:02} Uhr", hour.value, minutes.value),
+                    ),
+                    None => (uhr, format!("{} Uhr", hour.value)),
+                })
+            }
+            Language::Spanish => {
+                // "las tres y media", "la una en punto"
+                if !self.is_word(i, "la") && !self.is_word(i, "las") {
+                    return None;
+                }
+                let hour = self.number(i + 1).filter(|n| {
+                    self.joins(i + 1) && !n.ordinal && n.words == 1 && (1..=12).contains(&n.value)
+                })?;
+                let (minutes, end) = if let Some(end) = self.phrase(hour.end, "en punto") {
+                    (0, end)
+                } else if let Some(end) = self.phrase(hour.end, "y media") {
+                    (30, end)
+                } else if let Some(end) = self.phrase(hour.end, "y cuarto") {
+                    (15, end)
+                } else {
+                    let y = self.phrase(hour.end, "y")?;
+                    let minutes = self
+                        .number(y)
+                        .filter(|n| self.joins(y) && !n.ordinal && (1..60).contains(&n.value))?;
+                    (minutes.value, minutes.end)
+                };
+                Some((
+                    end,
+                    format!("{} {}:{:02}", self.words[i].core, hour.value, minutes),
+                ))
+            }
+        }
+    }
+
+    /// "am", "p.m." or "p m" after an English time
+    fn meridiem(&self, j: usize) -> Option<(&'static str, usize)> {
+        if !self.joins(j) {
+            return None;
+        }
+        let written = |letter: &str| match letter {
+            "a" => Some("a.m."),
+            "p" => Some("p.m."),
+            _ => None,
+        };
+        match self.words[j].lower.as_str() {
+            "am" | "a.m" => Some(("a.m.", j + 1)),
+            "pm" | "p.m" => Some(("p.m.", j + 1)),
+            letter if self.is_word(j + 1, "m") && self.joins(j + 1) => {
+                written(letter).map(|meridiem| (meridiem, j + 2))
+            }
+            _ => None,
+        }
+    }
+
+    /// Currency, percentages and units after a number
+    fn measure(&self, i: usize) -> Option<(usize, String)> {
+        let number = self.number(i).filter(|n| !n.ordinal)?;
+        let end = number.end;
+
+        for currency in self.rules.currencies {
+            let Some(after) = currency
+                .words
+                .iter()
+                .find_map(|word| self.phrase(end, word))
+            else {
+                continue;
+            };
+            let (cents, end) = self.subunit_amount(after, currency).unzip();
+            let amount = match (cents, &number.decimals) {
+                (Some(cents), _) => format!(
+                    "{}{}{:02}",
+                    self.group(number.value),
+                    self.rules.decimal_separator,
+                    cents
+                ),
+                (None, Some(decimals)) => format!(
+                    "{}{}{:0<2}",
+                    self.group(number.value),
+                    self.rules.decimal_separator,
+                    decimals
+                ),
+                (None, None) => self.group(number.value),
+            };
+            let written = match self.rules.language {
+                Language::English => format!("{}{}", currency.symbol, amount),
+                _ => format!("{} {}", amount, currency.symbol),
+            };
+            return Some((end.unwrap_or(after), written));
+        }
+
+        let written_number = self.format_number(&number);
+        if let Some(after) = self
+            .rules
+            .percent
+            .iter()
+            .find_map(|phrase| self.phrase(end, phrase))
+        {
+            return Some((
+                after,
+                format!("{}{}", written_number, self.rules.percent_sign),
+            ));
+        }
+
+        for (phrase, symbol) in self.rules.units {
+            if let Some(after) = self.phrase(end, phrase) {
+                let separator =
+                    if symbol.starts_with('°') && self.rules.language == Language::English {
+                        ""
+                    } else {
+                        " "
+                    };
+                return Some((after, format!("{}{}{}", written_number, separator, symbol)));
+            }
+        }
+
+        None
+    }
+
+    /// The cents in "five dollars and ten cents" or "zehn Euro fünfzig"
+    fn subunit_amount(&self, j: usize, currency: &Currency) -> Option<(u64, usize)> {
+        let start = self
+            .rules
+            .currency_conjunctions
+            .iter()
+            .find_map(|conjunction| self.phrase(j, conjunction))
+            .unwrap_or(j);
+        if !self.joins(start) {
+            return None;
+        }
+        let cents = self
+            .number(start)
+            .filter(|n| !n.ordinal && n.decimals.is_none() && n.value < 100)?;
+
+        if let Some(end) = currency
+            .subunits
+            .iter()
+            .find_map(|subunit| self.phrase(cents.end, subunit))
+        {
+            return Some((cents.value, end));
+        }
+        // Without the subunit word the amount has to end the phrase
+        let ends_phrase =
+            cents.end == self.words.len() || !self.words[cents.end - 1].trail.is_empty();
+        (ends_phrase && start == j).then_some((cents.value, cents.end))
+    }
+
+    /// A run of at least seven single digits
+    fn phone_number(&self, i: usize) -> Option<(usize, String)> {
+        let mut digits = String::new();
+        let mut j = i;
+        while j < self.words.len() && (j == i || self.joins(j)) {
+            let digit =
+                if self.rules.language == Language::English && j > i && self.words[j].lower == "oh"
+                {
+                    Some(0)
+                } else {
+                    self.digit(j)
+                };
+            match digit {
+                Some(digit) => digits.push_str(&digit.to_string()),
+                None => break,
+            }
+            j += 1;
+        }
+        if digits.len() < 7 {
+            return None;
+        }
+
+        let written = match (self.rules.language, digits.len()) {
+            (Language::English, 7) => format!("{}-{}", &digits[..3], &digits[3..]),
+            (Language::English, 10) => {
+                format!("{}-{}-{}", &digits[..3], &digits[3..6], &digits[6..])
+            }
+            (Language::English, 11) if digits.starts_with('1') => {
+                format!("1-{}-{}-{}", &digits[1..4], &digits[4..7], &digits[7..])
+            }
+            _ => digits,
+        };
+        Some((j, written))
+    }
+
+    /// Numbers outside any other pattern, written with digits unless they
+    /// are a single small word
+    fn plain_number(&self, i: usize) -> Option<(usize, String)> {
+        let number = self.number(i)?;
+        if number.digits {
+            return None;
+        }
+        let small = number.words == 1 && number.value < 10;
+
+        if number.ordinal {
+            if small {
+                return None;
+            }
+            return match self.rules.language {
+                Language::English => Some((
+                    number.end,
+                    format!("{}{}", number.value, english_suffix(number.value)),
+                )),
+                Language::German => Some((number.end, format!("{}.", number.value))),
+                Language::Spanish => None,
+            };
+        }
+
+        if small && number.decimals.is_none() {
+            return None;
+        }
+        Some((number.end, self.format_number(&number)))
+    }
+
+    fn format_number(&self, number: &Number) -> String {
+        if number.digits && number.decimals.is_none() {
+            return number.value.to_string();
+        }
+        match &number.decimals {
+            Some(decimals) => format!(
+                "{}{}{}",
+                self.group(number.value),
+                self.rules.decimal_separator,
+                decimals
+            ),
+            None => self.group(number.value),
+        }
+    }
+
+    /// Writes `value` with thousands separators from five digits up
+    fn group(&self, value: u64) -> String {
+        let digits = value.to_string();
+        if digits.len() < 5 {
+            return digits;
+        }
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(self.rules.group_separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+fn english_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// "3rd", "21st"
+fn english_numeric_ordinal(lower: &str) -> Option<u64> {
+    let digits = lower.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &lower[digits.len()..];
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value = digits.parse().ok()?;
+    (suffix == english_suffix(value)).then_some(value)
+}
+
+/// Parses a German number written as one word, such as "fünfundzwanzig" or
+/// "neunzehnhundertneunzig"
+fn german_compound(word: &str) -> Option<u64> {
+    // Articles, not numbers
+    if matches!(word, "ein" | "und") {
+        return None;
+    }
+
+    let mut total = 0u64;
+    let mut current = 0u64;
+    let mut rest = word;
+    let mut parts = 0;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("hundert") {
+            current = current.max(1) * 100;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("tausend") {
+            total += current.max(1) * 1000;
+            current = 0;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("und") {
+            // Only between a unit and the tens after it
+            if parts == 0
+                || !GERMAN_PARTS
+                    .iter()
+                    .any(|(part, value)| *value >= 20 && after.starts_with(part))
+            {
+                return None;
+            }
+            rest = after;
+        } else {
+            let (part, value) = GERMAN_PARTS
+                .iter()
+                .find(|(part, _)| rest.starts_with(part))?;
+            current += value;
+            rest = &rest[part.len()..];
+        }
+        parts += 1;
+    }
+
+    Some(total + current)
+}
+
+/// Parses a German ordinal in any inflection, such as "dritten" or
+/// "zwanzigste"
+fn german_ordinal(word: &str) -> Option<u64> {
+    let stem = ["", "n", "r", "s", "m"]
+        .iter()
+        .find_map(|ending| word.strip_suffix(ending).and_then(|w| w.strip_suffix('e')))?;
+
+    // "erste", "dritte", "achte" and "siebte" are irregular
+    let regular = |stem: &str| {
+        let stem = if stem == "er" {
+            "eins".to_string()
+        } else if let Some(start) = stem.strip_suffix("drit") {
+            format!("{}drei", start)
+        } else if let Some(start) = stem.strip_suffix("ach") {
+            format!("{}acht", start)
+        } else if let Some(start) = stem.strip_suffix("sieb") {
+            format!("{}sieben", start)
+        } else {
+            stem.to_string()
+        };
+        german_compound(&stem)
+    };
+
+    stem.strip_suffix("st")
+        .and_then(regular)
+        .or_else(|| stem.strip_suffix('t').and_then(regular))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn en(text: &str) -> String {
+        inverse_normalize(text, "en")
+    }
+
+    #[test]
+    fn test_cardinals() {
+        assert_eq!(en("I waited twenty five minutes"), "I waited 25 minutes");
+        assert_eq!(en("one hundred and one dalmatians"), "101 dalmatians");
+        assert_eq!(
+            en("about two thousand five hundred people"),
+            "about 2500 people"
+        );
+        assert_eq!(en("three million users"), "3,000,000 users");
+        assert_eq!(en("forty-two"), "42");
+        assert_eq!(en("pi is three point one four"), "pi is 3.14");
+    }
+
+    #[test]
+    fn test_small_numbers_stay_words() {
+        assert_eq!(en("I have one question"), "I have one question");
+        assert_eq!(en("the first time"), "the first time");
+        assert_eq!(en("one two three"), "one two three");
+    }
+
+    #[test]
+    fn test_ordinals() {
+        assert_eq!(en("the twenty first century"), "the 21st century");
+        assert_eq!(en("her twelfth birthday"), "her 12th birthday");
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(en("march third twenty twenty six"), "March 3, 2026");
+        assert_eq!(en("on may first."), "on May 1.");
+        assert_eq!(
+            en("on the fourth of july nineteen ninety"),
+            "on July 4, 1990"
+        );
+        assert_eq!(en("you may one day"), "you may one day");
+    }
+
+    #[test]
+    fn test_years() {
+        assert_eq!(en("back in nineteen ninety nine"), "back in 1999");
+        assert_eq!(en("since twenty oh five"), "since 2005");
+    }
+
+    #[test]
+    fn test_times() {
+        assert_eq!(en("at three thirty pm"), "at 3:30 p.m.");
+        assert_eq!(
+            en("at seven oh five a.m. tomorrow"),
+            "at 7:05 a.m. tomorrow"
+        );
+        assert_eq!(en("see you at ten pm."), "see you at 10 p.m.");
+        assert_eq!(en("at ten o'clock"), "at 10:00");
+        assert_eq!(en("at 9 p m"), "at 9 p.m.");
+    }
+
+    #[test]
+    fn test_currency() {
+        assert_eq!(en("twenty five dollars and ten cents"), "$25.10");
+        assert_eq!(en("it costs one dollar"), "it costs $1");
+        assert_eq!(en("about 40 euros"), "about €40");
+        assert_eq!(en("twelve point five dollars"), "$12.50");
+    }
+
+    #[test]
+    fn test_percent_and_units() {
+        assert_eq!(en("five percent"), "5%");
+        assert_eq!(en("ran ten kilometers"), "ran 10 km");
+        assert_eq!(en("it is twenty degrees celsius"), "it is 20°C");
+        assert_eq!(en("two gigabytes"), "2 GB");
+    }
+
+    #[test]
+    fn test_phone_numbers() {
+        assert_eq!(
+            en("call five five five one two three four"),
+            "call 555-1234"
+        );
+        assert_eq!(
+            en("call eight oh oh five five five one two one two"),
+            "call 800-555-1212"
+        );
+    }
+
+    #[test]
+    fn test_punctuation_splits_numbers() {
+        assert_eq!(en("twenty, twenty five"), "20, 25");
+        assert_eq!(en("(twenty five)"), "(25)");
+    }
+
+    #[test]
+    fn test_german() {
+        let de = |text: &str| inverse_normalize(text, "de");
+        assert_eq!(de("fünfundzwanzig Euro fünfzig"), "25,50 €");
+        assert_eq!(
+            de("am dritten März zweitausendsechsundzwanzig"),
+            "am 3. März 2026"
+        );
+        assert_eq!(de("um fünfzehn Uhr dreißig"), "um 15:30 Uhr");
+        assert_eq!(de("zehn Prozent"), "10 %");
+        assert_eq!(de("drei Komma fünf Kilometer"), "3,5 km");
+        assert_eq!(de("der zwanzigste Platz"), "der 20. Platz");
+        assert_eq!(de("ein Hund und zwei Katzen"), "ein Hund und zwei Katzen");
+        assert_eq!(de("zwei Millionen"), "2.000.000");
+    }
+
+    #[test]
+    fn test_spanish() {
+        let es = |text: &str| inverse_normalize(text, "es");
+        assert_eq!(es("treinta y cinco euros"), "35 €");
+        assert_eq!(
+            es("tres de marzo de dos mil veintiséis"),
+            "3 de marzo de 2026"
+        );
+        assert_eq!(es("a las tres y media"), "a las 3:30");
+        assert_eq!(es("veinte por ciento"), "20 %");
+        assert_eq!(es("doscientos cincuenta kilómetros"), "250 km");
+    }
+
+    #[test]
+    fn test_unsupported_language_is_unchanged() {
+        assert_eq!(
+            inverse_normalize("vingt-cinq euros", "fr"),
+            "vingt-cinq euros"
+        );
+    }
+
+    #[test]
+    fn test_preserves_surrounding_text() {
+        assert_eq!(
+            en("Hello  world, it's twenty five!"),
+            "Hello  world, it's 25!"
+        );
+    }
+}
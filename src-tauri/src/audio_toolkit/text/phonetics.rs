@@ -0,0 +1,206 @@
+use natural::phonetics::soundex;
+
+/// Decides whether two words sound alike, which lets custom word correction
+/// accept spellings that are further apart than the threshold allows
+pub trait PhoneticMatcher: Sync {
+    fn sounds_alike(&self, a: &str, b: &str) -> bool;
+}
+
+/// Metaphone, tuned for English spelling
+pub struct Metaphone;
+
+/// Soundex, coarse but reasonable for most Latin-script languages
+pub struct Soundex;
+
+/// For scripts where letter-based phonetic codes make no sense
+pub struct NoPhonetics;
+
+impl PhoneticMatcher for Metaphone {
+    fn sounds_alike(&self, a: &str, b: &str) -> bool {
+        match (fold_latin(a), fold_latin(b)) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (metaphone(&a), metaphone(&b));
+                !a.is_empty() && a == b
+            }
+            _ => false,
+        }
+    }
+}
+
+impl PhoneticMatcher for Soundex {
+    fn sounds_alike(&self, a: &str, b: &str) -> bool {
+        match (fold_latin(a), fold_latin(b)) {
+            (Some(a), Some(b)) if !a.is_empty() && !b.is_empty() => soundex(&a, &b),
+            _ => false,
+        }
+    }
+}
+
+impl PhoneticMatcher for NoPhonetics {
+    fn sounds_alike(&self, _a: &str, _b: &str) -> bool {
+        false
+    }
+}
+
+/// Picks the phonetic matcher for a transcription language such as "en" or
+/// "pt-BR". Automatic detection uses Metaphone, which only ever matches
+/// Latin-script words.
+pub fn for_language(language: &str) -> &'static dyn PhoneticMatcher {
+    let base = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match base.as_str() {
+        "en" | "auto" => &Metaphone,
+        "zh" | "yue" | "ja" | "ko" | "th" | "lo" | "km" | "my" | "bo" | "ar" | "fa" | "ur"
+        | "he" | "yi" | "hi" | "bn" | "ta" | "te" | "kn" | "ml" | "mr" | "gu" | "pa" | "ne"
+        | "si" | "ru" | "uk" | "be" | "bg" | "sr" | "mk" | "kk" | "mn" | "el" | "ka" | "hy"
+        | "am" => &NoPhonetics,
+        _ => &Soundex,
+    }
+}
+
+/// Lowercases a word and strips accents from Latin letters, dropping anything
+/// that isn't a letter. Returns `None` for words in other scripts.
+fn fold_latin(word: &str) -> Option<String> {
+    let mut folded = String::new();
+    for c in word.chars().flat_map(char::to_lowercase) {
+        let base = match c {
+            'a'..='z' => c,
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+            'ç' | 'ć' | 'č' => 'c',
+            'ď' | 'đ' => 'd',
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+            'ğ' => 'g',
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => 'i',
+            'ł' | 'ľ' | 'ĺ' => 'l',
+            'ñ' | 'ń' | 'ň' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => 'o',
+            'ŕ' | 'ř' => 'r',
+            'ś' | 'š' | 'ş' | 'ș' => 's',
+            'ť' | 'ţ' | 'ț' => 't',
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => 'u',
+            'ý' | 'ÿ' => 'y',
+            'ź' | 'ż' | 'ž' => 'z',
+            'ß' => {
+                folded.push_str("ss");
+                continue;
+            }
+            c if c.is_alphabetic() => return None,
+            _ => continue,
+        };
+        folded.push(base);
+    }
+    Some(folded)
+}
+
+/// Encodes a lowercase ASCII word with Lawrence Philips' original Metaphone
+/// rules
+fn metaphone(word: &str) -> String {
+    let letters: Vec<u8> = word.to_ascii_uppercase().into_bytes();
+    // Silent first letters
+    let start = match letters.as_slice() {
+        [b'A', b'E', ..]
+        | [b'G', b'N', ..]
+        | [b'K', b'N', ..]
+        | [b'P', b'N', ..]
+        | [b'W', b'R', ..] => 1,
+        _ => 0,
+    };
+    let letters = &letters[start..];
+    let at = |i: usize| letters.get(i).copied().unwrap_or(0);
+    let is_vowel = |c: u8| matches!(c, b'A' | b'E' | b'I' | b'O' | b'U');
+    let is_front_vowel = |c: u8| matches!(c, b'E' | b'I' | b'Y');
+
+    let mut code = String::new();
+    for i in 0..letters.len() {
+        let c = letters[i];
+        let prev = if i > 0 { at(i - 1) } else { 0 };
+        let next = at(i + 1);
+        // Doubled letters sound once, except "cc" as in "accent"
+        if c == prev && c != b'C' {
+            continue;
+        }
+
+        match c {
+            // Vowels only count at the start
+            b'A' | b'E' | b'I' | b'O' | b'U' if i == 0 => code.push(c as char),
+            // Silent in a final "mb"
+            b'B' if !(prev == b'M' && i + 1 == letters.len()) => code.push('B'),
+            b'C' => {
+                if next == b'I' && at(i + 2) == b'A' || next == b'H' && prev != b'S' {
+                    code.push('X');
+                } else if is_front_vowel(next) {
+                    if prev != b'S' {
+                        code.push('S');
+                    }
+                } else {
+                    code.push('K');
+                }
+            }
+            b'D' => {
+                if next == b'G' && is_front_vowel(at(i + 2)) {
+                    code.push('J');
+                } else {
+                    code.push('T');
+                }
+            }
+            b'G' => {
+                let silent_gh = next == b'H' && i + 2 < letters.len() && !is_vowel(at(i + 2));
+                let silent_gn =
+                    next == b'N' && (i + 2 == letters.len() || &letters[i + 1..] == b"NED");
+                if silent_gh || silent_gn {
+                    continue;
+                }
+                if is_front_vowel(next) {
+                    code.push('J');
+                } else {
+                    code.push('K');
+                }
+            }
+            b'H' => {
+                let after_modifier =
+                    matches!(prev, b'C' | b'S' | b'P' | b'T' | b'G') || (i == 1 && prev == b'W');
+                if !after_modifier && is_vowel(next) {
+                    code.push('H');
+                }
+            }
+            b'K' if prev != b'C' => code.push('K'),
+            b'P' => code.push(if next == b'H' { 'F' } else { 'P' }),
+            b'Q' => code.push('K'),
+            b'S' => {
+                if next == b'H' || next == b'I' && matches!(at(i + 2), b'O' | b'A') {
+                    code.push('X');
+                } else {
+                    code.push('S');
+                }
+            }
+            b'T' => {
+                if next == b'I' && matches!(at(i + 2), b'O' | b'A') {
+                    code.push('X');
+                } else if next == b'H' {
+                    code.push('0');
+                } else if !(next == b'C' && at(i + 2) == b'H') {
+                    code.push('T');
+                }
+            }
+            b'V' => code.push('F'),
+            // An initial "wh" is said as "w"
+            b'W' | b'Y' if is_vowel(next) || (i == 0 && c == b'W' && next == b'H') => {
+                code.push(c as char)
+            }
+            b'X' => {
+                if i == 0 {
+                    code.push('S');
+                } else {
+                    code.push_str("KS");
+                }
+            }
+            b'Z' => code.push('S'),
+            b'F' | b'J' | b'L' | b'M' | b'N' | b'R' => code.push(c as char),
+            _ => {}
+        }
+    }
+    code
+}
@@ -0,0 +1,482 @@
+use serde::Serialize;
+use specta::Type;
+
+/// Languages with a built-in command set
+pub const SUPPORTED_LANGUAGES: &[&str] = &["en", "de", "es", "fr"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    /// Punctuation that attaches to the previous word
+    Punctuation(&'static str),
+    /// A line or paragraph break
+    Break(&'static str),
+    Bullet,
+    CapsOn,
+    CapsOff,
+    /// Deletes the previous sentence
+    ScratchThat,
+    /// Types the following command's words instead of running it
+    Literal,
+}
+
+// Every language uses the same command ids so settings carry over when the
+// language changes.
+const COMMANDS: &[(&str, Action, &str)] = &[
+    ("comma", Action::Punctuation(","), "Inserts a comma"),
+    (
+        "period",
+        Action::Punctuation("."),
+        "Ends the sentence with a period",
+    ),
+    (
+        "question_mark",
+        Action::Punctuation("?"),
+        "Inserts a question mark",
+    ),
+    (
+        "exclamation_mark",
+        Action::Punctuation("!"),
+        "Inserts an exclamation mark",
+    ),
+    ("colon", Action::Punctuation(":"), "Inserts a colon"),
+    ("semicolon", Action::Punctuation(";"), "Inserts a semicolon"),
+    ("new_line", Action::Break("\n"), "Starts a new line"),
+    (
+        "new_paragraph",
+        Action::Break("\n\n"),
+        "Starts a new paragraph",
+    ),
+    (
+        "bullet_point",
+        Action::Bullet,
+        "Starts a bulleted list item",
+    ),
+    (
+        "caps_on",
+        Action::CapsOn,
+        "Types the following words in capitals",
+    ),
+    ("caps_off", Action::CapsOff, "Stops typing in capitals"),
+    (
+        "scratch_that",
+        Action::ScratchThat,
+        "Deletes the previous sentence",
+    ),
+    (
+        "literal",
+        Action::Literal,
+        "Types the next command as words",
+    ),
+];
+
+type Phrases = &'static [(&'static str, &'static [&'static str])];
+
+const ENGLISH: Phrases = &[
+    ("comma", &["comma"]),
+    ("period", &["period", "full stop"]),
+    ("question_mark", &["question mark"]),
+    (
+        "exclamation_mark",
+        &["exclamation mark", "exclamation point"],
+    ),
+    ("colon", &["colon"]),
+    ("semicolon", &["semicolon"]),
+    ("new_line", &["new line"]),
+    ("new_paragraph", &["new paragraph"]),
+    ("bullet_point", &["bullet point"]),
+    ("caps_on", &["all caps"]),
+    ("caps_off", &["end caps"]),
+    ("scratch_that", &["scratch that"]),
+    ("literal", &["literal"]),
+];
+
+const GERMAN: Phrases = &[
+    ("comma", &["komma"]),
+    ("period", &["punkt"]),
+    ("question_mark", &["fragezeichen"]),
+    ("exclamation_mark", &["ausrufezeichen"]),
+    ("colon", &["doppelpunkt"]),
+    ("semicolon", &["semikolon", "strichpunkt"]),
+    ("new_line", &["neue zeile"]),
+    ("new_paragraph", &["neuer absatz"]),
+    ("bullet_point", &["aufzählungspunkt"]),
+    ("caps_on", &["alles groß"]),
+    ("caps_off", &["ende groß"]),
+    ("scratch_that", &["streich das"]),
+    ("literal", &["wörtlich"]),
+];
+
+const SPANISH: Phrases = &[
+    ("comma", &["coma"]),
+    ("period", &["punto"]),
+    ("question_mark", &["signo de interrogación"]),
+    ("exclamation_mark", &["signo de exclamación"]),
+    ("colon", &["dos puntos"]),
+    ("semicolon", &["punto y coma"]),
+    ("new_line", &["nueva línea"]),
+    ("new_paragraph", &["nuevo párrafo"]),
+    ("bullet_point", &["viñeta"]),
+    ("caps_on", &["todo mayúsculas"]),
+    ("caps_off", &["fin mayúsculas"]),
+    ("scratch_that", &["borra eso"]),
+    ("literal", &["literal"]),
+];
+
+const FRENCH: Phrases = &[
+    ("comma", &["virgule"]),
+    ("period", &["point"]),
+    ("question_mark", &["point d'interrogation"]),
+    ("exclamation_mark", &["point d'exclamation"]),
+    ("colon", &["deux points", "deux-points"]),
+    ("semicolon", &["point-virgule"]),
+    ("new_line", &["à la ligne", "nouvelle ligne"]),
+    ("new_paragraph", &["nouveau paragraphe"]),
+    ("bullet_point", &["puce"]),
+    ("caps_on", &["tout en majuscules"]),
+    ("caps_off", &["fin majuscules"]),
+    ("scratch_that", &["efface ça"]),
+    ("literal", &["littéral"]),
+];
+
+/// A command as shown in settings
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct VoiceCommandInfo {
+    pub id: String,
+    /// What to say, in the command set's language
+    pub phrases: Vec<String>,
+    pub description: String,
+    pub enabled: bool,
+}
+
+/// Picks the command set for a language setting, where `auto` follows the
+/// transcription language. Automatic language detection falls back to
+/// English. Returns `None` if the language has no command set.
+pub fn resolve_language(setting: &str, selected_language: &str) -> Option<&'static str> {
+    let language = if setting == "auto" {
+        selected_language
+    } else {
+        setting
+    };
+    let base = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let base = if base == "auto" {
+        "en".to_string()
+    } else {
+        base
+    };
+
+    SUPPORTED_LANGUAGES
+        .iter()
+        .find(|supported| **supported == base)
+        .copied()
+}
+
+/// Lists a language's commands, marking the ones in `disabled` as off
+pub fn list_commands(language: &str, disabled: &[String]) -> Vec<VoiceCommandInfo> {
+    let Some(phrases) = phrases_for(language) else {
+        return Vec::new();
+    };
+
+    COMMANDS
+        .iter()
+        .map(|(id, _, description)| VoiceCommandInfo {
+            id: id.to_string(),
+            phrases: phrases
+                .iter()
+                .find(|(phrase_id, _)| phrase_id == id)
+                .map(|(_, phrases)| phrases.iter().map(|p| p.to_string()).collect())
+                .unwrap_or_default(),
+            description: description.to_string(),
+            enabled: !disabled.iter().any(|d| d == id),
+        })
+        .collect()
+}
+
+/// Interprets spoken punctuation and formatting commands in transcribed text
+///
+/// Commands are matched case-insensitively on whole words, ignoring
+/// punctuation the engine put around them, and the longest matching phrase
+/// wins. Punctuation commands replace any punctuation the engine already
+/// added at that point.
+///
+/// # Arguments
+/// * `text` - The transcribed text
+/// * `language` - A language from [`SUPPORTED_LANGUAGES`]; other languages leave the text unchanged
+/// * `disabled` - Ids of commands to leave as plain words
+///
+/// # Returns
+/// The text with the commands carried out
+pub fn apply_voice_commands(text: &str, language: &str, disabled: &[String]) -> String {
+    let Some(phrases) = phrases_for(language) else {
+        return text.to_string();
+    };
+
+    let mut commands: Vec<(Vec<String>, Action)> = phrases
+        .iter()
+        .filter(|(id, _)| !disabled.iter().any(|d| d == id))
+        .filter_map(|(id, phrases)| {
+            let action = COMMANDS
+                .iter()
+                .find(|(command_id, _, _)| command_id == id)?
+                .1;
+            Some(phrases.iter().map(move |phrase| {
+                let words = phrase.split_whitespace().map(str::to_string).collect();
+                (words, action)
+            }))
+        })
+        .flatten()
+        .collect();
+    // Longest first, so "punto y coma" wins over "punto"
+    commands.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = tokens.iter().map(|token| normalize(token)).collect();
+
+    let mut output = Output::default();
+    let mut i = 0;
+    while i < tokens.len() {
+        match match_command(&commands, &normalized[i..]) {
+            Some((len, Action::Literal)) => {
+                i += len;
+                // The next command's words, or a single word, are typed as is
+                let literal_len = match_command(&commands, &normalized[i..])
+                    .map_or(1, |(len, _)| len)
+                    .min(tokens.len() - i);
+                for token in &tokens[i..i + literal_len] {
+                    output.word(token);
+                }
+                i += literal_len;
+            }
+            Some((len, action)) => {
+                output.command(action);
+                i += len;
+            }
+            None => {
+                output.word(tokens[i]);
+                i += 1;
+            }
+        }
+    }
+
+    output.text
+}
+
+fn phrases_for(language: &str) -> Option<Phrases> {
+    match language {
+        "en" => Some(ENGLISH),
+        "de" => Some(GERMAN),
+        "es" => Some(SPANISH),
+        "fr" => Some(FRENCH),
+        _ => None,
+    }
+}
+
+fn match_command(commands: &[(Vec<String>, Action)], words: &[String]) -> Option<(usize, Action)> {
+    commands
+        .iter()
+        .find(|(phrase, _)| words.len() >= phrase.len() && words[..phrase.len()] == phrase[..])
+        .map(|(phrase, action)| (phrase.len(), *action))
+}
+
+/// Lowercases a token and strips the punctuation around it
+fn normalize(token: &str) -> String {
+    token
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+#[derive(Default)]
+struct Output {
+    text: String,
+    caps: bool,
+    capitalize_next: bool,
+}
+
+impl Output {
+    fn word(&mut self, word: &str) {
+        if !self.text.is_empty() && !self.text.ends_with(char::is_whitespace) {
+            self.text.push(' ');
+        }
+        if self.caps {
+            self.text.push_str(&word.to_uppercase());
+        } else if self.capitalize_next {
+            self.text.push_str(&capitalize(word));
+        } else {
+            self.text.push_str(word);
+        }
+        self.capitalize_next = false;
+    }
+
+    fn command(&mut self, action: Action) {
+        match action {
+            Action::Punctuation(mark) => {
+                self.trim_spaces();
+                let kept = self
+                    .text
+                    .trim_end_matches([',', '.', ';', ':', '!', '?'])
+                    .len();
+                self.text.truncate(kept);
+                self.text.push_str(mark);
+                self.capitalize_next = matches!(mark, "." | "?" | "!");
+            }
+            Action::Break(separator) => {
+                self.trim_spaces();
+                if !self.text.is_empty() {
+                    self.text.push_str(separator);
+                }
+                self.capitalize_next = true;
+            }
+            Action::Bullet => {
+                self.trim_spaces();
+                if !self.text.is_empty() && !self.text.ends_with('\n') {
+                    self.text.push('\n');
+                }
+                self.text.push_str("- ");
+                self.capitalize_next = true;
+            }
+            Action::CapsOn => self.caps = true,
+            Action::CapsOff => self.caps = false,
+            Action::ScratchThat => self.scratch_sentence(),
+            // Handled while matching, as it needs the following words
+            Action::Literal => {}
+        }
+    }
+
+    /// Removes the sentence (or list item) before the command
+    fn scratch_sentence(&mut self) {
+        let sentence = self.text.trim_end().trim_end_matches(['.', '!', '?']);
+        let start = sentence.rfind(['.', '!', '?', '\n']).map_or(0, |i| i + 1);
+        self.text.truncate(start);
+        self.trim_spaces();
+        self.capitalize_next = self.text.is_empty() || self.text.ends_with(['.', '!', '?', '\n']);
+    }
+
+    fn trim_spaces(&mut self) {
+        let kept = self.text.trim_end_matches([' ', '\t']).len();
+        self.text.truncate(kept);
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(text: &str) -> String {
+        apply_voice_commands(text, "en", &[])
+    }
+
+    #[test]
+    fn test_punctuation_commands() {
+        assert_eq!(
+            apply("hello comma how are you question mark"),
+            "hello, how are you?"
+        );
+        assert_eq!(apply("done period next one"), "done. Next one");
+    }
+
+    #[test]
+    fn test_punctuation_replaces_engine_punctuation() {
+        assert_eq!(apply("Hello, comma, world."), "Hello, world.");
+        assert_eq!(apply("It works. Period."), "It works.");
+    }
+
+    #[test]
+    fn test_line_and_paragraph_breaks() {
+        assert_eq!(
+            apply("Dear Sam, new paragraph. thanks for writing new line Alex"),
+            "Dear Sam,\n\nThanks for writing\nAlex"
+        );
+    }
+
+    #[test]
+    fn test_bullet_points() {
+        assert_eq!(
+            apply("shopping list colon bullet point milk bullet point eggs"),
+            "shopping list:\n- Milk\n- Eggs"
+        );
+    }
+
+    #[test]
+    fn test_all_caps() {
+        assert_eq!(
+            apply("this is all caps very important end caps okay"),
+            "this is VERY IMPORTANT okay"
+        );
+    }
+
+    #[test]
+    fn test_scratch_that() {
+        assert_eq!(
+            apply("First sentence. Second sentence, scratch that. Third sentence."),
+            "First sentence. Third sentence."
+        );
+        assert_eq!(apply("never mind scratch that"), "");
+        assert_eq!(
+            apply("bullet point one bullet point two scratch that"),
+            "- One\n"
+        );
+    }
+
+    #[test]
+    fn test_literal() {
+        assert_eq!(apply("the literal period is over"), "the period is over");
+        assert_eq!(apply("type literal new line here"), "type new line here");
+    }
+
+    #[test]
+    fn test_disabled_commands_stay_words() {
+        let disabled = vec!["period".to_string()];
+        assert_eq!(
+            apply_voice_commands("a period of time comma really", "en", &disabled),
+            "a period of time, really"
+        );
+    }
+
+    #[test]
+    fn test_other_languages() {
+        assert_eq!(
+            apply_voice_commands("Hallo Komma wie geht's Fragezeichen", "de", &[]),
+            "Hallo, wie geht's?"
+        );
+        assert_eq!(
+            apply_voice_commands("uno punto y coma dos punto", "es", &[]),
+            "uno; dos."
+        );
+        assert_eq!(
+            apply_voice_commands("bonjour virgule ça va point d'interrogation", "fr", &[]),
+            "bonjour, ça va?"
+        );
+        assert_eq!(apply_voice_commands("ciao comma", "it", &[]), "ciao comma");
+    }
+
+    #[test]
+    fn test_resolve_language() {
+        assert_eq!(resolve_language("auto", "de"), Some("de"));
+        assert_eq!(resolve_language("auto", "auto"), Some("en"));
+        assert_eq!(resolve_language("auto", "zh-Hans"), None);
+        assert_eq!(resolve_language("es", "en"), Some("es"));
+    }
+
+    #[test]
+    fn test_list_commands() {
+        let disabled = vec!["literal".to_string()];
+        let commands = list_commands("en", &disabled);
+        assert_eq!(commands.len(), COMMANDS.len());
+        let period = commands.iter().find(|c| c.id == "period").unwrap();
+        assert_eq!(period.phrases, vec!["period", "full stop"]);
+        assert!(period.enabled);
+        assert!(!commands.iter().find(|c| c.id == "literal").unwrap().enabled);
+        assert!(list_commands("xx", &[]).is_empty());
+    }
+}
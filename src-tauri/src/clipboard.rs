@@ -1,5 +1,5 @@
 use crate::input::{self, EnigoState};
-use crate::settings::{get_settings, ClipboardHandling, PasteMethod};
+use crate::settings::{AppSettings, ClipboardHandling, PasteMethod};
 use enigo::Enigo;
 use log::info;
 use tauri::{AppHandle, Manager};
@@ -132,8 +132,7 @@ fn send_paste_via_dotool(paste_method: &PasteMethod) -> Result<(), String> {
     Ok(())
 }
 
-pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
-    let settings = get_settings(&app_handle);
+pub fn paste(text: String, app_handle: AppHandle, settings: &AppSettings) -> Result<(), String> {
     let paste_method = settings.paste_method;
 
     // Append trailing space if setting is enabled
@@ -1,6 +1,6 @@
 use crate::audio_feedback;
 use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
-use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
+use crate::managers::audio::{AudioRecordingManager, HandsFreeState, MicrophoneMode};
 use crate::settings::{get_settings, write_settings};
 use log::warn;
 use serde::{Deserialize, Serialize};
@@ -200,3 +200,20 @@ pub fn is_recording(app: AppHandle) -> bool {
     let audio_manager = app.state::<Arc<AudioRecordingManager>>();
     audio_manager.is_recording()
 }
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_hands_free_state(app: AppHandle) -> HandsFreeState {
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    audio_manager.hands_free_state()
+}
+
+/// Pauses or resumes hands-free listening, returning whether it is paused
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_hands_free_paused(app: AppHandle) -> Result<bool, String> {
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    audio_manager
+        .toggle_hands_free_paused()
+        .map_err(|e| e.to_string())
+}
@@ -1,4 +1,16 @@
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::actions::process_transcription;
+use crate::audio_toolkit::corrections::CorrectionKind;
+use crate::audio_toolkit::{read_audio_file, ReplacementKind, ReplacementRule};
+use crate::export::{self, ExportArchive, ExportEntry, ExportFormat, ExportSelection};
+use crate::managers::history::{
+    CorrectionProposal, HistoryEntry, HistoryManager, HistoryPage, HistorySearchResult,
+    TranscriptionRevision,
+};
+use crate::managers::transcription::{TranscriptSegment, Transcription, TranscriptionManager};
+use crate::settings::{
+    get_replacement_rules, get_settings, write_replacement_rules, write_settings,
+};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -14,6 +26,35 @@ pub async fn get_history_entries(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_entries_page(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    limit: i64,
+    offset: i64,
+) -> Result<HistoryPage, String> {
+    history_manager
+        .get_history_entries_page(limit, offset)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    query: String,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<HistorySearchResult>, String> {
+    history_manager
+        .search_history(&query, limit, offset)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn toggle_history_entry_saved(
@@ -27,6 +68,196 @@ pub async fn toggle_history_entry_saved(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_entry_segments(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Vec<TranscriptSegment>, String> {
+    history_manager
+        .get_entry_segments(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_entry_revisions(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Vec<TranscriptionRevision>, String> {
+    history_manager
+        .get_revisions(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Runs an entry's saved recording through transcription and post-processing
+/// again, optionally with a different model, language or prompt, and stores
+/// the result as a new revision of the entry.
+#[tauri::command]
+#[specta::specta]
+pub async fn retranscribe_history_entry(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    id: i64,
+    model_id: Option<String>,
+    language: Option<String>,
+    prompt_id: Option<String>,
+) -> Result<TranscriptionRevision, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry not found: {}", id))?;
+    let audio_path = history_manager.get_audio_file_path(&entry.file_name);
+    if !audio_path.exists() {
+        return Err(format!("Recording not found: {}", entry.file_name));
+    }
+
+    let mut settings = get_settings(&app);
+    if let Some(language) = language {
+        settings.selected_language = language;
+    }
+    if let Some(prompt_id) = prompt_id {
+        if !settings
+            .post_process_prompts
+            .iter()
+            .any(|p| p.id == prompt_id)
+        {
+            return Err(format!("Post-processing prompt not found: {}", prompt_id));
+        }
+        settings.post_process_enabled = true;
+        settings.post_process_selected_prompt_id = Some(prompt_id);
+    }
+    let model_id = model_id.unwrap_or_else(|| settings.selected_model.clone());
+
+    let transcription: Transcription = {
+        let tm = Arc::clone(&transcription_manager);
+        let settings = settings.clone();
+        let model_id = model_id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let samples = read_audio_file(&audio_path)?;
+            tm.transcribe_with_model(samples, &model_id, &settings)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?
+    };
+
+    let processed =
+        process_transcription(&settings, &get_replacement_rules(&app), &transcription.text).await;
+
+    history_manager
+        .save_revision(
+            id,
+            model_id,
+            settings.selected_language.clone(),
+            transcription,
+            processed,
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Saves the user's correction of an entry's text, or reverts the entry to
+/// its original text when `text` is `None`. Words the edit replaced are
+/// collected as correction proposals.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_history_entry_text(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    text: Option<String>,
+) -> Result<(), String> {
+    history_manager
+        .update_entry_text(id, text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_correction_proposals(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<Vec<CorrectionProposal>, String> {
+    history_manager
+        .get_correction_proposals()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Learns a correction as a custom word or an exact replacement rule,
+/// defaulting to the proposal's suggested kind
+#[tauri::command]
+#[specta::specta]
+pub async fn accept_correction_proposal(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    kind: Option<CorrectionKind>,
+) -> Result<(), String> {
+    let proposal = history_manager
+        .get_correction_proposal(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Correction proposal {} not found", id))?;
+
+    match kind.unwrap_or(proposal.suggested_kind) {
+        CorrectionKind::CustomWord => {
+            // Fuzzy matching alone won't catch what was heard, so spell out
+            // the spoken form
+            let word = if proposal.suggested_kind == CorrectionKind::CustomWord {
+                proposal.corrected.clone()
+            } else {
+                format!("{} -> {}", proposal.heard, proposal.corrected)
+            };
+            let mut settings = get_settings(&app);
+            if !settings.custom_words.contains(&word) {
+                settings.custom_words.push(word);
+                write_settings(&app, settings);
+            }
+        }
+        CorrectionKind::Replacement => {
+            let rule = ReplacementRule {
+                id: format!("correction_{}", proposal.id),
+                enabled: true,
+                kind: ReplacementKind::Exact,
+                pattern: proposal.heard.clone(),
+                replacement: proposal.corrected.clone(),
+                case_sensitive: false,
+            };
+            let mut rules = get_replacement_rules(&app);
+            if !rules.iter().any(|existing| existing.id == rule.id) {
+                rules.push(rule);
+                write_replacement_rules(&app, &rules);
+            }
+        }
+    }
+
+    history_manager
+        .resolve_correction_proposal(id, true)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn dismiss_correction_proposal(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<(), String> {
+    history_manager
+        .resolve_correction_proposal(id, false)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_audio_file_path(
@@ -53,6 +284,61 @@ pub async fn delete_history_entry(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn export_history(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    selection: ExportSelection,
+    format: ExportFormat,
+    archive: ExportArchive,
+    destination_dir: String,
+) -> Result<Vec<String>, String> {
+    let entries = match selection {
+        ExportSelection::Entry { id } => history_manager
+            .get_entry_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect(),
+        ExportSelection::DateRange { from, to } => history_manager
+            .get_entries_in_range(from, to)
+            .await
+            .map_err(|e| e.to_string())?,
+        ExportSelection::Saved => history_manager
+            .get_saved_entries()
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+
+    let mut export_entries = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let segments = history_manager
+            .get_entry_segments(entry.id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let audio_path = history_manager.get_audio_file_path(&entry.file_name);
+        export_entries.push(ExportEntry {
+            entry,
+            segments,
+            audio_path,
+        });
+    }
+
+    let written = export::export_entries(
+        &export_entries,
+        format,
+        archive,
+        &PathBuf::from(destination_dir),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(written
+        .into_iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn update_history_limit(
@@ -1,7 +1,9 @@
 pub mod audio;
 pub mod history;
 pub mod models;
+pub mod replacements;
 pub mod transcription;
+pub mod voice_commands;
 
 use crate::settings::{get_settings, write_settings, AppSettings, LogLevel};
 use crate::utils::cancel_current_operation;
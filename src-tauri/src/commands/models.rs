@@ -1,6 +1,9 @@
-use crate::managers::model::{ModelInfo, ModelManager};
-use crate::managers::transcription::TranscriptionManager;
+use crate::managers::model::{
+    CustomModel, DownloadProgress, EngineType, ModelInfo, ModelManager, ModelVerification,
+};
+use crate::managers::transcription::{BenchmarkReport, TranscriptionManager};
 use crate::settings::{get_settings, write_settings};
+use std::path::Path;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -44,6 +47,128 @@ pub async fn delete_model(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<ModelVerification, String> {
+    // Hashing a large model takes a while, so keep it off the async runtime
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || model_manager.verify_model(&model_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn repair_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    if transcription_manager.get_current_model().as_deref() == Some(model_id.as_str()) {
+        transcription_manager
+            .unload_model()
+            .map_err(|e| e.to_string())?;
+    }
+
+    model_manager
+        .repair_model(&model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    let unload_if_loaded = || -> Result<(), String> {
+        if transcription_manager.get_current_model().as_deref() == Some(model_id.as_str()) {
+            transcription_manager
+                .unload_model()
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    };
+
+    // The installed files stay usable until the new version is swapped in,
+    // but they can't be replaced while loaded
+    unload_if_loaded()?;
+    model_manager
+        .update_model(&model_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    // A recording during the download may have loaded the old version again
+    unload_if_loaded()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn refresh_model_catalog(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<bool, String> {
+    model_manager
+        .refresh_catalog()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn add_custom_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model: CustomModel,
+) -> Result<ModelInfo, String> {
+    model_manager
+        .add_custom_model(model)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_custom_model(
+    app_handle: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    if transcription_manager.get_current_model().as_deref() == Some(model_id.as_str()) {
+        transcription_manager
+            .unload_model()
+            .map_err(|e| e.to_string())?;
+    }
+
+    model_manager
+        .remove_custom_model(&model_id)
+        .map_err(|e| e.to_string())?;
+
+    let mut settings = get_settings(&app_handle);
+    if settings.selected_model == model_id {
+        settings.selected_model = String::new();
+        write_settings(&app_handle, settings);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn import_model_archive(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+    path: String,
+) -> Result<(), String> {
+    model_manager
+        .import_model_archive(&model_id, Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn set_active_model(
@@ -102,20 +227,35 @@ pub async fn is_model_loading(
 #[tauri::command]
 #[specta::specta]
 pub async fn has_any_models_available(
+    app_handle: AppHandle,
     model_manager: State<'_, Arc<ModelManager>>,
 ) -> Result<bool, String> {
     let models = model_manager.get_available_models();
-    Ok(models.iter().any(|m| m.is_downloaded))
+    let settings = get_settings(&app_handle);
+    Ok(models
+        .iter()
+        .any(|m| is_usable(m, &settings.selected_model)))
 }
 
 #[tauri::command]
 #[specta::specta]
 pub async fn has_any_models_or_downloads(
+    app_handle: AppHandle,
     model_manager: State<'_, Arc<ModelManager>>,
 ) -> Result<bool, String> {
     let models = model_manager.get_available_models();
+    let settings = get_settings(&app_handle);
     // Return true if any models are downloaded OR if any downloads are in progress
-    Ok(models.iter().any(|m| m.is_downloaded))
+    Ok(models
+        .iter()
+        .any(|m| is_usable(m, &settings.selected_model)))
+}
+
+/// The remote model is always "downloaded", so it only counts once the user
+/// has chosen it; otherwise first-run onboarding would be skipped.
+fn is_usable(model: &ModelInfo, selected_model: &str) -> bool {
+    model.is_downloaded
+        && (!matches!(model.engine_type, EngineType::Remote) || model.id == selected_model)
 }
 
 #[tauri::command]
@@ -131,7 +271,87 @@ pub async fn cancel_download(
 
 #[tauri::command]
 #[specta::specta]
-pub async fn get_recommended_first_model() -> Result<String, String> {
-    // Recommend Parakeet V3 model for first-time users - fastest and most accurate
-    Ok("parakeet-tdt-0.6b-v3".to_string())
+pub async fn pause_download(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    model_manager
+        .pause_download(&model_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_download(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    model_manager
+        .resume_download(&model_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_download_queue(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<DownloadProgress>, String> {
+    Ok(model_manager.get_download_queue())
+}
+
+/// Benchmarks the installed models on this machine. With `auto_select`, the
+/// recommended model is loaded and selected.
+#[tauri::command]
+#[specta::specta]
+pub async fn benchmark_models(
+    app_handle: AppHandle,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    auto_select: bool,
+) -> Result<BenchmarkReport, String> {
+    // Loading and running every model takes a while
+    let manager = transcription_manager.inner().clone();
+    let report = tauri::async_runtime::spawn_blocking(move || manager.benchmark_models())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    if auto_select {
+        let mut settings = get_settings(&app_handle);
+        if let Some(model_id) = &report.recommended_model {
+            if &settings.selected_model != model_id {
+                transcription_manager
+                    .load_model(model_id)
+                    .map_err(|e| e.to_string())?;
+                settings.selected_model = model_id.clone();
+                write_settings(&app_handle, settings);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_model_benchmarks(
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<BenchmarkReport, String> {
+    transcription_manager
+        .get_benchmark_report()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_recommended_first_model(
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<String, String> {
+    // Prefer what the benchmarks measured on this machine, if anything was
+    let benchmarked = transcription_manager
+        .get_benchmark_report()
+        .ok()
+        .and_then(|report| report.recommended_model);
+
+    // Otherwise recommend Parakeet V3 for first-time users - fastest and most accurate
+    Ok(benchmarked.unwrap_or_else(|| "parakeet-tdt-0.6b-v3".to_string()))
 }
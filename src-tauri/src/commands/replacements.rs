@@ -0,0 +1,61 @@
+use crate::audio_toolkit::replacements::{self, ReplacementRule};
+use crate::settings::{get_replacement_rules as read_rules, write_replacement_rules};
+use tauri::AppHandle;
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_replacement_rules(app: AppHandle) -> Result<Vec<ReplacementRule>, String> {
+    Ok(read_rules(&app))
+}
+
+/// Replaces every rule. The order of `rules` is the order they are applied in.
+#[tauri::command]
+#[specta::specta]
+pub fn set_replacement_rules(app: AppHandle, rules: Vec<ReplacementRule>) -> Result<(), String> {
+    replacements::validate_rules(&rules)?;
+    write_replacement_rules(&app, &rules);
+    Ok(())
+}
+
+/// Imports rules exported from Handy. With `merge`, imported rules replace
+/// existing ones with the same id and the rest are appended; otherwise they
+/// replace all rules.
+#[tauri::command]
+#[specta::specta]
+pub fn import_replacement_rules(
+    app: AppHandle,
+    json: String,
+    merge: bool,
+) -> Result<Vec<ReplacementRule>, String> {
+    let imported = replacements::import_rules(&json)?;
+
+    let rules = if merge {
+        let mut rules = read_rules(&app);
+        for rule in imported {
+            match rules.iter_mut().find(|existing| existing.id == rule.id) {
+                Some(existing) => *existing = rule,
+                None => rules.push(rule),
+            }
+        }
+        rules
+    } else {
+        imported
+    };
+
+    write_replacement_rules(&app, &rules);
+    Ok(rules)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn export_replacement_rules(app: AppHandle) -> Result<String, String> {
+    Ok(replacements::export_rules(&read_rules(&app)))
+}
+
+/// Shows what the current rules make of `text`, for trying rules out
+#[tauri::command]
+#[specta::specta]
+pub fn preview_replacements(app: AppHandle, text: String) -> Result<String, String> {
+    let replacer = replacements::Replacer::new(&read_rules(&app))?;
+    Ok(replacer.apply(&text))
+}
@@ -0,0 +1,48 @@
+use crate::audio_toolkit::voice_commands::{self, VoiceCommandInfo};
+use crate::settings::{get_settings, write_settings};
+use serde::Serialize;
+use specta::Type;
+use tauri::AppHandle;
+
+#[derive(Serialize, Type)]
+pub struct VoiceCommandSet {
+    /// The language the commands are spoken in, or `None` if the current
+    /// language has no command set
+    pub language: Option<String>,
+    pub commands: Vec<VoiceCommandInfo>,
+}
+
+/// Returns the commands for the configured language, as they would be
+/// recognized in the next transcription
+#[tauri::command]
+#[specta::specta]
+pub fn get_voice_commands(app: AppHandle) -> Result<VoiceCommandSet, String> {
+    let settings = get_settings(&app);
+    let language = voice_commands::resolve_language(
+        &settings.voice_commands_language,
+        &settings.selected_language,
+    );
+
+    Ok(VoiceCommandSet {
+        language: language.map(str::to_string),
+        commands: language
+            .map(|language| {
+                voice_commands::list_commands(language, &settings.voice_commands_disabled)
+            })
+            .unwrap_or_default(),
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_voice_command_enabled(app: AppHandle, id: String, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings
+        .voice_commands_disabled
+        .retain(|disabled| disabled != &id);
+    if !enabled {
+        settings.voice_commands_disabled.push(id);
+    }
+    write_settings(&app, settings);
+    Ok(())
+}
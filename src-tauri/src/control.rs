@@ -0,0 +1,161 @@
+use crate::actions::action_for_binding;
+use crate::managers::transcription::TranscriptionManager;
+use crate::tray::TrayIconState;
+use crate::ManagedToggleState;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingCommand {
+    Start,
+    Stop,
+    Toggle,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingState {
+    Idle,
+    Recording,
+    Transcribing,
+}
+
+impl From<&TrayIconState> for RecordingState {
+    fn from(state: &TrayIconState) -> Self {
+        match state {
+            TrayIconState::Idle => RecordingState::Idle,
+            TrayIconState::Recording => RecordingState::Recording,
+            TrayIconState::Transcribing => RecordingState::Transcribing,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct ControlStatus {
+    pub state: RecordingState,
+    pub active_bindings: Vec<String>,
+    pub model: Option<String>,
+    pub model_loaded: bool,
+}
+
+static RECORDING_STATE: Mutex<RecordingState> = Mutex::new(RecordingState::Idle);
+
+/// Records the app's recording state and emits `recording-state-changed` when
+/// it changes
+pub fn set_recording_state(app: &AppHandle, state: RecordingState) {
+    {
+        let mut current = RECORDING_STATE.lock().unwrap();
+        if *current == state {
+            return;
+        }
+        *current = state;
+    }
+    debug!("Recording state changed to {:?}", state);
+    let _ = app.emit("recording-state-changed", state);
+}
+
+pub fn recording_state() -> RecordingState {
+    *RECORDING_STATE.lock().unwrap()
+}
+
+/// Starts, stops or toggles a binding the way its shortcut does in toggle
+/// mode. `trigger` names the source for logging (e.g. `SIGUSR2`). Returns
+/// whether the binding is active afterwards.
+pub fn trigger_binding(
+    app: &AppHandle,
+    binding_id: &str,
+    trigger: &str,
+    command: BindingCommand,
+) -> Result<bool, String> {
+    let action = action_for_binding(binding_id)
+        .ok_or_else(|| format!("No action defined for binding ID '{}'", binding_id))?;
+
+    // Determine action and update state while holding the lock,
+    // but RELEASE the lock before calling the action to avoid deadlocks.
+    // (Actions may need to acquire the lock themselves, e.g., cancel_current_operation)
+    let should_start = {
+        let toggle_state_manager = app.state::<ManagedToggleState>();
+        let mut states = toggle_state_manager
+            .lock()
+            .map_err(|e| format!("Failed to lock toggle state manager: {}", e))?;
+
+        let is_currently_active = states
+            .active_toggles
+            .entry(binding_id.to_string())
+            .or_insert(false);
+
+        let should_start = match command {
+            BindingCommand::Start => true,
+            BindingCommand::Stop => false,
+            BindingCommand::Toggle => !*is_currently_active,
+        };
+        if should_start == *is_currently_active {
+            debug!(
+                "{}: binding '{}' is already {}",
+                trigger,
+                binding_id,
+                if should_start { "active" } else { "inactive" }
+            );
+            return Ok(should_start);
+        }
+        *is_currently_active = should_start;
+        should_start
+    }; // Lock released here
+
+    if should_start {
+        debug!("{}: Starting binding '{}'", trigger, binding_id);
+        action.start(app, binding_id, trigger);
+    } else {
+        debug!("{}: Stopping binding '{}'", trigger, binding_id);
+        action.stop(app, binding_id, trigger);
+    }
+
+    Ok(should_start)
+}
+
+/// Stops a binding as if its shortcut had been released, whatever the
+/// shortcut mode, so the next press starts it again in toggle mode
+pub fn release_binding(app: &AppHandle, binding_id: &str, trigger: &str) -> Result<(), String> {
+    let action = action_for_binding(binding_id)
+        .ok_or_else(|| format!("No action defined for binding ID '{}'", binding_id))?;
+
+    {
+        let toggle_state_manager = app.state::<ManagedToggleState>();
+        let mut states = toggle_state_manager
+            .lock()
+            .map_err(|e| format!("Failed to lock toggle state manager: {}", e))?;
+        states.active_toggles.insert(binding_id.to_string(), false);
+    }
+
+    debug!("{}: Releasing binding '{}'", trigger, binding_id);
+    action.stop(app, binding_id, trigger);
+    Ok(())
+}
+
+pub fn status(app: &AppHandle) -> ControlStatus {
+    let mut active_bindings: Vec<String> = match app.state::<ManagedToggleState>().lock() {
+        Ok(states) => states
+            .active_toggles
+            .iter()
+            .filter(|(_, active)| **active)
+            .map(|(id, _)| id.clone())
+            .collect(),
+        Err(e) => {
+            warn!("Failed to lock toggle state manager: {}", e);
+            Vec::new()
+        }
+    };
+    active_bindings.sort();
+
+    let tm = app.state::<Arc<TranscriptionManager>>();
+    ControlStatus {
+        state: recording_state(),
+        active_bindings,
+        model: tm.get_current_model(),
+        model_loaded: tm.is_model_loaded(),
+    }
+}
@@ -0,0 +1,534 @@
+use crate::audio_toolkit::subtitles::{to_srt, to_vtt, SubtitleCue};
+use crate::managers::history::HistoryEntry;
+use crate::managers::transcription::TranscriptSegment;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Used to size a single cue when an entry has neither segments nor audio.
+const FALLBACK_WORDS_PER_SECOND: f32 = 2.5;
+
+/// Which history entries to export
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportSelection {
+    Entry {
+        id: i64,
+    },
+    /// Unix timestamps in seconds, inclusive
+    DateRange {
+        from: i64,
+        to: i64,
+    },
+    Saved,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Markdown,
+    Jsonl,
+    Csv,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportArchive {
+    None,
+    Zip,
+    Tar,
+}
+
+/// A history entry together with everything needed to export it
+pub struct ExportEntry {
+    pub entry: HistoryEntry,
+    pub segments: Vec<TranscriptSegment>,
+    pub audio_path: PathBuf,
+}
+
+impl ExportEntry {
+    /// The text the user ended up with: their edit, or else the pasted or
+    /// post-processed text if available
+    fn final_text(&self) -> &str {
+        self.entry
+            .edited_text
+            .as_deref()
+            .or(self.entry.final_text.as_deref())
+            .or(self.entry.post_processed_text.as_deref())
+            .unwrap_or(&self.entry.transcription_text)
+    }
+}
+
+#[derive(Serialize)]
+struct JsonlRecord<'a> {
+    id: i64,
+    timestamp: i64,
+    date: String,
+    title: &'a str,
+    saved: bool,
+    file_name: &'a str,
+    transcription_text: &'a str,
+    post_processed_text: Option<&'a str>,
+    post_process_prompt: Option<&'a str>,
+    final_text: Option<&'a str>,
+    edited_text: Option<&'a str>,
+    segments: &'a [TranscriptSegment],
+}
+
+/// Writes the entries to `destination_dir` in the given format, optionally
+/// bundling the documents and referenced recordings into an archive.
+/// Returns the paths of the files written.
+pub fn export_entries(
+    entries: &[ExportEntry],
+    format: ExportFormat,
+    archive: ExportArchive,
+    destination_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("No history entries match the selection"));
+    }
+
+    fs::create_dir_all(destination_dir)?;
+
+    let base_name = format!("handy-export-{}", Local::now().format("%Y%m%d-%H%M%S"));
+    let documents = render_documents(entries, format, &base_name)?;
+    debug!(
+        "Rendered {} export document(s) for {} entries",
+        documents.len(),
+        entries.len()
+    );
+
+    let written = match archive {
+        ExportArchive::None => {
+            let mut written = Vec::with_capacity(documents.len());
+            for (name, contents) in &documents {
+                let path = destination_dir.join(name);
+                fs::write(&path, contents)?;
+                written.push(path);
+            }
+            written
+        }
+        ExportArchive::Zip => {
+            let path = destination_dir.join(format!("{}.zip", base_name));
+            write_zip(&path, &documents, entries)?;
+            vec![path]
+        }
+        ExportArchive::Tar => {
+            let path = destination_dir.join(format!("{}.tar", base_name));
+            write_tar(&path, &documents, entries)?;
+            vec![path]
+        }
+    };
+
+    Ok(written)
+}
+
+/// Renders the export as `(file name, contents)` pairs. Subtitle formats get
+/// one file per recording so the timings line up with its WAV file; the other
+/// formats produce a single document.
+fn render_documents(
+    entries: &[ExportEntry],
+    format: ExportFormat,
+    base_name: &str,
+) -> Result<Vec<(String, String)>> {
+    let documents = match format {
+        ExportFormat::Srt | ExportFormat::Vtt => entries
+            .iter()
+            .map(|entry| {
+                let cues = subtitle_cues(entry);
+                let stem = Path::new(&entry.entry.file_name)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("handy")
+                    .to_string();
+                if format == ExportFormat::Srt {
+                    (format!("{}.srt", stem), to_srt(&cues))
+                } else {
+                    (format!("{}.vtt", stem), to_vtt(&cues))
+                }
+            })
+            .collect(),
+        ExportFormat::Markdown => vec![(format!("{}.md", base_name), to_markdown(entries))],
+        ExportFormat::Jsonl => vec![(format!("{}.jsonl", base_name), to_jsonl(entries)?)],
+        ExportFormat::Csv => vec![(format!("{}.csv", base_name), to_csv(entries))],
+    };
+
+    Ok(documents)
+}
+
+/// Segment cues carry the raw transcript, so they are only used while the
+/// entry still reads the same. An entry whose text was post-processed,
+/// rewritten or edited gets a single cue with its final text, like every other
+/// format exports.
+fn subtitle_cues(entry: &ExportEntry) -> Vec<SubtitleCue> {
+    if !entry.segments.is_empty() && entry.final_text() == entry.entry.transcription_text {
+        return entry
+            .segments
+            .iter()
+            .map(|segment| SubtitleCue {
+                start: segment.start,
+                end: segment.end,
+                text: segment.text.clone(),
+            })
+            .collect();
+    }
+
+    // No usable timings, so show the whole text for the length of the recording
+    let text = entry.final_text();
+    let end = wav_duration(&entry.audio_path).unwrap_or_else(|| {
+        (text.split_whitespace().count() as f32 / FALLBACK_WORDS_PER_SECOND).max(1.0)
+    });
+    vec![SubtitleCue {
+        start: 0.0,
+        end,
+        text: text.to_string(),
+    }]
+}
+
+fn wav_duration(path: &Path) -> Option<f32> {
+    let reader = hound::WavReader::open(path).ok()?;
+    let spec = reader.spec();
+    Some(reader.duration() as f32 / spec.sample_rate as f32)
+}
+
+fn local_datetime(timestamp: i64) -> Option<DateTime<Local>> {
+    DateTime::from_timestamp(timestamp, 0).map(|utc| utc.with_timezone(&Local))
+}
+
+fn to_markdown(entries: &[ExportEntry]) -> String {
+    let mut out = String::from("# Handy Transcripts\n");
+    let mut current_day = String::new();
+
+    for entry in entries {
+        let datetime = local_datetime(entry.entry.timestamp);
+        let day = datetime
+            .map(|dt| dt.format("%B %e, %Y").to_string())
+            .unwrap_or_else(|| "Unknown date".to_string());
+        if day != current_day {
+            out.push_str(&format!("\n## {}\n", day));
+            current_day = day;
+        }
+
+        let time = datetime
+            .map(|dt| dt.format("%l:%M%p").to_string())
+            .unwrap_or_default();
+        out.push_str(&format!("\n### {}\n\n", time.trim()));
+        out.push_str(entry.final_text().trim());
+        out.push('\n');
+    }
+
+    out
+}
+
+fn to_jsonl(entries: &[ExportEntry]) -> Result<String> {
+    let mut out = String::new();
+    for entry in entries {
+        let record = JsonlRecord {
+            id: entry.entry.id,
+            timestamp: entry.entry.timestamp,
+            date: local_datetime(entry.entry.timestamp)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            title: &entry.entry.title,
+            saved: entry.entry.saved,
+            file_name: &entry.entry.file_name,
+            transcription_text: &entry.entry.transcription_text,
+            post_processed_text: entry.entry.post_processed_text.as_deref(),
+            post_process_prompt: entry.entry.post_process_prompt.as_deref(),
+            final_text: entry.entry.final_text.as_deref(),
+            edited_text: entry.entry.edited_text.as_deref(),
+            segments: &entry.segments,
+        };
+        out.push_str(&serde_json::to_string(&record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn to_csv(entries: &[ExportEntry]) -> String {
+    let mut out = String::from(
+        "id,timestamp,date,title,saved,file_name,transcription_text,post_processed_text,post_process_prompt,final_text,edited_text\n",
+    );
+    for entry in entries {
+        let fields = [
+            entry.entry.id.to_string(),
+            entry.entry.timestamp.to_string(),
+            local_datetime(entry.entry.timestamp)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            entry.entry.title.clone(),
+            entry.entry.saved.to_string(),
+            entry.entry.file_name.clone(),
+            entry.entry.transcription_text.clone(),
+            entry.entry.post_processed_text.clone().unwrap_or_default(),
+            entry.entry.post_process_prompt.clone().unwrap_or_default(),
+            entry.entry.final_text.clone().unwrap_or_default(),
+            entry.entry.edited_text.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Recordings that still exist on disk, with their path inside the archive
+fn archive_recordings(entries: &[ExportEntry]) -> Vec<(&Path, String)> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            if entry.audio_path.exists() {
+                Some((
+                    entry.audio_path.as_path(),
+                    format!("recordings/{}", entry.entry.file_name),
+                ))
+            } else {
+                warn!(
+                    "Recording {} is missing and will not be exported",
+                    entry.entry.file_name
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+fn write_zip(path: &Path, documents: &[(String, String)], entries: &[ExportEntry]) -> Result<()> {
+    let mut zip = zip::ZipWriter::new(File::create(path)?);
+    // WAV audio barely compresses, so everything is stored as-is
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    for (name, contents) in documents {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+
+    for (audio_path, name) in archive_recordings(entries) {
+        zip.start_file(name.as_str(), options)?;
+        io::copy(&mut File::open(audio_path)?, &mut zip)?;
+    }
+
+    zip.finish()?;
+    debug!("Wrote export archive {:?}", path);
+    Ok(())
+}
+
+fn write_tar(path: &Path, documents: &[(String, String)], entries: &[ExportEntry]) -> Result<()> {
+    let mut builder = tar::Builder::new(File::create(path)?);
+    let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+
+    for (name, contents) in documents {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        builder.append_data(&mut header, name, contents.as_bytes())?;
+    }
+
+    for (audio_path, name) in archive_recordings(entries) {
+        builder.append_path_with_name(audio_path, name)?;
+    }
+
+    builder.into_inner()?.flush()?;
+    debug!("Wrote export archive {:?}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export_entry(id: i64, file_name: &str, text: &str) -> ExportEntry {
+        ExportEntry {
+            entry: HistoryEntry {
+                id,
+                file_name: file_name.to_string(),
+                timestamp: 1_700_000_000,
+                saved: false,
+                title: "Recording".to_string(),
+                transcription_text: text.to_string(),
+                post_processed_text: None,
+                post_process_prompt: None,
+                final_text: None,
+                edited_text: None,
+            },
+            segments: Vec::new(),
+            audio_path: PathBuf::from("/nonexistent/handy-export-test.wav"),
+        }
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain text"), "plain text");
+        assert_eq!(csv_field("one, two"), "\"one, two\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut entry = export_entry(7, "handy-1.wav", "Hello, world");
+        entry.entry.edited_text = Some("Hello, World".to_string());
+        let csv = to_csv(&[entry]);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("id,timestamp,date,title,saved,file_name,transcription_text,post_processed_text,post_process_prompt,final_text,edited_text")
+        );
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("7,1700000000,"));
+        assert!(row.ends_with(",Recording,false,handy-1.wav,\"Hello, world\",,,,\"Hello, World\""));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_final_text_preference() {
+        let mut entry = export_entry(1, "handy-1.wav", "raw");
+        assert_eq!(entry.final_text(), "raw");
+        entry.entry.post_processed_text = Some("processed".to_string());
+        assert_eq!(entry.final_text(), "processed");
+        entry.entry.final_text = Some("pasted".to_string());
+        assert_eq!(entry.final_text(), "pasted");
+        entry.entry.edited_text = Some("edited".to_string());
+        assert_eq!(entry.final_text(), "edited");
+    }
+
+    #[test]
+    fn test_subtitle_cues_without_segments() {
+        // Five words at the fallback rate, as the recording is missing
+        let entry = export_entry(1, "handy-1.wav", "one two three four five");
+        let cues = subtitle_cues(&entry);
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].start, 0.0);
+        assert_eq!(cues[0].end, 2.0);
+        assert_eq!(cues[0].text, "one two three four five");
+
+        // Short texts still get a cue of at least a second
+        let entry = export_entry(2, "handy-2.wav", "hi");
+        assert_eq!(subtitle_cues(&entry)[0].end, 1.0);
+    }
+
+    #[test]
+    fn test_subtitle_cues_from_segments() {
+        let mut entry = export_entry(1, "handy-1.wav", "Hello there. General Kenobi.");
+        entry.segments = vec![
+            TranscriptSegment {
+                start: 0.0,
+                end: 1.0,
+                text: "Hello there.".to_string(),
+                words: Vec::new(),
+            },
+            TranscriptSegment {
+                start: 1.0,
+                end: 2.5,
+                text: "General Kenobi.".to_string(),
+                words: Vec::new(),
+            },
+        ];
+        let cues = subtitle_cues(&entry);
+        assert_eq!(cues.len(), 2);
+        assert_eq!((cues[1].start, cues[1].end), (1.0, 2.5));
+        assert_eq!(cues[1].text, "General Kenobi.");
+
+        // A corrected entry exports its final text rather than the raw segments
+        entry.entry.edited_text = Some("Hello there. General Grievous.".to_string());
+        let cues = subtitle_cues(&entry);
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].text, "Hello there. General Grievous.");
+    }
+
+    #[test]
+    fn test_document_names() {
+        let entries = [
+            export_entry(1, "handy-1700000000.wav", "first"),
+            export_entry(2, "handy-1700000100.wav", "second"),
+        ];
+        let names = |format| -> Vec<String> {
+            render_documents(&entries, format, "handy-export")
+                .unwrap()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+        assert_eq!(
+            names(ExportFormat::Srt),
+            vec!["handy-1700000000.srt", "handy-1700000100.srt"]
+        );
+        assert_eq!(
+            names(ExportFormat::Vtt),
+            vec!["handy-1700000000.vtt", "handy-1700000100.vtt"]
+        );
+        assert_eq!(names(ExportFormat::Markdown), vec!["handy-export.md"]);
+        assert_eq!(names(ExportFormat::Jsonl), vec!["handy-export.jsonl"]);
+        assert_eq!(names(ExportFormat::Csv), vec!["handy-export.csv"]);
+    }
+
+    #[test]
+    fn test_to_jsonl() {
+        let jsonl = to_jsonl(&[
+            export_entry(1, "handy-1.wav", "first"),
+            export_entry(2, "handy-2.wav", "second"),
+        ])
+        .unwrap();
+        let records: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["id"], 2);
+        assert_eq!(records[1]["transcription_text"], "second");
+        assert!(records[1]["edited_text"].is_null());
+    }
+
+    #[test]
+    fn test_archive_entry_names() {
+        let dir = std::env::temp_dir().join(format!("handy-export-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let audio_path = dir.join("handy-1700000000.wav");
+        fs::write(&audio_path, b"RIFF").unwrap();
+
+        let mut present = export_entry(1, "handy-1700000000.wav", "first");
+        present.audio_path = audio_path;
+        let missing = export_entry(2, "handy-1700000100.wav", "second");
+        let entries = [present, missing];
+
+        // Missing recordings are left out rather than failing the export
+        let recordings = archive_recordings(&entries);
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].1, "recordings/handy-1700000000.wav");
+
+        let archive_path = dir.join("export.tar");
+        let documents = vec![("handy-export.md".to_string(), "# Notes\n".to_string())];
+        write_tar(&archive_path, &documents, &entries).unwrap();
+        let mut archive = tar::Archive::new(File::open(&archive_path).unwrap());
+        let names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["handy-export.md", "recordings/handy-1700000000.wav"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
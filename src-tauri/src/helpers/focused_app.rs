@@ -0,0 +1,208 @@
+use log::debug;
+#[cfg(target_os = "linux")]
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+
+/// Identifiers of the application that currently has keyboard focus
+#[derive(Debug, Clone, Default)]
+pub struct FocusedApp {
+    /// Executable name, e.g. `alacritty` or `Code.exe`
+    pub process_name: Option<String>,
+    /// X11 `WM_CLASS` instance and class names, or the Win32 window class
+    pub window_classes: Vec<String>,
+    /// macOS bundle identifier, e.g. `com.apple.Terminal`
+    pub bundle_id: Option<String>,
+}
+
+impl FocusedApp {
+    /// All identifiers that profiles can be matched against
+    pub fn identifiers(&self) -> Vec<String> {
+        let mut identifiers = Vec::new();
+        identifiers.extend(self.process_name.clone());
+        identifiers.extend(self.window_classes.iter().cloned());
+        identifiers.extend(self.bundle_id.clone());
+        identifiers
+    }
+}
+
+/// Looks up the focused application. Implemented per platform; returns `None`
+/// when the platform or session (e.g. pure Wayland) doesn't expose it.
+pub trait FocusedAppResolver: Send + Sync {
+    fn focused_app(&self) -> Option<FocusedApp>;
+}
+
+/// The resolver for the current platform
+pub fn default_resolver() -> Box<dyn FocusedAppResolver> {
+    #[cfg(target_os = "linux")]
+    return Box::new(X11Resolver);
+    #[cfg(target_os = "macos")]
+    return Box::new(MacOsResolver);
+    #[cfg(target_os = "windows")]
+    return Box::new(WindowsResolver);
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return Box::new(NoopResolver);
+}
+
+pub fn focused_app() -> Option<FocusedApp> {
+    let app = default_resolver().focused_app();
+    debug!("Focused app: {:?}", app);
+    app
+}
+
+/// Looks up the focused application on a thread of its own, so a slow lookup
+/// doesn't hold up whatever the caller does meanwhile
+pub fn spawn_lookup() -> JoinHandle<Option<FocusedApp>> {
+    thread::spawn(focused_app)
+}
+
+/// Uses `xprop` to read the active window's class and PID. Works on X11 and
+/// for XWayland windows.
+#[cfg(target_os = "linux")]
+pub struct X11Resolver;
+
+#[cfg(target_os = "linux")]
+impl FocusedAppResolver for X11Resolver {
+    fn focused_app(&self) -> Option<FocusedApp> {
+        let active = run(Command::new("xprop").args(["-root", "_NET_ACTIVE_WINDOW"]))?;
+        // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007
+        let window_id = active.split('#').nth(1)?.trim().split(',').next()?.trim();
+        if window_id.is_empty() || window_id == "0x0" {
+            return None;
+        }
+
+        let props = run(Command::new("xprop").args(["-id", window_id, "WM_CLASS", "_NET_WM_PID"]))?;
+        let mut app = FocusedApp::default();
+        for line in props.lines() {
+            if line.starts_with("WM_CLASS") {
+                // WM_CLASS(STRING) = "alacritty", "Alacritty"
+                if let Some((_, values)) = line.split_once('=') {
+                    app.window_classes = values
+                        .split(',')
+                        .map(|v| v.trim().trim_matches('"').to_string())
+                        .filter(|v| !v.is_empty())
+                        .collect();
+                }
+            } else if line.starts_with("_NET_WM_PID") {
+                // _NET_WM_PID(CARDINAL) = 12345
+                if let Some(pid) = line.split('=').nth(1).map(str::trim) {
+                    app.process_name = std::fs::read_to_string(format!("/proc/{}/comm", pid))
+                        .ok()
+                        .map(|name| name.trim().to_string());
+                }
+            }
+        }
+
+        Some(app)
+    }
+}
+
+/// Asks `NSWorkspace` for the frontmost application, which needs no
+/// Automation permission
+#[cfg(target_os = "macos")]
+pub struct MacOsResolver;
+
+#[cfg(target_os = "macos")]
+impl FocusedAppResolver for MacOsResolver {
+    fn focused_app(&self) -> Option<FocusedApp> {
+        use objc2_app_kit::NSWorkspace;
+
+        let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+        Some(FocusedApp {
+            process_name: app
+                .executableURL()
+                .and_then(|url| url.lastPathComponent())
+                .map(|name| name.to_string()),
+            window_classes: Vec::new(),
+            bundle_id: app.bundleIdentifier().map(|id| id.to_string()),
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub struct WindowsResolver;
+
+#[cfg(target_os = "windows")]
+impl FocusedAppResolver for WindowsResolver {
+    fn focused_app(&self) -> Option<FocusedApp> {
+        use windows::core::PWSTR;
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{
+            OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+            PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetClassNameW, GetForegroundWindow, GetWindowThreadProcessId,
+        };
+
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_invalid() {
+                return None;
+            }
+
+            let mut app = FocusedApp::default();
+
+            let mut class_name = [0u16; 256];
+            let len = GetClassNameW(hwnd, &mut class_name);
+            if len > 0 {
+                app.window_classes
+                    .push(String::from_utf16_lossy(&class_name[..len as usize]));
+            }
+
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            if let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
+                let mut path = [0u16; 1024];
+                let mut size = path.len() as u32;
+                if QueryFullProcessImageNameW(
+                    process,
+                    PROCESS_NAME_WIN32,
+                    PWSTR(path.as_mut_ptr()),
+                    &mut size,
+                )
+                .is_ok()
+                {
+                    let path = String::from_utf16_lossy(&path[..size as usize]);
+                    app.process_name = std::path::Path::new(&path)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string());
+                }
+                let _ = CloseHandle(process);
+            }
+
+            Some(app)
+        }
+    }
+}
+
+/// Used where the focused application can't be looked up, so no profile
+/// ever matches
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub struct NoopResolver;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+impl FocusedAppResolver for NoopResolver {
+    fn focused_app(&self) -> Option<FocusedApp> {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn run(command: &mut Command) -> Option<String> {
+    match command.output() {
+        Ok(output) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).to_string())
+        }
+        Ok(output) => {
+            debug!(
+                "Focused app lookup failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            None
+        }
+        Err(e) => {
+            debug!("Focused app lookup failed: {}", e);
+            None
+        }
+    }
+}
@@ -1 +1,2 @@
 pub mod clamshell;
+pub mod focused_app;
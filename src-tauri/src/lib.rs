@@ -5,6 +5,8 @@ mod audio_feedback;
 pub mod audio_toolkit;
 mod clipboard;
 mod commands;
+mod control;
+mod export;
 mod helpers;
 mod input;
 mod llm_client;
@@ -16,11 +18,19 @@ mod signal_handle;
 mod tray;
 mod tray_i18n;
 mod utils;
+
+pub use managers::model::{builtin_models, read_custom_models, CustomModel, EngineType, ModelInfo};
+pub use managers::transcription::{
+    correct_transcription, load_engine_at, SpeechEngine, TranscriptSegment, Transcription,
+};
+pub use settings::{get_default_settings, AppSettings, SETTINGS_STORE_PATH};
 use specta_typescript::{BigIntExportBehavior, Typescript};
 use tauri_specta::{collect_commands, Builder};
 
 use env_filter::Builder as EnvFilterBuilder;
+use managers::api_server::ApiServerManager;
 use managers::audio::AudioRecordingManager;
+use managers::control_socket::ControlSocketManager;
 use managers::history::HistoryManager;
 use managers::model::ModelManager;
 use managers::transcription::TranscriptionManager;
@@ -133,6 +143,32 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     app_handle.manage(transcription_manager.clone());
     app_handle.manage(history_manager.clone());
 
+    // Look for a newer model catalog in the background
+    let catalog_model_manager = model_manager.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = catalog_model_manager.refresh_catalog().await {
+            log::warn!("Failed to refresh model catalog: {}", e);
+        }
+    });
+
+    // Start the local transcription API if it is enabled
+    let api_server_manager = Arc::new(ApiServerManager::new(
+        app_handle,
+        transcription_manager.clone(),
+        model_manager.clone(),
+    ));
+    if let Err(e) = api_server_manager.start() {
+        log::error!("Failed to start API server: {}", e);
+    }
+    app_handle.manage(api_server_manager);
+
+    // Start the control socket if it is enabled
+    let control_socket_manager = Arc::new(ControlSocketManager::new(app_handle));
+    if let Err(e) = control_socket_manager.start() {
+        log::error!("Failed to start control socket: {}", e);
+    }
+    app_handle.manage(control_socket_manager);
+
     // Initialize the shortcuts
     shortcut::init_shortcuts(app_handle);
 
@@ -234,6 +270,9 @@ pub fn run() {
     let specta_builder = Builder::<tauri::Wry>::new().commands(collect_commands![
         shortcut::change_binding,
         shortcut::reset_binding,
+        shortcut::add_transcribe_binding,
+        shortcut::update_transcribe_binding,
+        shortcut::remove_transcribe_binding,
         shortcut::change_ptt_setting,
         shortcut::change_audio_feedback_setting,
         shortcut::change_audio_feedback_volume_setting,
@@ -245,6 +284,17 @@ pub fn run() {
         shortcut::change_overlay_position_setting,
         shortcut::change_debug_mode_setting,
         shortcut::change_word_correction_threshold_setting,
+        shortcut::change_custom_words_mode_setting,
+        shortcut::change_transcription_context_prompt_setting,
+        shortcut::change_inverse_text_normalization_setting,
+        shortcut::change_voice_commands_enabled_setting,
+        shortcut::change_voice_commands_language_setting,
+        shortcut::change_hands_free_enabled_setting,
+        shortcut::change_hands_free_end_silence_setting,
+        shortcut::change_hands_free_min_utterance_setting,
+        shortcut::change_auto_stop_silence_setting,
+        shortcut::change_no_speech_timeout_setting,
+        shortcut::change_max_recording_setting,
         shortcut::change_paste_method_setting,
         shortcut::change_clipboard_handling_setting,
         shortcut::change_post_process_enabled_setting,
@@ -256,12 +306,29 @@ pub fn run() {
         shortcut::add_post_process_prompt,
         shortcut::update_post_process_prompt,
         shortcut::delete_post_process_prompt,
+        shortcut::add_app_profile,
+        shortcut::update_app_profile,
+        shortcut::delete_app_profile,
         shortcut::set_post_process_selected_prompt,
         shortcut::update_custom_words,
         shortcut::suspend_binding,
         shortcut::resume_binding,
         shortcut::change_mute_while_recording_setting,
         shortcut::change_append_trailing_space_setting,
+        shortcut::change_streaming_transcription_setting,
+        shortcut::change_api_server_enabled_setting,
+        shortcut::change_api_server_bind_address_setting,
+        shortcut::change_api_server_token_setting,
+        shortcut::change_control_socket_enabled_setting,
+        shortcut::change_model_catalog_url_setting,
+        shortcut::change_model_download_concurrency_setting,
+        shortcut::change_model_download_max_kib_per_sec_setting,
+        shortcut::change_model_download_mirrors_setting,
+        shortcut::change_benchmark_target_latency_setting,
+        shortcut::set_remote_transcription_provider,
+        shortcut::change_remote_transcription_base_url_setting,
+        shortcut::change_remote_transcription_api_key_setting,
+        shortcut::change_remote_transcription_model_setting,
         shortcut::change_app_language_setting,
         shortcut::change_update_checks_setting,
         trigger_update_check,
@@ -274,17 +341,36 @@ pub fn run() {
         commands::open_recordings_folder,
         commands::open_log_dir,
         commands::open_app_data_dir,
+        commands::replacements::get_replacement_rules,
+        commands::replacements::set_replacement_rules,
+        commands::replacements::import_replacement_rules,
+        commands::replacements::export_replacement_rules,
+        commands::replacements::preview_replacements,
+        commands::voice_commands::get_voice_commands,
+        commands::voice_commands::set_voice_command_enabled,
         commands::models::get_available_models,
         commands::models::get_model_info,
         commands::models::download_model,
         commands::models::delete_model,
+        commands::models::verify_model,
+        commands::models::repair_model,
+        commands::models::update_model,
+        commands::models::refresh_model_catalog,
+        commands::models::add_custom_model,
+        commands::models::remove_custom_model,
+        commands::models::import_model_archive,
         commands::models::cancel_download,
+        commands::models::pause_download,
+        commands::models::resume_download,
+        commands::models::get_download_queue,
         commands::models::set_active_model,
         commands::models::get_current_model,
         commands::models::get_transcription_model_status,
         commands::models::is_model_loading,
         commands::models::has_any_models_available,
         commands::models::has_any_models_or_downloads,
+        commands::models::benchmark_models,
+        commands::models::get_model_benchmarks,
         commands::models::get_recommended_first_model,
         commands::audio::update_microphone_mode,
         commands::audio::get_microphone_mode,
@@ -299,13 +385,25 @@ pub fn run() {
         commands::audio::set_clamshell_microphone,
         commands::audio::get_clamshell_microphone,
         commands::audio::is_recording,
+        commands::audio::get_hands_free_state,
+        commands::audio::toggle_hands_free_paused,
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::unload_model_manually,
         commands::history::get_history_entries,
+        commands::history::get_history_entries_page,
+        commands::history::search_history,
         commands::history::toggle_history_entry_saved,
+        commands::history::get_history_entry_segments,
+        commands::history::get_history_entry_revisions,
+        commands::history::retranscribe_history_entry,
+        commands::history::update_history_entry_text,
+        commands::history::get_correction_proposals,
+        commands::history::accept_correction_proposal,
+        commands::history::dismiss_correction_proposal,
         commands::history::get_audio_file_path,
         commands::history::delete_history_entry,
+        commands::history::export_history,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
         helpers::clamshell::is_laptop,
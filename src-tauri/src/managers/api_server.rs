@@ -0,0 +1,816 @@
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::decode_audio_bytes;
+use crate::audio_toolkit::subtitles::{to_srt, to_vtt, SubtitleCue};
+use crate::managers::model::ModelManager;
+use crate::managers::transcription::{Transcription, TranscriptionManager};
+use crate::settings::get_settings;
+use anyhow::{anyhow, Result};
+use axum::extract::{DefaultBodyLimit, Multipart, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use log::{debug, error, info};
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::async_runtime::JoinHandle;
+use tauri::AppHandle;
+use tokio::sync::{mpsc, oneshot};
+
+// Same upload limit as OpenAI's transcription endpoint
+const MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+// Requests waiting for the model beyond this are rejected with 503
+const MAX_QUEUED_REQUESTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    Json,
+    Text,
+    Srt,
+    Vtt,
+    VerboseJson,
+}
+
+impl ResponseFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(Self::Json),
+            "text" => Some(Self::Text),
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            "verbose_json" => Some(Self::VerboseJson),
+            _ => None,
+        }
+    }
+}
+
+/// The multipart fields of an OpenAI transcription request that Handy uses
+struct TranscriptionRequest {
+    audio: Vec<u8>,
+    model: Option<String>,
+    language: Option<String>,
+    prompt: Option<String>,
+    response_format: ResponseFormat,
+    word_timestamps: bool,
+}
+
+struct TranscriptionJob {
+    samples: Vec<f32>,
+    model: Option<String>,
+    language: Option<String>,
+    prompt: Option<String>,
+    translate: bool,
+}
+
+type JobReply = oneshot::Sender<Result<Transcription>>;
+
+#[derive(Clone)]
+struct ServerState {
+    token: Option<String>,
+    queue: mpsc::Sender<(TranscriptionJob, JobReply)>,
+    model_manager: Arc<ModelManager>,
+}
+
+struct RunningServer {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// Error body in the shape OpenAI clients expect
+struct ApiError {
+    status: StatusCode,
+    error_type: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error_type: "invalid_request_error",
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            error_type: "invalid_request_error",
+            message: "Invalid or missing API token".to_string(),
+        }
+    }
+
+    fn unavailable(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            error_type: "server_error",
+            message: message.into(),
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            error_type: "server_error",
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = json!({
+            "error": {
+                "message": self.message,
+                "type": self.error_type,
+                "param": null,
+                "code": null,
+            }
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Serves the loaded model over a localhost HTTP API compatible with OpenAI's
+/// `/v1/audio/transcriptions` and `/v1/audio/translations` endpoints.
+pub struct ApiServerManager {
+    app_handle: AppHandle,
+    transcription_manager: Arc<TranscriptionManager>,
+    model_manager: Arc<ModelManager>,
+    running: Mutex<Option<RunningServer>>,
+}
+
+impl ApiServerManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        transcription_manager: Arc<TranscriptionManager>,
+        model_manager: Arc<ModelManager>,
+    ) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            transcription_manager,
+            model_manager,
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Starts the server if it is enabled in the settings
+    pub fn start(&self) -> Result<()> {
+        let settings = get_settings(&self.app_handle);
+        if !settings.api_server_enabled {
+            return Ok(());
+        }
+
+        let address: SocketAddr = settings.api_server_bind_address.parse().map_err(|_| {
+            anyhow!(
+                "Invalid API server address: {}",
+                settings.api_server_bind_address
+            )
+        })?;
+        let token = settings.api_server_token.filter(|t| !t.is_empty());
+        if token.is_none() && !address.ip().is_loopback() {
+            return Err(anyhow!(
+                "Refusing to serve the API on non-loopback address {} without a token",
+                address
+            ));
+        }
+
+        // Bind synchronously so address errors reach the caller
+        let listener = std::net::TcpListener::bind(address)
+            .map_err(|e| anyhow!("Failed to bind API server to {}: {}", address, e))?;
+        listener.set_nonblocking(true)?;
+
+        let (queue_tx, queue_rx) = mpsc::channel(MAX_QUEUED_REQUESTS);
+        {
+            let app_handle = self.app_handle.clone();
+            let tm = self.transcription_manager.clone();
+            let mm = self.model_manager.clone();
+            thread::spawn(move || run_queue(app_handle, tm, mm, queue_rx));
+        }
+
+        let state = ServerState {
+            token,
+            queue: queue_tx,
+            model_manager: self.model_manager.clone(),
+        };
+        let router = Router::new()
+            .route("/v1/audio/transcriptions", post(transcriptions))
+            .route("/v1/audio/translations", post(translations))
+            .route("/v1/models", get(list_models))
+            .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+            .with_state(state);
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let task = tauri::async_runtime::spawn(async move {
+            let listener = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to start API server: {}", e);
+                    return;
+                }
+            };
+            let shutdown = async {
+                let _ = shutdown_rx.await;
+            };
+            if let Err(e) = axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown)
+                .await
+            {
+                error!("API server error: {}", e);
+            }
+            info!("API server stopped");
+        });
+
+        *self.running.lock().unwrap() = Some(RunningServer {
+            shutdown: shutdown_tx,
+            task,
+        });
+        info!("API server listening on http://{}", address);
+        Ok(())
+    }
+
+    /// Stops the server and waits for in-flight requests to finish
+    pub async fn stop(&self) {
+        let running = self.running.lock().unwrap().take();
+        if let Some(running) = running {
+            let _ = running.shutdown.send(());
+            let _ = running.task.await;
+        }
+    }
+
+    /// Applies changed settings by stopping and, if still enabled, starting again
+    pub async fn restart(&self) -> Result<()> {
+        self.stop().await;
+        self.start()
+    }
+}
+
+/// Transcribes queued requests one at a time on a dedicated thread. Ends when
+/// the server shuts down and the last sender is dropped.
+fn run_queue(
+    app_handle: AppHandle,
+    tm: Arc<TranscriptionManager>,
+    mm: Arc<ModelManager>,
+    mut queue: mpsc::Receiver<(TranscriptionJob, JobReply)>,
+) {
+    while let Some((job, reply)) = queue.blocking_recv() {
+        let _ = reply.send(transcribe_job(&app_handle, &tm, &mm, job));
+    }
+    debug!("API transcription queue closed");
+}
+
+fn transcribe_job(
+    app_handle: &AppHandle,
+    tm: &TranscriptionManager,
+    mm: &ModelManager,
+    job: TranscriptionJob,
+) -> Result<Transcription> {
+    let mut settings = get_settings(app_handle);
+    if let Some(language) = job.language {
+        settings.selected_language = language;
+    }
+    // Takes the place of the context prompt; engines without prompts ignore it
+    if let Some(prompt) = job.prompt {
+        settings.transcription_context_prompt = prompt;
+    }
+    settings.translate_to_english = job.translate;
+
+    // Clients usually send a placeholder such as `whisper-1`; only the id of a
+    // downloaded Handy model selects a specific model
+    let requested_model = job
+        .model
+        .as_deref()
+        .filter(|id| mm.get_model_info(id).is_some_and(|m| m.is_downloaded));
+    if let Some(model_id) = requested_model {
+        return tm.transcribe_with_model(job.samples, model_id, &settings);
+    }
+
+    if !tm.is_model_loaded() {
+        tm.initiate_model_load();
+    }
+    tm.transcribe_with_settings(job.samples, &settings)
+}
+
+fn authorize(state: &ServerState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let Some(token) = &state.token else {
+        return Ok(());
+    };
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if provided.is_some_and(|provided| tokens_match(provided, token)) {
+        Ok(())
+    } else {
+        Err(ApiError::unauthorized())
+    }
+}
+
+/// Compares tokens in time that depends only on their lengths, so response
+/// timing does not reveal how much of a guessed token was correct
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let (provided, expected) = (provided.as_bytes(), expected.as_bytes());
+    if provided.len() != expected.len() {
+        return false;
+    }
+    provided
+        .iter()
+        .zip(expected)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+async fn transcriptions(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Result<Response, ApiError> {
+    handle_request(state, headers, multipart, false).await
+}
+
+async fn translations(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Result<Response, ApiError> {
+    handle_request(state, headers, multipart, true).await
+}
+
+async fn list_models(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    authorize(&state, &headers)?;
+
+    let mut models: Vec<_> = state
+        .model_manager
+        .get_available_models()
+        .into_iter()
+        .filter(|m| m.is_downloaded)
+        .map(|m| {
+            json!({
+                "id": m.id,
+                "object": "model",
+                "created": 0,
+                "owned_by": "handy",
+            })
+        })
+        .collect();
+    models.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+    Ok(Json(json!({ "object": "list", "data": models })).into_response())
+}
+
+async fn handle_request(
+    state: ServerState,
+    headers: HeaderMap,
+    multipart: Multipart,
+    translate: bool,
+) -> Result<Response, ApiError> {
+    authorize(&state, &headers)?;
+
+    let request = parse_request(multipart).await?;
+    debug!(
+        "API {} request: {} bytes, model {:?}, language {:?}, format {:?}",
+        if translate {
+            "translation"
+        } else {
+            "transcription"
+        },
+        request.audio.len(),
+        request.model,
+        request.language,
+        request.response_format
+    );
+
+    let audio = request.audio;
+    let samples = tauri::async_runtime::spawn_blocking(move || decode_audio_bytes(audio))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .map_err(|e| ApiError::invalid_request(format!("Could not decode audio: {}", e)))?;
+    let duration = samples.len() as f32 / WHISPER_SAMPLE_RATE as f32;
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let job = TranscriptionJob {
+        samples,
+        model: request.model,
+        language: request.language.clone(),
+        prompt: request.prompt,
+        translate,
+    };
+    state.queue.try_send((job, reply_tx)).map_err(|e| match e {
+        mpsc::error::TrySendError::Full(_) => {
+            ApiError::unavailable("Too many transcription requests are queued")
+        }
+        mpsc::error::TrySendError::Closed(_) => {
+            ApiError::unavailable("The transcription queue is not running")
+        }
+    })?;
+
+    let transcription = reply_rx
+        .await
+        .map_err(|_| ApiError::internal("Transcription was cancelled"))?
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(render_response(
+        transcription,
+        request.response_format,
+        request.word_timestamps,
+        if translate { "translate" } else { "transcribe" },
+        request.language.as_deref().unwrap_or("auto"),
+        duration,
+    ))
+}
+
+async fn parse_request(mut multipart: Multipart) -> Result<TranscriptionRequest, ApiError> {
+    let mut audio = None;
+    let mut model = None;
+    let mut language = None;
+    let mut prompt = None;
+    let mut response_format = ResponseFormat::Json;
+    let mut word_timestamps = false;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::invalid_request(e.body_text()))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::invalid_request(e.body_text()))?;
+            audio = Some(bytes.to_vec());
+            continue;
+        }
+
+        let value = field
+            .text()
+            .await
+            .map_err(|e| ApiError::invalid_request(e.body_text()))?;
+        match name.as_str() {
+            "model" => model = Some(value).filter(|v| !v.is_empty()),
+            "language" => language = Some(value).filter(|v| !v.is_empty()),
+            "prompt" => prompt = Some(value).filter(|v| !v.trim().is_empty()),
+            "response_format" => {
+                response_format = ResponseFormat::parse(&value).ok_or_else(|| {
+                    ApiError::invalid_request(format!("Unsupported response_format: {}", value))
+                })?;
+            }
+            "timestamp_granularities[]" | "timestamp_granularities" => {
+                word_timestamps |= value == "word";
+            }
+            // temperature and other OpenAI parameters are accepted but unused
+            _ => {}
+        }
+    }
+
+    let audio = audio.ok_or_else(|| ApiError::invalid_request("Missing 'file' field"))?;
+    Ok(TranscriptionRequest {
+        audio,
+        model,
+        language,
+        prompt,
+        response_format,
+        word_timestamps,
+    })
+}
+
+fn render_response(
+    transcription: Transcription,
+    format: ResponseFormat,
+    word_timestamps: bool,
+    task: &str,
+    language: &str,
+    duration: f32,
+) -> Response {
+    match format {
+        ResponseFormat::Json => Json(json!({ "text": transcription.text })).into_response(),
+        ResponseFormat::Text => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            transcription.text,
+        )
+            .into_response(),
+        ResponseFormat::Srt => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            to_srt(&subtitle_cues(transcription, duration)),
+        )
+            .into_response(),
+        ResponseFormat::Vtt => (
+            [(header::CONTENT_TYPE, "text/vtt; charset=utf-8")],
+            to_vtt(&subtitle_cues(transcription, duration)),
+        )
+            .into_response(),
+        ResponseFormat::VerboseJson => {
+            let segments: Vec<_> = transcription
+                .segments
+                .iter()
+                .enumerate()
+                .map(|(id, segment)| {
+                    json!({
+                        "id": id,
+                        "start": segment.start,
+                        "end": segment.end,
+                        "text": segment.text,
+                    })
+                })
+                .collect();
+            let mut body = json!({
+                "task": task,
+                "language": language,
+                "duration": duration,
+                "text": transcription.text,
+                "segments": segments,
+            });
+            if word_timestamps {
+                let words: Vec<_> = transcription
+                    .segments
+                    .iter()
+                    .flat_map(|segment| segment.words.iter())
+                    .map(|word| {
+                        json!({
+                            "word": word.text,
+                            "start": word.start,
+                            "end": word.end,
+                        })
+                    })
+                    .collect();
+                body["words"] = json!(words);
+            }
+            Json(body).into_response()
+        }
+    }
+}
+
+fn subtitle_cues(transcription: Transcription, duration: f32) -> Vec<SubtitleCue> {
+    if transcription.segments.is_empty() {
+        return vec![SubtitleCue {
+            start: 0.0,
+            end: duration,
+            text: transcription.text,
+        }];
+    }
+
+    transcription
+        .segments
+        .into_iter()
+        .map(|segment| SubtitleCue {
+            start: segment.start,
+            end: segment.end,
+            text: segment.text,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::transcription::{TranscriptSegment, TranscriptWord};
+    use axum::body::Body;
+    use axum::extract::FromRequest;
+    use axum::http::Request;
+
+    const BOUNDARY: &str = "handy-test-boundary";
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    /// Parses a multipart body made of the given text fields and, if given,
+    /// a `file` field
+    fn parse(
+        fields: &[(&str, &str)],
+        file: Option<&[u8]>,
+    ) -> Result<TranscriptionRequest, ApiError> {
+        let mut body = Vec::new();
+        for (name, value) in fields {
+            body.extend_from_slice(
+                format!(
+                    "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+                )
+                .as_bytes(),
+            );
+        }
+        if let Some(file) = file {
+            body.extend_from_slice(
+                format!(
+                    "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.wav\"\r\nContent-Type: audio/wav\r\n\r\n"
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(file);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+
+        let request = Request::builder()
+            .method("POST")
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+        block_on(async {
+            let multipart = Multipart::from_request(request, &()).await.unwrap();
+            parse_request(multipart).await
+        })
+    }
+
+    fn render(format: ResponseFormat, word_timestamps: bool) -> (String, String) {
+        let transcription = Transcription {
+            text: "Hello there. Bye.".to_string(),
+            segments: vec![
+                TranscriptSegment {
+                    start: 0.0,
+                    end: 1.5,
+                    text: "Hello there.".to_string(),
+                    words: vec![
+                        TranscriptWord {
+                            start: 0.0,
+                            end: 0.5,
+                            text: "Hello".to_string(),
+                            confidence: None,
+                        },
+                        TranscriptWord {
+                            start: 0.75,
+                            end: 1.5,
+                            text: "there.".to_string(),
+                            confidence: None,
+                        },
+                    ],
+                },
+                TranscriptSegment {
+                    start: 2.0,
+                    end: 2.5,
+                    text: "Bye.".to_string(),
+                    words: Vec::new(),
+                },
+            ],
+        };
+        let response = render_response(
+            transcription,
+            format,
+            word_timestamps,
+            "transcribe",
+            "en",
+            3.0,
+        );
+        let content_type = response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = block_on(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        (content_type, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn test_response_format_parse() {
+        assert_eq!(ResponseFormat::parse("json"), Some(ResponseFormat::Json));
+        assert_eq!(ResponseFormat::parse("text"), Some(ResponseFormat::Text));
+        assert_eq!(ResponseFormat::parse("srt"), Some(ResponseFormat::Srt));
+        assert_eq!(ResponseFormat::parse("vtt"), Some(ResponseFormat::Vtt));
+        assert_eq!(
+            ResponseFormat::parse("verbose_json"),
+            Some(ResponseFormat::VerboseJson)
+        );
+        assert_eq!(ResponseFormat::parse("JSON"), None);
+        assert_eq!(ResponseFormat::parse("xml"), None);
+        assert_eq!(ResponseFormat::parse(""), None);
+    }
+
+    #[test]
+    fn test_parse_request_reads_fields() {
+        let request = parse(
+            &[
+                ("model", "whisper-1"),
+                ("language", "de"),
+                ("prompt", "Names: Handy, Tauri."),
+                ("response_format", "verbose_json"),
+                ("timestamp_granularities[]", "segment"),
+                ("timestamp_granularities[]", "word"),
+                ("temperature", "0"),
+            ],
+            Some(b"RIFF"),
+        )
+        .unwrap_or_else(|e| panic!("{}", e.message));
+
+        assert_eq!(request.audio, b"RIFF");
+        assert_eq!(request.model.as_deref(), Some("whisper-1"));
+        assert_eq!(request.language.as_deref(), Some("de"));
+        assert_eq!(request.prompt.as_deref(), Some("Names: Handy, Tauri."));
+        assert_eq!(request.response_format, ResponseFormat::VerboseJson);
+        assert!(request.word_timestamps);
+    }
+
+    #[test]
+    fn test_parse_request_defaults() {
+        let request = parse(&[("model", ""), ("prompt", "  ")], Some(b"RIFF"))
+            .unwrap_or_else(|e| panic!("{}", e.message));
+
+        assert_eq!(request.model, None);
+        assert_eq!(request.language, None);
+        assert_eq!(request.prompt, None);
+        assert_eq!(request.response_format, ResponseFormat::Json);
+        assert!(!request.word_timestamps);
+    }
+
+    #[test]
+    fn test_parse_request_requires_file() {
+        let error = parse(&[("model", "whisper-1")], None)
+            .err()
+            .expect("a request without a file is rejected");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.error_type, "invalid_request_error");
+        assert_eq!(error.message, "Missing 'file' field");
+    }
+
+    #[test]
+    fn test_parse_request_rejects_unknown_format() {
+        let error = parse(&[("response_format", "xml")], Some(b"RIFF"))
+            .err()
+            .expect("an unknown format is rejected");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.message, "Unsupported response_format: xml");
+    }
+
+    #[test]
+    fn test_render_response_json_and_text() {
+        let (content_type, body) = render(ResponseFormat::Json, false);
+        assert_eq!(content_type, "application/json");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            json!({ "text": "Hello there. Bye." })
+        );
+
+        let (content_type, body) = render(ResponseFormat::Text, false);
+        assert_eq!(content_type, "text/plain; charset=utf-8");
+        assert_eq!(body, "Hello there. Bye.");
+    }
+
+    #[test]
+    fn test_render_response_subtitles() {
+        let (content_type, body) = render(ResponseFormat::Srt, false);
+        assert_eq!(content_type, "text/plain; charset=utf-8");
+        assert_eq!(
+            body,
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n\
+             2\n00:00:02,000 --> 00:00:02,500\nBye.\n\n"
+        );
+
+        let (content_type, body) = render(ResponseFormat::Vtt, false);
+        assert_eq!(content_type, "text/vtt; charset=utf-8");
+        assert_eq!(
+            body,
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.500\nHello there.\n\n\
+             00:00:02.000 --> 00:00:02.500\nBye.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_render_response_verbose_json() {
+        let (content_type, body) = render(ResponseFormat::VerboseJson, false);
+        assert_eq!(content_type, "application/json");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "task": "transcribe",
+                "language": "en",
+                "duration": 3.0,
+                "text": "Hello there. Bye.",
+                "segments": [
+                    { "id": 0, "start": 0.0, "end": 1.5, "text": "Hello there." },
+                    { "id": 1, "start": 2.0, "end": 2.5, "text": "Bye." },
+                ],
+            })
+        );
+
+        let (_, body) = render(ResponseFormat::VerboseJson, true);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            body["words"],
+            json!([
+                { "word": "Hello", "start": 0.0, "end": 0.5 },
+                { "word": "there.", "start": 0.75, "end": 1.5 },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret-token", "secret-token"));
+        assert!(!tokens_match("secret-tokem", "secret-token"));
+        assert!(!tokens_match("secret", "secret-token"));
+        assert!(!tokens_match("", "secret-token"));
+    }
+}
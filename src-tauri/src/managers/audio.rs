@@ -1,11 +1,19 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::actions;
+use crate::audio_feedback;
+use crate::audio_toolkit::{
+    list_input_devices, vad::SmoothedVad, AudioRecorder, RecorderEvent, SileroVad, UtteranceConfig,
+};
+use crate::control;
 use crate::helpers::clamshell;
+use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, AppSettings};
 use crate::utils;
 use log::{debug, error, info};
+use serde::Serialize;
+use specta::Type;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use tauri::Manager;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 
 fn set_mute(mute: bool) {
     // Expected behavior:
@@ -98,6 +106,10 @@ fn set_mute(mute: bool) {
 
 const WHISPER_SAMPLE_RATE: usize = 16000;
 
+// How long before the maximum recording duration the warning sounds, at most
+// half of the maximum
+const MAX_DURATION_WARNING: Duration = Duration::from_secs(10);
+
 /* ──────────────────────────────────────────────────────────────── */
 
 #[derive(Clone, Debug)]
@@ -112,6 +124,68 @@ pub enum MicrophoneMode {
     OnDemand,
 }
 
+/// Hands-free listening, emitted as `hands-free-state-changed` when it changes
+#[derive(Clone, Copy, Debug, Default, Serialize, Type)]
+pub struct HandsFreeState {
+    pub enabled: bool,
+    pub paused: bool,
+}
+
+impl HandsFreeState {
+    pub fn is_listening(&self) -> bool {
+        self.enabled && !self.paused
+    }
+}
+
+/// The auto-stop rules of the recording in progress, read from the settings
+/// when it starts
+#[derive(Default)]
+struct AutoStop {
+    binding_id: String,
+    silence_limit: Option<Duration>,
+    no_speech_limit: Option<Duration>,
+    max_duration: Option<Duration>,
+    /// The warning before the maximum duration has played
+    warned: bool,
+    /// A rule was hit and the binding is being released
+    triggered: bool,
+    /// The recording is being stopped, by a rule or by the shortcut
+    stopping: bool,
+}
+
+impl AutoStop {
+    fn new(binding_id: &str, settings: &AppSettings) -> Self {
+        let limit = |secs: u32| (secs > 0).then(|| Duration::from_secs(secs as u64));
+        Self {
+            binding_id: binding_id.to_string(),
+            silence_limit: limit(settings.auto_stop_silence_secs),
+            no_speech_limit: limit(settings.no_speech_timeout_secs),
+            max_duration: limit(settings.max_recording_secs),
+            ..Default::default()
+        }
+    }
+}
+
+fn utterance_config(settings: &AppSettings) -> UtteranceConfig {
+    UtteranceConfig {
+        end_silence: Duration::from_millis(settings.hands_free_end_silence_ms as u64),
+        min_length: Duration::from_millis(settings.hands_free_min_utterance_ms as u64),
+    }
+}
+
+/// Pads recordings shorter than a second with silence, which transcribes
+/// them more reliably
+pub fn pad_short_recording(samples: Vec<f32>) -> Vec<f32> {
+    let s_len = samples.len();
+    if s_len < WHISPER_SAMPLE_RATE && s_len > 0 {
+        let mut padded = samples;
+        padded.resize(WHISPER_SAMPLE_RATE * 5 / 4, 0.0);
+        padded
+    } else {
+        samples
+    }
+}
+
 /* ──────────────────────────────────────────────────────────────── */
 
 fn create_audio_recorder(
@@ -123,7 +197,9 @@ fn create_audio_recorder(
     let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
 
     // Recorder with VAD plus a spectrum-level callback that forwards updates to
-    // the frontend.
+    // the frontend, and an event callback that feeds speech segments to a
+    // streaming transcription session when one is active and queues
+    // hands-free utterances for transcription.
     let recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
         .with_vad(Box::new(smoothed_vad))
@@ -132,6 +208,26 @@ fn create_audio_recorder(
             move |levels| {
                 utils::emit_levels(&app_handle, &levels);
             }
+        })
+        .with_event_callback({
+            let app_handle = app_handle.clone();
+            move |event| match event {
+                RecorderEvent::SpeechSegment(samples) => {
+                    if let Some(tm) = app_handle.try_state::<Arc<TranscriptionManager>>() {
+                        tm.push_streaming_chunk(samples);
+                    }
+                }
+                RecorderEvent::Utterance(samples) => actions::queue_utterance(&app_handle, samples),
+                RecorderEvent::Progress {
+                    elapsed,
+                    silence,
+                    heard_speech,
+                } => {
+                    if let Some(rm) = app_handle.try_state::<Arc<AudioRecordingManager>>() {
+                        rm.check_auto_stop(elapsed, silence, heard_speech);
+                    }
+                }
+            }
         });
 
     Ok(recorder)
@@ -149,6 +245,8 @@ pub struct AudioRecordingManager {
     is_open: Arc<Mutex<bool>>,
     is_recording: Arc<Mutex<bool>>,
     did_mute: Arc<Mutex<bool>>,
+    hands_free: Arc<Mutex<HandsFreeState>>,
+    auto_stop: Arc<Mutex<AutoStop>>,
 }
 
 impl AudioRecordingManager {
@@ -171,10 +269,15 @@ impl AudioRecordingManager {
             is_open: Arc::new(Mutex::new(false)),
             is_recording: Arc::new(Mutex::new(false)),
             did_mute: Arc::new(Mutex::new(false)),
+            hands_free: Arc::new(Mutex::new(HandsFreeState {
+                enabled: settings.hands_free_enabled,
+                paused: false,
+            })),
+            auto_stop: Arc::new(Mutex::new(AutoStop::default())),
         };
 
-        // Always-on?  Open immediately.
-        if matches!(mode, MicrophoneMode::AlwaysOn) {
+        // Always-on or hands-free?  Open immediately.
+        if matches!(mode, MicrophoneMode::AlwaysOn) || settings.hands_free_enabled {
             manager.start_microphone_stream()?;
         }
 
@@ -210,6 +313,12 @@ impl AudioRecordingManager {
         }
     }
 
+    /// Whether the microphone stays open between recordings
+    fn keeps_stream_open(&self) -> bool {
+        matches!(*self.mode.lock().unwrap(), MicrophoneMode::AlwaysOn)
+            || self.hands_free.lock().unwrap().is_listening()
+    }
+
     /* ---------- microphone life-cycle -------------------------------------- */
 
     /// Applies mute if mute_while_recording is enabled and stream is open
@@ -271,6 +380,10 @@ impl AudioRecordingManager {
         if let Some(rec) = recorder_opt.as_mut() {
             rec.open(selected_device)
                 .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+            if self.hands_free.lock().unwrap().is_listening() {
+                rec.listen(Some(utterance_config(&settings)))
+                    .map_err(|e| anyhow::anyhow!("Failed to start listening: {}", e))?;
+            }
         }
 
         *open_flag = true;
@@ -314,7 +427,9 @@ impl AudioRecordingManager {
 
         match (cur_mode, &new_mode) {
             (MicrophoneMode::AlwaysOn, MicrophoneMode::OnDemand) => {
-                if matches!(*self.state.lock().unwrap(), RecordingState::Idle) {
+                if matches!(*self.state.lock().unwrap(), RecordingState::Idle)
+                    && !self.hands_free.lock().unwrap().is_listening()
+                {
                     drop(mode_guard);
                     self.stop_microphone_stream();
                 }
@@ -336,6 +451,9 @@ impl AudioRecordingManager {
         let mut state = self.state.lock().unwrap();
 
         if let RecordingState::Idle = *state {
+            *self.auto_stop.lock().unwrap() =
+                AutoStop::new(binding_id, &get_settings(&self.app_handle));
+
             // Ensure microphone is open in on-demand mode
             if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
                 if let Err(e) = self.start_microphone_stream() {
@@ -396,20 +514,12 @@ impl AudioRecordingManager {
                 *self.is_recording.lock().unwrap() = false;
 
                 // In on-demand mode turn the mic off again
-                if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+                if !self.keeps_stream_open() {
                     self.stop_microphone_stream();
                 }
 
                 // Pad if very short
-                let s_len = samples.len();
-                // debug!("Got {} samples", s_len);
-                if s_len < WHISPER_SAMPLE_RATE && s_len > 0 {
-                    let mut padded = samples;
-                    padded.resize(WHISPER_SAMPLE_RATE * 5 / 4, 0.0);
-                    Some(padded)
-                } else {
-                    Some(samples)
-                }
+                Some(pad_short_recording(samples))
             }
             _ => None,
         }
@@ -436,9 +546,143 @@ impl AudioRecordingManager {
             *self.is_recording.lock().unwrap() = false;
 
             // In on-demand mode turn the mic off again
-            if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+            if !self.keeps_stream_open() {
                 self.stop_microphone_stream();
             }
         }
     }
+
+    /* ---------- auto-stop ------------------------------------------------------ */
+
+    /// Claims the stop of the recording for `binding_id`. Returns false when
+    /// it is already being stopped, as when an auto-stop rule ended a
+    /// push-to-talk recording before the key was released.
+    pub fn begin_stop(&self, binding_id: &str) -> bool {
+        let mut auto_stop = self.auto_stop.lock().unwrap();
+        if auto_stop.binding_id != binding_id {
+            return true;
+        }
+        !std::mem::replace(&mut auto_stop.stopping, true)
+    }
+
+    /// Releases the recording's binding once it has been silent, without
+    /// speech or running for longer than the settings allow, warning before
+    /// the maximum duration is reached
+    fn check_auto_stop(&self, elapsed: Duration, silence: Duration, heard_speech: bool) {
+        if !self.is_recording() {
+            return;
+        }
+        let mut auto_stop = self.auto_stop.lock().unwrap();
+        if auto_stop.triggered || auto_stop.stopping {
+            return;
+        }
+
+        let reason = if heard_speech
+            && auto_stop
+                .silence_limit
+                .is_some_and(|limit| silence >= limit)
+        {
+            "trailing silence"
+        } else if !heard_speech
+            && auto_stop
+                .no_speech_limit
+                .is_some_and(|limit| silence >= limit)
+        {
+            "lack of speech"
+        } else if auto_stop.max_duration.is_some_and(|max| elapsed >= max) {
+            "maximum duration"
+        } else {
+            if let Some(max) = auto_stop.max_duration {
+                if !auto_stop.warned && elapsed + MAX_DURATION_WARNING.min(max / 2) >= max {
+                    auto_stop.warned = true;
+                    self.play_auto_stop_warning();
+                }
+            }
+            return;
+        };
+        auto_stop.triggered = true;
+        let binding_id = auto_stop.binding_id.clone();
+        drop(auto_stop);
+
+        info!("Auto-stopping recording for binding {binding_id} after its {reason}");
+        // Keep the audio thread free while the recording is stopped
+        let app = self.app_handle.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = control::release_binding(&app, &binding_id, "auto-stop") {
+                error!("Failed to auto-stop recording: {e}");
+            }
+        });
+    }
+
+    fn play_auto_stop_warning(&self) {
+        let rm = self.clone();
+        std::thread::spawn(move || {
+            // Lift mute_while_recording so the warning is audible
+            rm.remove_mute();
+            audio_feedback::play_warning_sound_blocking(&rm.app_handle);
+            if rm.is_recording() {
+                rm.apply_mute();
+            }
+        });
+    }
+
+    /* ---------- hands-free listening ---------------------------------------- */
+
+    pub fn hands_free_state(&self) -> HandsFreeState {
+        *self.hands_free.lock().unwrap()
+    }
+
+    /// Turns hands-free listening on or off. Turning it on also resumes it
+    /// if it was paused.
+    pub fn set_hands_free(&self, enabled: bool) -> Result<(), anyhow::Error> {
+        *self.hands_free.lock().unwrap() = HandsFreeState {
+            enabled,
+            paused: false,
+        };
+        self.update_listening()
+    }
+
+    /// Pauses or resumes hands-free listening, returning whether it is
+    /// paused now
+    pub fn toggle_hands_free_paused(&self) -> Result<bool, anyhow::Error> {
+        let paused = {
+            let mut hands_free = self.hands_free.lock().unwrap();
+            if !hands_free.enabled {
+                return Err(anyhow::anyhow!("Hands-free mode is off"));
+            }
+            hands_free.paused = !hands_free.paused;
+            hands_free.paused
+        };
+        self.update_listening()?;
+        Ok(paused)
+    }
+
+    /// Applies the hands-free state and settings to the microphone, opening
+    /// it to listen or closing it if nothing else needs it
+    pub fn update_listening(&self) -> Result<(), anyhow::Error> {
+        let state = self.hands_free_state();
+        let is_open = *self.is_open.lock().unwrap();
+
+        if state.is_listening() && !is_open {
+            // Starts listening once the stream is open
+            self.start_microphone_stream()?;
+        } else if is_open {
+            let config = state
+                .is_listening()
+                .then(|| utterance_config(&get_settings(&self.app_handle)));
+            if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                rec.listen(config)
+                    .map_err(|e| anyhow::anyhow!("Failed to update listening: {}", e))?;
+            }
+            if !self.keeps_stream_open() && !self.is_recording() {
+                self.stop_microphone_stream();
+            }
+        }
+
+        debug!("Hands-free listening: {:?}", state);
+        if let Err(e) = self.app_handle.emit("hands-free-state-changed", state) {
+            error!("Failed to emit hands-free-state-changed event: {}", e);
+        }
+        Ok(())
+    }
 }
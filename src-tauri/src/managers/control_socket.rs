@@ -0,0 +1,577 @@
+use crate::control::{self, BindingCommand};
+use crate::utils::cancel_current_operation;
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Listener};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// Events forwarded to `subscribe` clients
+const SUBSCRIBABLE_EVENTS: [&str; 4] = [
+    "recording-state-changed",
+    "transcription-completed",
+    "model-state-changed",
+    "history-updated",
+];
+
+#[derive(Deserialize)]
+struct ControlMessage {
+    /// Echoed back in the response so clients can match replies
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(flatten)]
+    request: ControlRequest,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum ControlRequest {
+    Start {
+        #[serde(default = "default_binding")]
+        binding: String,
+    },
+    Stop {
+        #[serde(default = "default_binding")]
+        binding: String,
+    },
+    Toggle {
+        #[serde(default = "default_binding")]
+        binding: String,
+    },
+    Cancel,
+    Status,
+    Subscribe {
+        /// Defaults to every subscribable event
+        #[serde(default)]
+        events: Option<Vec<String>>,
+    },
+}
+
+fn default_binding() -> String {
+    "transcribe".to_string()
+}
+
+/// Lets scripts and other local tools drive recording over a Unix domain
+/// socket (a named pipe on Windows) speaking newline-delimited JSON.
+///
+/// Each request is one line such as `{"command": "toggle", "binding":
+/// "transcribe"}` and gets one `{"ok": true, ...}` or `{"ok": false,
+/// "error": "..."}` line back. After `subscribe` the connection streams
+/// `{"event": "...", "payload": ...}` lines until the client disconnects.
+pub struct ControlSocketManager {
+    app_handle: AppHandle,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ControlSocketManager {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            task: Mutex::new(None),
+        }
+    }
+
+    /// Starts listening if the control socket is enabled in the settings
+    pub fn start(&self) -> Result<()> {
+        let settings = crate::settings::get_settings(&self.app_handle);
+        if !settings.control_socket_enabled {
+            return Ok(());
+        }
+
+        let task = self.listen()?;
+        *self.task.lock().unwrap() = Some(task);
+        Ok(())
+    }
+
+    /// Stops accepting and closes open connections, including `subscribe`
+    /// streams, since the listener task owns their tasks
+    pub fn stop(&self) {
+        if let Some(task) = self.task.lock().unwrap().take() {
+            task.abort();
+            #[cfg(unix)]
+            if let Ok(path) = socket_path(&self.app_handle) {
+                let _ = std::fs::remove_file(path);
+            }
+            info!("Control socket stopped");
+        }
+    }
+
+    #[cfg(unix)]
+    fn listen(&self) -> Result<JoinHandle<()>> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = socket_path(&self.app_handle)?;
+        // A previous run may have left its socket behind
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        let listener = std::os::unix::net::UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        info!("Control socket listening on {}", path.display());
+
+        let app_handle = self.app_handle.clone();
+        Ok(tauri::async_runtime::spawn(async move {
+            let listener = match tokio::net::UnixListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to start control socket: {}", e);
+                    return;
+                }
+            };
+            // Dropping the set when this task is aborted aborts every connection
+            let mut connections = JoinSet::new();
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        connections.spawn(handle_connection(app_handle.clone(), stream));
+                    }
+                    Err(e) => warn!("Control socket accept failed: {}", e),
+                }
+                while connections.try_join_next().is_some() {}
+            }
+        }))
+    }
+
+    #[cfg(windows)]
+    fn listen(&self) -> Result<JoinHandle<()>> {
+        let sid = windows_pipe::current_user_sid()?;
+        let pipe_name = windows_pipe::pipe_name(&sid);
+        info!("Control socket listening on {}", pipe_name);
+        let app_handle = self.app_handle.clone();
+        Ok(tauri::async_runtime::spawn(async move {
+            // Failing here rather than joining a pipe someone else created
+            let mut server = match windows_pipe::create(&pipe_name, &sid, true) {
+                Ok(server) => server,
+                Err(e) => {
+                    error!("Failed to create control pipe {}: {}", pipe_name, e);
+                    return;
+                }
+            };
+            // Dropping the set when this task is aborted aborts every connection
+            let mut connections = JoinSet::new();
+            loop {
+                if let Err(e) = server.connect().await {
+                    warn!("Control pipe connection failed: {}", e);
+                    continue;
+                }
+                // Hand the connected instance to the client and wait on a fresh one
+                let next = match windows_pipe::create(&pipe_name, &sid, false) {
+                    Ok(next) => next,
+                    Err(e) => {
+                        error!("Failed to create control pipe {}: {}", pipe_name, e);
+                        return;
+                    }
+                };
+                let client = std::mem::replace(&mut server, next);
+                connections.spawn(handle_connection(app_handle.clone(), client));
+                while connections.try_join_next().is_some() {}
+            }
+        }))
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/handy.sock` when available, otherwise `handy.sock` in
+/// the app data directory
+#[cfg(unix)]
+pub fn socket_path(app_handle: &AppHandle) -> Result<PathBuf> {
+    use tauri::Manager;
+
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return Ok(PathBuf::from(runtime_dir).join("handy.sock"));
+    }
+    let app_data_dir = app_handle.path().app_data_dir()?;
+    std::fs::create_dir_all(&app_data_dir)?;
+    Ok(app_data_dir.join("handy.sock"))
+}
+
+/// `\\.\pipe\handy-<user SID>`, so each user of a machine gets their own pipe
+#[cfg(windows)]
+pub fn socket_path(_app_handle: &AppHandle) -> Result<PathBuf> {
+    Ok(PathBuf::from(windows_pipe::pipe_name(
+        &windows_pipe::current_user_sid()?,
+    )))
+}
+
+#[cfg(windows)]
+mod windows_pipe {
+    use anyhow::Result;
+    use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::{CloseHandle, LocalFree, HANDLE, HLOCAL};
+    use windows::Win32::Security::Authorization::{
+        ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW,
+        SDDL_REVISION_1,
+    };
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenUser, PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES, TOKEN_QUERY,
+        TOKEN_USER,
+    };
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    pub fn pipe_name(sid: &str) -> String {
+        format!(r"\\.\pipe\handy-{}", sid)
+    }
+
+    /// The SID of the user running Handy, such as `S-1-5-21-...`
+    pub fn current_user_sid() -> Result<String> {
+        unsafe {
+            let mut token = HANDLE::default();
+            OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)?;
+
+            let mut size = 0u32;
+            // Fails with the buffer size needed
+            let _ = GetTokenInformation(token, TokenUser, None, 0, &mut size);
+            // u64 keeps the buffer aligned for TOKEN_USER
+            let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+            let info = GetTokenInformation(
+                token,
+                TokenUser,
+                Some(buffer.as_mut_ptr().cast()),
+                size,
+                &mut size,
+            );
+            let _ = CloseHandle(token);
+            info?;
+
+            let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+            let mut sid = PWSTR::null();
+            ConvertSidToStringSidW(user.User.Sid, &mut sid)?;
+            let sid_string = sid.to_string();
+            let _ = LocalFree(Some(HLOCAL(sid.0.cast())));
+            Ok(sid_string?)
+        }
+    }
+
+    /// Creates an instance of the pipe that only `sid` may open. The first
+    /// instance fails if the pipe already exists.
+    pub fn create(name: &str, sid: &str, first: bool) -> std::io::Result<NamedPipeServer> {
+        // A protected DACL granting the user full access and nobody else any
+        let sddl = HSTRING::from(format!("D:P(A;;GA;;;{})", sid));
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                &sddl,
+                SDDL_REVISION_1,
+                &mut descriptor,
+                None,
+            )?;
+            let mut attributes = SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: descriptor.0,
+                ..Default::default()
+            };
+            let server = ServerOptions::new()
+                .first_pipe_instance(first)
+                .reject_remote_clients(true)
+                .create_with_security_attributes_raw(
+                    name,
+                    (&mut attributes as *mut SECURITY_ATTRIBUTES).cast(),
+                );
+            let _ = LocalFree(Some(HLOCAL(descriptor.0)));
+            server
+        }
+    }
+}
+
+async fn handle_connection<S>(app_handle: AppHandle, stream: S)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    debug!("Control socket client connected");
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message = match serde_json::from_str::<ControlMessage>(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                let response = json!({ "ok": false, "error": format!("Invalid request: {}", e) });
+                if write_line(&mut writer, &response).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if let ControlRequest::Subscribe { events } = message.request {
+            subscribe(&app_handle, message.id, events, &mut lines, &mut writer).await;
+            break;
+        }
+
+        let app = app_handle.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || execute(&app, message.request))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+        if write_line(&mut writer, &response(message.id, result))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+
+    debug!("Control socket client disconnected");
+}
+
+/// Called on a blocking thread, since actions may wait on audio devices
+fn execute(app: &AppHandle, request: ControlRequest) -> Result<Value, String> {
+    let (binding, command) = match request {
+        ControlRequest::Start { binding } => (binding, BindingCommand::Start),
+        ControlRequest::Stop { binding } => (binding, BindingCommand::Stop),
+        ControlRequest::Toggle { binding } => (binding, BindingCommand::Toggle),
+        ControlRequest::Cancel => {
+            cancel_current_operation(app);
+            return Ok(json!({}));
+        }
+        ControlRequest::Status => {
+            return serde_json::to_value(control::status(app)).map_err(|e| e.to_string());
+        }
+        ControlRequest::Subscribe { .. } => unreachable!("subscribe is handled by the connection"),
+    };
+
+    let active = control::trigger_binding(app, &binding, "control socket", command)?;
+    Ok(json!({ "binding": binding, "active": active }))
+}
+
+async fn subscribe<R, W>(
+    app_handle: &AppHandle,
+    id: Option<Value>,
+    events: Option<Vec<String>>,
+    lines: &mut tokio::io::Lines<BufReader<R>>,
+    writer: &mut W,
+) where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let events = events.unwrap_or_else(|| {
+        SUBSCRIBABLE_EVENTS
+            .iter()
+            .map(|event| event.to_string())
+            .collect()
+    });
+    if let Some(unknown) = events
+        .iter()
+        .find(|event| !SUBSCRIBABLE_EVENTS.contains(&event.as_str()))
+    {
+        let result = Err(format!(
+            "Unknown event '{}'; expected one of {}",
+            unknown,
+            SUBSCRIBABLE_EVENTS.join(", ")
+        ));
+        let _ = write_line(writer, &response(id, result)).await;
+        return;
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listeners = Listeners {
+        app_handle,
+        ids: events
+            .iter()
+            .map(|event| {
+                let tx = tx.clone();
+                let name = event.clone();
+                app_handle.listen_any(event.clone(), move |e| {
+                    let _ = tx.send(event_line(&name, e.payload()));
+                })
+            })
+            .collect(),
+    };
+    drop(tx);
+
+    let result = Ok(json!({ "events": events }));
+    if write_line(writer, &response(id, result)).await.is_ok() {
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => {
+                        if write_line(writer, &event).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                // Further input is ignored; EOF means the client went away
+                line = lines.next_line() => {
+                    if !matches!(line, Ok(Some(_))) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    drop(listeners);
+}
+
+/// Removes the event listeners of a subscription, also when its connection
+/// task is aborted by `stop`
+struct Listeners<'a> {
+    app_handle: &'a AppHandle,
+    ids: Vec<tauri::EventId>,
+}
+
+impl Drop for Listeners<'_> {
+    fn drop(&mut self) {
+        for id in self.ids.drain(..) {
+            self.app_handle.unlisten(id);
+        }
+    }
+}
+
+/// What a subscriber receives for an event with the given JSON payload
+fn event_line(name: &str, payload: &str) -> Value {
+    let payload = serde_json::from_str(payload).unwrap_or(Value::Null);
+    json!({ "event": name, "payload": payload })
+}
+
+fn response(id: Option<Value>, result: Result<Value, String>) -> Value {
+    let mut response = match result {
+        Ok(Value::Object(fields)) => {
+            let mut response = json!({ "ok": true });
+            response.as_object_mut().unwrap().extend(fields);
+            response
+        }
+        Ok(_) => json!({ "ok": true }),
+        Err(e) => json!({ "ok": false, "error": e }),
+    };
+    if let Some(id) = id {
+        response["id"] = id;
+    }
+    response
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, value: &Value) -> std::io::Result<()> {
+    let mut line = value.to_string();
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> ControlMessage {
+        serde_json::from_str(line).unwrap_or_else(|e| panic!("{}: {}", line, e))
+    }
+
+    #[test]
+    fn test_parse_binding_commands() {
+        let message = parse(r#"{"command": "toggle", "binding": "dictate", "id": 7}"#);
+        assert_eq!(message.id, Some(json!(7)));
+        assert!(
+            matches!(message.request, ControlRequest::Toggle { binding } if binding == "dictate")
+        );
+
+        let message = parse(r#"{"command": "start"}"#);
+        assert_eq!(message.id, None);
+        assert!(
+            matches!(message.request, ControlRequest::Start { binding } if binding == "transcribe")
+        );
+
+        let message = parse(r#"{"command": "stop", "id": "a"}"#);
+        assert_eq!(message.id, Some(json!("a")));
+        assert!(
+            matches!(message.request, ControlRequest::Stop { binding } if binding == "transcribe")
+        );
+
+        assert!(matches!(
+            parse(r#"{"command": "cancel"}"#).request,
+            ControlRequest::Cancel
+        ));
+        assert!(matches!(
+            parse(r#"{"command": "status"}"#).request,
+            ControlRequest::Status
+        ));
+    }
+
+    #[test]
+    fn test_parse_subscribe() {
+        let message = parse(r#"{"command": "subscribe"}"#);
+        assert!(matches!(
+            message.request,
+            ControlRequest::Subscribe { events: None }
+        ));
+
+        let message = parse(r#"{"command": "subscribe", "events": ["history-updated"]}"#);
+        assert!(matches!(
+            message.request,
+            ControlRequest::Subscribe { events: Some(events) } if events == ["history-updated"]
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_requests() {
+        for line in [
+            r#"{"command": "explode"}"#,
+            r#"{"binding": "transcribe"}"#,
+            r#"{"command": "toggle", "binding": 3}"#,
+            "toggle",
+        ] {
+            assert!(
+                serde_json::from_str::<ControlMessage>(line).is_err(),
+                "{} was accepted",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        assert_eq!(
+            response(None, Ok(json!({ "binding": "transcribe", "active": true }))),
+            json!({ "ok": true, "binding": "transcribe", "active": true })
+        );
+        assert_eq!(
+            response(Some(json!(1)), Ok(json!({}))),
+            json!({ "ok": true, "id": 1 })
+        );
+        assert_eq!(
+            response(Some(json!("x")), Ok(Value::Null)),
+            json!({ "ok": true, "id": "x" })
+        );
+        assert_eq!(
+            response(None, Err("No binding 'nope'".to_string())),
+            json!({ "ok": false, "error": "No binding 'nope'" })
+        );
+    }
+
+    #[test]
+    fn test_event_line_serialization() {
+        assert_eq!(
+            event_line("recording-state-changed", r#"{"recording":true}"#),
+            json!({ "event": "recording-state-changed", "payload": { "recording": true } })
+        );
+        assert_eq!(
+            event_line("history-updated", ""),
+            json!({ "event": "history-updated", "payload": null })
+        );
+    }
+
+    #[test]
+    fn test_write_line_writes_one_line() {
+        let mut out = Vec::new();
+        let value = json!({ "ok": true, "text": "two\nlines" });
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(write_line(&mut out, &value))
+            .unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches('\n').count(), 1);
+        assert!(out.ends_with('\n'));
+        assert_eq!(serde_json::from_str::<Value>(&out).unwrap(), value);
+    }
+}
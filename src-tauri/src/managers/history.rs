@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Local, Utc};
 use log::{debug, error, info};
 use rusqlite::{params, Connection, OptionalExtension};
@@ -9,7 +9,10 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::actions::ProcessedTranscription;
+use crate::audio_toolkit::corrections::{extract_corrections, suggest_kind, CorrectionKind};
 use crate::audio_toolkit::save_wav_file;
+use crate::managers::transcription::{TranscriptSegment, TranscriptWord, Transcription};
 
 /// Database migrations for transcription history.
 /// Each migration is applied in order. The library tracks which migrations
@@ -31,6 +34,83 @@ static MIGRATIONS: &[M] = &[
     ),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_processed_text TEXT;"),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_process_prompt TEXT;"),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS transcription_segments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            history_id INTEGER NOT NULL,
+            segment_index INTEGER NOT NULL,
+            start_time REAL NOT NULL,
+            end_time REAL NOT NULL,
+            text TEXT NOT NULL,
+            words TEXT NOT NULL DEFAULT '[]'
+        );
+        CREATE INDEX IF NOT EXISTS idx_transcription_segments_history_id
+            ON transcription_segments (history_id);",
+    ),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS transcription_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            history_id INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            model_id TEXT NOT NULL,
+            language TEXT NOT NULL,
+            transcription_text TEXT NOT NULL,
+            post_processed_text TEXT,
+            post_process_prompt TEXT,
+            segments TEXT NOT NULL DEFAULT '[]'
+        );
+        CREATE INDEX IF NOT EXISTS idx_transcription_revisions_history_id
+            ON transcription_revisions (history_id);",
+    ),
+    M::up("ALTER TABLE transcription_history ADD COLUMN edited_text TEXT;"),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS correction_proposals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            heard TEXT NOT NULL,
+            corrected TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            last_seen INTEGER NOT NULL,
+            UNIQUE (heard, corrected)
+        );
+        CREATE TABLE IF NOT EXISTS correction_occurrences (
+            history_id INTEGER NOT NULL,
+            proposal_id INTEGER NOT NULL,
+            PRIMARY KEY (history_id, proposal_id)
+        );",
+    ),
+    M::up(
+        "ALTER TABLE transcription_history ADD COLUMN final_text TEXT;
+        ALTER TABLE transcription_revisions ADD COLUMN final_text TEXT;",
+    ),
+    M::up(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS transcription_history_fts USING fts5(
+            transcription_text,
+            post_processed_text,
+            final_text,
+            edited_text,
+            content = 'transcription_history',
+            content_rowid = 'id',
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+        CREATE TRIGGER IF NOT EXISTS transcription_history_fts_insert
+        AFTER INSERT ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts (rowid, transcription_text, post_processed_text, final_text, edited_text)
+            VALUES (new.id, new.transcription_text, new.post_processed_text, new.final_text, new.edited_text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS transcription_history_fts_delete
+        AFTER DELETE ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts (transcription_history_fts, rowid, transcription_text, post_processed_text, final_text, edited_text)
+            VALUES ('delete', old.id, old.transcription_text, old.post_processed_text, old.final_text, old.edited_text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS transcription_history_fts_update
+        AFTER UPDATE OF transcription_text, post_processed_text, final_text, edited_text ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts (transcription_history_fts, rowid, transcription_text, post_processed_text, final_text, edited_text)
+            VALUES ('delete', old.id, old.transcription_text, old.post_processed_text, old.final_text, old.edited_text);
+            INSERT INTO transcription_history_fts (rowid, transcription_text, post_processed_text, final_text, edited_text)
+            VALUES (new.id, new.transcription_text, new.post_processed_text, new.final_text, new.edited_text);
+        END;
+        INSERT INTO transcription_history_fts (transcription_history_fts) VALUES ('rebuild');",
+    ),
 ];
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -41,8 +121,61 @@ pub struct HistoryEntry {
     pub saved: bool,
     pub title: String,
     pub transcription_text: String,
+    /// The output of Chinese variant conversion or LLM post-processing
     pub post_processed_text: Option<String>,
     pub post_process_prompt: Option<String>,
+    /// The text that was pasted, when voice commands or replacement rules
+    /// changed it after any post-processing
+    pub final_text: Option<String>,
+    /// The text as corrected by the user. The transcription itself is kept
+    /// unchanged.
+    pub edited_text: Option<String>,
+}
+
+/// A re-transcription of a history entry's recording. The original
+/// transcription stays on the entry itself; revisions are kept alongside it.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptionRevision {
+    pub id: i64,
+    pub history_id: i64,
+    pub created_at: i64,
+    pub model_id: String,
+    pub language: String,
+    pub transcription_text: String,
+    pub post_processed_text: Option<String>,
+    pub post_process_prompt: Option<String>,
+    pub final_text: Option<String>,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// A history entry matching a search, with the matched text highlighted in
+/// `snippet` using `<mark>` tags. Lower `rank` is a better match.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistorySearchResult {
+    pub entry: HistoryEntry,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+/// A word or phrase the user keeps correcting in their history, offered to
+/// be learned as a custom word or replacement rule
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct CorrectionProposal {
+    pub id: i64,
+    /// What the transcription said, lowercase
+    pub heard: String,
+    /// What the user typed instead
+    pub corrected: String,
+    /// How many history entries the user made this correction in
+    pub occurrences: i64,
+    pub last_seen: i64,
+    pub suggested_kind: CorrectionKind,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub total: i64,
 }
 
 pub struct HistoryManager {
@@ -181,26 +314,22 @@ impl HistoryManager {
         &self,
         audio_samples: Vec<f32>,
         transcription_text: String,
-        post_processed_text: Option<String>,
-        post_process_prompt: Option<String>,
+        segments: Vec<TranscriptSegment>,
+        processed: ProcessedTranscription,
     ) -> Result<()> {
-        let timestamp = Utc::now().timestamp();
-        let file_name = format!("handy-{}.wav", timestamp);
-        let title = self.format_timestamp_title(timestamp);
+        let now = Utc::now();
+        let timestamp = now.timestamp();
+        // Back-to-back utterances can be saved within the same second
+        let file_name = format!("handy-{}.wav", now.timestamp_millis());
 
         // Save WAV file
         let file_path = self.recordings_dir.join(&file_name);
         save_wav_file(file_path, &audio_samples).await?;
 
         // Save to database
-        self.save_to_database(
-            file_name,
-            timestamp,
-            title,
-            transcription_text,
-            post_processed_text,
-            post_process_prompt,
-        )?;
+        let history_id =
+            self.save_to_database(file_name, timestamp, transcription_text, processed)?;
+        self.save_segments(history_id, &segments)?;
 
         // Clean up old entries
         self.cleanup_old_entries()?;
@@ -217,21 +346,90 @@ impl HistoryManager {
         &self,
         file_name: String,
         timestamp: i64,
-        title: String,
         transcription_text: String,
-        post_processed_text: Option<String>,
-        post_process_prompt: Option<String>,
-    ) -> Result<()> {
+        processed: ProcessedTranscription,
+    ) -> Result<i64> {
+        let title = self.format_timestamp_title(timestamp);
+        let final_text = rewritten_text(&transcription_text, &processed);
         let conn = self.get_connection()?;
         conn.execute(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![file_name, timestamp, false, title, transcription_text, post_processed_text, post_process_prompt],
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, final_text) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![file_name, timestamp, false, title, transcription_text, processed.post_processed_text, processed.post_process_prompt, final_text],
         )?;
 
         debug!("Saved transcription to database");
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn save_segments(&self, history_id: i64, segments: &[TranscriptSegment]) -> Result<()> {
+        if segments.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO transcription_segments (history_id, segment_index, start_time, end_time, text, words) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for (index, segment) in segments.iter().enumerate() {
+                let words = serde_json::to_string(&segment.words)?;
+                stmt.execute(params![
+                    history_id,
+                    index as i64,
+                    segment.start as f64,
+                    segment.end as f64,
+                    segment.text,
+                    words
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        debug!(
+            "Saved {} segments for history entry {}",
+            segments.len(),
+            history_id
+        );
         Ok(())
     }
 
+    pub async fn get_entry_segments(&self, history_id: i64) -> Result<Vec<TranscriptSegment>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT start_time, end_time, text, words FROM transcription_segments WHERE history_id = ?1 ORDER BY segment_index",
+        )?;
+
+        let rows = stmt.query_map(params![history_id], |row| {
+            Ok((
+                row.get::<_, f64>("start_time")?,
+                row.get::<_, f64>("end_time")?,
+                row.get::<_, String>("text")?,
+                row.get::<_, String>("words")?,
+            ))
+        })?;
+
+        let mut segments = Vec::new();
+        for row in rows {
+            let (start, end, text, words) = row?;
+            let words: Vec<TranscriptWord> = serde_json::from_str(&words).unwrap_or_else(|e| {
+                error!(
+                    "Failed to parse word timestamps for history entry {}: {}",
+                    history_id, e
+                );
+                Vec::new()
+            });
+            segments.push(TranscriptSegment {
+                start: start as f32,
+                end: end as f32,
+                text,
+                words,
+            });
+        }
+
+        Ok(segments)
+    }
+
     pub fn cleanup_old_entries(&self) -> Result<()> {
         let retention_period = crate::settings::get_recording_retention_period(&self.app_handle);
 
@@ -266,6 +464,14 @@ impl HistoryManager {
                 "DELETE FROM transcription_history WHERE id = ?1",
                 params![id],
             )?;
+            conn.execute(
+                "DELETE FROM transcription_segments WHERE history_id = ?1",
+                params![id],
+            )?;
+            conn.execute(
+                "DELETE FROM transcription_revisions WHERE history_id = ?1",
+                params![id],
+            )?;
 
             // Delete WAV file
             let file_path = self.recordings_dir.join(file_name);
@@ -355,7 +561,7 @@ impl HistoryManager {
     pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt FROM transcription_history ORDER BY timestamp DESC"
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, final_text, edited_text FROM transcription_history ORDER BY timestamp DESC"
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -368,6 +574,219 @@ impl HistoryManager {
                 transcription_text: row.get("transcription_text")?,
                 post_processed_text: row.get("post_processed_text")?,
                 post_process_prompt: row.get("post_process_prompt")?,
+                final_text: row.get("final_text")?,
+                edited_text: row.get("edited_text")?,
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+
+        Ok(entries)
+    }
+
+    /// Stores a re-transcription of an entry as a new revision
+    pub fn save_revision(
+        &self,
+        history_id: i64,
+        model_id: String,
+        language: String,
+        transcription: Transcription,
+        processed: ProcessedTranscription,
+    ) -> Result<TranscriptionRevision> {
+        let Transcription {
+            text: transcription_text,
+            segments,
+        } = transcription;
+        let final_text = rewritten_text(&transcription_text, &processed);
+        let ProcessedTranscription {
+            post_processed_text,
+            post_process_prompt,
+            ..
+        } = processed;
+        let created_at = Utc::now().timestamp();
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO transcription_revisions (history_id, created_at, model_id, language, transcription_text, post_processed_text, post_process_prompt, final_text, segments) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                history_id,
+                created_at,
+                model_id,
+                language,
+                transcription_text,
+                post_processed_text,
+                post_process_prompt,
+                final_text,
+                serde_json::to_string(&segments)?
+            ],
+        )?;
+
+        let revision = TranscriptionRevision {
+            id: conn.last_insert_rowid(),
+            history_id,
+            created_at,
+            model_id,
+            language,
+            transcription_text,
+            post_processed_text,
+            post_process_prompt,
+            final_text,
+            segments,
+        };
+        debug!(
+            "Saved revision {} for history entry {} (model: {})",
+            revision.id, history_id, revision.model_id
+        );
+
+        // Emit history updated event
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+
+        Ok(revision)
+    }
+
+    /// All revisions of an entry, oldest first
+    pub async fn get_revisions(&self, history_id: i64) -> Result<Vec<TranscriptionRevision>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, history_id, created_at, model_id, language, transcription_text, post_processed_text, post_process_prompt, final_text, segments FROM transcription_revisions WHERE history_id = ?1 ORDER BY created_at ASC, id ASC",
+        )?;
+
+        let rows = stmt.query_map(params![history_id], |row| {
+            let segments: String = row.get("segments")?;
+            Ok(TranscriptionRevision {
+                id: row.get("id")?,
+                history_id: row.get("history_id")?,
+                created_at: row.get("created_at")?,
+                model_id: row.get("model_id")?,
+                language: row.get("language")?,
+                transcription_text: row.get("transcription_text")?,
+                post_processed_text: row.get("post_processed_text")?,
+                post_process_prompt: row.get("post_process_prompt")?,
+                final_text: row.get("final_text")?,
+                segments: serde_json::from_str(&segments).unwrap_or_default(),
+            })
+        })?;
+
+        let mut revisions = Vec::new();
+        for row in rows {
+            revisions.push(row?);
+        }
+
+        Ok(revisions)
+    }
+
+    /// One page of entries, newest first, along with the total entry count
+    pub async fn get_history_entries_page(&self, limit: i64, offset: i64) -> Result<HistoryPage> {
+        let entries = self.query_entries(
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, final_text, edited_text FROM transcription_history ORDER BY timestamp DESC LIMIT ?1 OFFSET ?2",
+            params![limit, offset],
+        )?;
+
+        let conn = self.get_connection()?;
+        let total: i64 =
+            conn.query_row("SELECT COUNT(*) FROM transcription_history", [], |row| {
+                row.get(0)
+            })?;
+
+        Ok(HistoryPage { entries, total })
+    }
+
+    /// Full-text search over the raw, post-processed, pasted and edited transcriptions, best matches first
+    pub async fn search_history(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<HistorySearchResult>> {
+        let match_query = fts_match_query(query);
+        if match_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT h.id, h.file_name, h.timestamp, h.saved, h.title, h.transcription_text, h.post_processed_text, h.post_process_prompt, h.final_text, h.edited_text,
+                    snippet(transcription_history_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+                    bm25(transcription_history_fts) AS rank
+             FROM transcription_history_fts
+             JOIN transcription_history h ON h.id = transcription_history_fts.rowid
+             WHERE transcription_history_fts MATCH ?1
+             ORDER BY rank
+             LIMIT ?2 OFFSET ?3",
+        )?;
+
+        let rows = stmt.query_map(params![match_query, limit, offset], |row| {
+            Ok(HistorySearchResult {
+                entry: HistoryEntry {
+                    id: row.get("id")?,
+                    file_name: row.get("file_name")?,
+                    timestamp: row.get("timestamp")?,
+                    saved: row.get("saved")?,
+                    title: row.get("title")?,
+                    transcription_text: row.get("transcription_text")?,
+                    post_processed_text: row.get("post_processed_text")?,
+                    post_process_prompt: row.get("post_process_prompt")?,
+                    final_text: row.get("final_text")?,
+                    edited_text: row.get("edited_text")?,
+                },
+                snippet: row.get("snippet")?,
+                rank: row.get("rank")?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+
+        debug!(
+            "History search for {:?} returned {} results",
+            query,
+            results.len()
+        );
+        Ok(results)
+    }
+
+    /// Entries recorded between `from` and `to` (unix seconds, inclusive), oldest first
+    pub async fn get_entries_in_range(&self, from: i64, to: i64) -> Result<Vec<HistoryEntry>> {
+        self.query_entries(
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, final_text, edited_text FROM transcription_history WHERE timestamp >= ?1 AND timestamp <= ?2 ORDER BY timestamp ASC",
+            params![from, to],
+        )
+    }
+
+    /// All entries marked as saved, oldest first
+    pub async fn get_saved_entries(&self) -> Result<Vec<HistoryEntry>> {
+        self.query_entries(
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, final_text, edited_text FROM transcription_history WHERE saved = 1 ORDER BY timestamp ASC",
+            [],
+        )
+    }
+
+    fn query_entries<P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(sql)?;
+
+        let rows = stmt.query_map(params, |row| {
+            Ok(HistoryEntry {
+                id: row.get("id")?,
+                file_name: row.get("file_name")?,
+                timestamp: row.get("timestamp")?,
+                saved: row.get("saved")?,
+                title: row.get("title")?,
+                transcription_text: row.get("transcription_text")?,
+                post_processed_text: row.get("post_processed_text")?,
+                post_process_prompt: row.get("post_process_prompt")?,
+                final_text: row.get("final_text")?,
+                edited_text: row.get("edited_text")?,
             })
         })?;
 
@@ -406,6 +825,128 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// Stores the user's edit of an entry's text, or reverts to the original
+    /// when `text` is `None`. Every word or phrase the edit replaced is
+    /// counted towards a correction proposal.
+    pub async fn update_entry_text(&self, id: i64, text: Option<String>) -> Result<()> {
+        let entry = self
+            .get_entry_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow!("History entry {} not found", id))?;
+        let delivered = entry
+            .final_text
+            .as_deref()
+            .or(entry.post_processed_text.as_deref())
+            .unwrap_or(&entry.transcription_text);
+
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let corrections = record_edit(&tx, id, delivered, text.as_deref(), Utc::now().timestamp())?;
+        tx.commit()?;
+
+        debug!(
+            "Updated text of history entry {} ({} corrections)",
+            id, corrections
+        );
+
+        // Emit history updated event
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+        if let Err(e) = self.app_handle.emit("correction-proposals-updated", ()) {
+            error!("Failed to emit correction-proposals-updated event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Corrections the user hasn't accepted or dismissed yet, most frequent first
+    pub async fn get_correction_proposals(&self) -> Result<Vec<CorrectionProposal>> {
+        self.query_proposals(
+            "SELECT p.id, p.heard, p.corrected, COUNT(o.history_id) AS occurrences, p.last_seen
+             FROM correction_proposals p
+             JOIN correction_occurrences o ON o.proposal_id = p.id
+             WHERE p.status = 'pending'
+             GROUP BY p.id
+             ORDER BY occurrences DESC, p.last_seen DESC",
+            [],
+        )
+    }
+
+    pub async fn get_correction_proposal(&self, id: i64) -> Result<Option<CorrectionProposal>> {
+        let proposals = self.query_proposals(
+            "SELECT p.id, p.heard, p.corrected, COUNT(o.history_id) AS occurrences, p.last_seen
+             FROM correction_proposals p
+             LEFT JOIN correction_occurrences o ON o.proposal_id = p.id
+             WHERE p.id = ?1
+             GROUP BY p.id",
+            params![id],
+        )?;
+        Ok(proposals.into_iter().next())
+    }
+
+    /// Marks a proposal as accepted or dismissed. Either way it is not offered
+    /// again, however often the correction is made afterwards.
+    pub async fn resolve_correction_proposal(&self, id: i64, accepted: bool) -> Result<()> {
+        let status = if accepted { "accepted" } else { "dismissed" };
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE correction_proposals SET status = ?1 WHERE id = ?2",
+            params![status, id],
+        )?;
+
+        debug!("Correction proposal {} {}", id, status);
+
+        if let Err(e) = self.app_handle.emit("correction-proposals-updated", ()) {
+            error!("Failed to emit correction-proposals-updated event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn query_proposals<P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> Result<Vec<CorrectionProposal>> {
+        let settings = crate::settings::get_settings(&self.app_handle);
+        let language = if settings.translate_to_english {
+            "en"
+        } else {
+            settings.selected_language.as_str()
+        };
+
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(sql)?;
+
+        let rows = stmt.query_map(params, |row| {
+            let heard: String = row.get("heard")?;
+            let corrected: String = row.get("corrected")?;
+            let suggested_kind = suggest_kind(
+                &heard,
+                &corrected,
+                &settings.custom_words,
+                settings.word_correction_threshold,
+                language,
+            );
+            Ok(CorrectionProposal {
+                id: row.get("id")?,
+                heard,
+                corrected,
+                occurrences: row.get("occurrences")?,
+                last_seen: row.get("last_seen")?,
+                suggested_kind,
+            })
+        })?;
+
+        let mut proposals = Vec::new();
+        for row in rows {
+            proposals.push(row?);
+        }
+
+        Ok(proposals)
+    }
+
     pub fn get_audio_file_path(&self, file_name: &str) -> PathBuf {
         self.recordings_dir.join(file_name)
     }
@@ -413,7 +954,7 @@ impl HistoryManager {
     pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, final_text, edited_text
              FROM transcription_history WHERE id = ?1",
         )?;
 
@@ -428,6 +969,8 @@ impl HistoryManager {
                     transcription_text: row.get("transcription_text")?,
                     post_processed_text: row.get("post_processed_text")?,
                     post_process_prompt: row.get("post_process_prompt")?,
+                    final_text: row.get("final_text")?,
+                    edited_text: row.get("edited_text")?,
                 })
             })
             .optional()?;
@@ -455,6 +998,14 @@ impl HistoryManager {
             "DELETE FROM transcription_history WHERE id = ?1",
             params![id],
         )?;
+        conn.execute(
+            "DELETE FROM transcription_segments WHERE history_id = ?1",
+            params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM transcription_revisions WHERE history_id = ?1",
+            params![id],
+        )?;
 
         debug!("Deleted history entry with id: {}", id);
 
@@ -476,3 +1027,272 @@ impl HistoryManager {
         }
     }
 }
+
+/// Saves an entry's edit, or clears it when `text` is `None` or matches the
+/// delivered text, and replaces the corrections the entry contributes to
+/// proposals. Corrections are always taken between the delivered text and
+/// the edit, so re-editing never turns the user's earlier edit into something
+/// "heard", and reverting withdraws them. Returns how many corrections the
+/// edit contains.
+fn record_edit(
+    conn: &Connection,
+    history_id: i64,
+    delivered: &str,
+    text: Option<&str>,
+    now: i64,
+) -> Result<usize> {
+    let edited_text = text.filter(|text| *text != delivered);
+    conn.execute(
+        "UPDATE transcription_history SET edited_text = ?1 WHERE id = ?2",
+        params![edited_text, history_id],
+    )?;
+    conn.execute(
+        "DELETE FROM correction_occurrences WHERE history_id = ?1",
+        params![history_id],
+    )?;
+
+    let corrections = edited_text
+        .map(|text| extract_corrections(delivered, text))
+        .unwrap_or_default();
+    for correction in &corrections {
+        conn.execute(
+            "INSERT INTO correction_proposals (heard, corrected, last_seen) VALUES (?1, ?2, ?3)
+             ON CONFLICT (heard, corrected) DO UPDATE SET last_seen = excluded.last_seen",
+            params![correction.heard, correction.corrected, now],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO correction_occurrences (history_id, proposal_id)
+             SELECT ?1, id FROM correction_proposals WHERE heard = ?2 AND corrected = ?3",
+            params![history_id, correction.heard, correction.corrected],
+        )?;
+    }
+
+    // Pending proposals that no entry contributes to anymore are withdrawn
+    conn.execute(
+        "DELETE FROM correction_proposals
+         WHERE status = 'pending' AND id NOT IN (SELECT proposal_id FROM correction_occurrences)",
+        [],
+    )?;
+
+    Ok(corrections.len())
+}
+
+/// The pasted text, if voice commands or replacement rules made it differ
+/// from the post-processed or raw transcription
+fn rewritten_text(transcription_text: &str, processed: &ProcessedTranscription) -> Option<String> {
+    let post_processed = processed
+        .post_processed_text
+        .as_deref()
+        .unwrap_or(transcription_text);
+    (processed.final_text != post_processed).then(|| processed.final_text.clone())
+}
+
+/// Turns free-form user input into an FTS5 query. Every term is quoted so
+/// FTS5 operators in the input are searched literally, and the last term is
+/// prefix-matched so results update while typing.
+fn fts_match_query(input: &str) -> String {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    match terms.split_last() {
+        Some((last, rest)) => {
+            let mut query = rest.to_vec();
+            query.push(format!("{}*", last));
+            query.join(" ")
+        }
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_db(entries: &[&str]) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        Migrations::new(MIGRATIONS.to_vec())
+            .to_latest(&mut conn)
+            .unwrap();
+        for text in entries {
+            conn.execute(
+                "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text)
+                 VALUES ('a.wav', 0, 0, 'title', ?1)",
+                params![text],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn pending_proposals(conn: &Connection) -> Vec<(String, String, i64)> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT p.heard, p.corrected, COUNT(o.history_id)
+                 FROM correction_proposals p
+                 JOIN correction_occurrences o ON o.proposal_id = p.id
+                 WHERE p.status = 'pending'
+                 GROUP BY p.id
+                 ORDER BY p.heard, p.corrected",
+            )
+            .unwrap();
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap();
+        rows.map(|row| row.unwrap()).collect()
+    }
+
+    fn proposal(heard: &str, corrected: &str, occurrences: i64) -> (String, String, i64) {
+        (heard.to_string(), corrected.to_string(), occurrences)
+    }
+
+    #[test]
+    fn test_edit_re_edit_and_revert_corrections() {
+        let delivered = "We store it in post grass today.";
+        let conn = history_db(&[delivered, delivered]);
+
+        record_edit(
+            &conn,
+            1,
+            delivered,
+            Some("We store it in Postgrs today."),
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            pending_proposals(&conn),
+            vec![proposal("post grass", "Postgrs", 1)]
+        );
+
+        // Fixing a typo in the edit replaces the entry's correction instead of
+        // proposing the typo as something that was heard
+        record_edit(
+            &conn,
+            1,
+            delivered,
+            Some("We store it in Postgres today."),
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            pending_proposals(&conn),
+            vec![proposal("post grass", "Postgres", 1)]
+        );
+
+        // Saving the same edit again still counts the entry once
+        record_edit(
+            &conn,
+            1,
+            delivered,
+            Some("We store it in Postgres today."),
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            pending_proposals(&conn),
+            vec![proposal("post grass", "Postgres", 1)]
+        );
+
+        // Occurrences count distinct entries
+        record_edit(
+            &conn,
+            2,
+            delivered,
+            Some("We store it in Postgres today."),
+            4,
+        )
+        .unwrap();
+        assert_eq!(
+            pending_proposals(&conn),
+            vec![proposal("post grass", "Postgres", 2)]
+        );
+
+        // Reverting withdraws the entry's contribution
+        record_edit(&conn, 1, delivered, None, 5).unwrap();
+        assert_eq!(
+            pending_proposals(&conn),
+            vec![proposal("post grass", "Postgres", 1)]
+        );
+        let edited: Option<String> = conn
+            .query_row(
+                "SELECT edited_text FROM transcription_history WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(edited, None);
+
+        // Revert then re-edit doesn't count the entry twice
+        record_edit(
+            &conn,
+            1,
+            delivered,
+            Some("We store it in Postgres today."),
+            6,
+        )
+        .unwrap();
+        assert_eq!(
+            pending_proposals(&conn),
+            vec![proposal("post grass", "Postgres", 2)]
+        );
+
+        record_edit(&conn, 1, delivered, None, 7).unwrap();
+        record_edit(&conn, 2, delivered, Some(delivered), 8).unwrap();
+        assert!(pending_proposals(&conn).is_empty());
+    }
+
+    #[test]
+    fn test_fts_match_query_quotes_terms() {
+        assert_eq!(fts_match_query("hello world"), "\"hello\" \"world\"*");
+        assert_eq!(fts_match_query("  spaced   out  "), "\"spaced\" \"out\"*");
+        assert_eq!(fts_match_query("say \"hi\""), "\"say\" \"\"\"hi\"\"\"*");
+    }
+
+    #[test]
+    fn test_fts_match_query_escapes_operators() {
+        assert_eq!(fts_match_query("*"), "\"*\"*");
+        assert_eq!(fts_match_query("-draft"), "\"-draft\"*");
+        assert_eq!(fts_match_query("a NEAR b"), "\"a\" \"NEAR\" \"b\"*");
+        assert_eq!(fts_match_query("NEAR(a b)"), "\"NEAR(a\" \"b)\"*");
+        assert_eq!(fts_match_query("x OR y"), "\"x\" \"OR\" \"y\"*");
+    }
+
+    #[test]
+    fn test_fts_match_query_empty_input() {
+        assert_eq!(fts_match_query(""), "");
+        assert_eq!(fts_match_query("   \t\n "), "");
+    }
+
+    #[test]
+    fn test_fts_match_query_runs_literally() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE notes USING fts5(text, tokenize = 'unicode61 remove_diacritics 2');
+             INSERT INTO notes VALUES ('meet near the station');
+             INSERT INTO notes VALUES ('a non-stop flight');
+             INSERT INTO notes VALUES ('say \"hello\" to the café');",
+        )
+        .unwrap();
+        let search = |input: &str| -> Vec<String> {
+            let mut stmt = conn
+                .prepare("SELECT text FROM notes WHERE notes MATCH ?1 ORDER BY rowid")
+                .unwrap();
+            let rows = stmt
+                .query_map([fts_match_query(input)], |row| row.get(0))
+                .unwrap();
+            rows.map(|row| row.unwrap()).collect()
+        };
+
+        // Operators and stray punctuation are valid queries that match literally
+        assert_eq!(search("NEAR"), vec!["meet near the station"]);
+        assert_eq!(search("NEAR(meet station)"), Vec::<String>::new());
+        assert_eq!(search("non-stop"), vec!["a non-stop flight"]);
+        assert_eq!(search("- meet"), vec!["meet near the station"]);
+        assert_eq!(search("\"hello"), vec!["say \"hello\" to the café"]);
+        assert_eq!(search("*"), Vec::<String>::new());
+        // The last term is a prefix and diacritics are ignored
+        assert_eq!(search("stat"), vec!["meet near the station"]);
+        assert_eq!(search("cafe"), vec!["say \"hello\" to the café"]);
+    }
+}
@@ -1,4 +1,6 @@
+pub mod api_server;
 pub mod audio;
+pub mod control_socket;
 pub mod history;
 pub mod model;
 pub mod transcription;
@@ -0,0 +1,223 @@
+use super::{EngineType, FileDigest, ModelInfo};
+use anyhow::Result;
+use base64::Engine as _;
+use minisign_verify::{PublicKey, Signature};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Shipped with the app and used until a newer signed catalog is fetched
+const BUNDLED_CATALOG: &str = include_str!("catalog.json");
+
+/// The release signing key from `tauri.conf.json`. Published catalogs are
+/// signed with `tauri signer sign`, the same way as app updates.
+const CATALOG_PUBLIC_KEY: &str = "RWT5AWYglSC3utQf/3c8jWaZ5ECl7vFNU3R/Yj0UwfDSJCPk1qyDQl//";
+
+/// The list of downloadable models. A new version of a model keeps its id
+/// and filename and bumps `version`, so installed copies can be updated in
+/// place.
+#[derive(Debug, Deserialize)]
+pub struct Catalog {
+    /// Increases with every published catalog; an older catalog never
+    /// replaces a newer one
+    pub revision: u64,
+    pub models: Vec<CatalogModel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogModel {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub filename: String,
+    pub url: Option<String>,
+    pub size_mb: u64,
+    #[serde(default)]
+    pub is_directory: bool,
+    pub engine_type: EngineType,
+    pub accuracy_score: f32,
+    pub speed_score: f32,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// Language codes the model can transcribe; absent means every language
+    /// the engine supports
+    #[serde(default)]
+    pub languages: Option<Vec<String>>,
+    /// SHA-256 of the file or archive that `url` serves
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Digests of the files a directory model's archive extracts to, keyed
+    /// by their path inside the model directory
+    #[serde(default)]
+    pub files: Option<BTreeMap<String, FileDigest>>,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+impl Catalog {
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_CATALOG).expect("Bundled model catalog is invalid")
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| anyhow::anyhow!("Invalid model catalog: {}", e))
+    }
+
+    /// Parses a downloaded catalog after checking its signature
+    pub fn parse_signed(json: &str, signature: &str) -> Result<Self> {
+        verify_signature(json.as_bytes(), signature, CATALOG_PUBLIC_KEY)?;
+        Self::parse(json)
+    }
+
+    pub fn into_models(self) -> HashMap<String, ModelInfo> {
+        self.models
+            .into_iter()
+            .map(|model| (model.id.clone(), model.into_model_info()))
+            .collect()
+    }
+}
+
+impl CatalogModel {
+    fn into_model_info(self) -> ModelInfo {
+        ModelInfo {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            filename: self.filename,
+            url: self.url,
+            size_mb: self.size_mb,
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory: self.is_directory,
+            engine_type: self.engine_type,
+            accuracy_score: self.accuracy_score,
+            speed_score: self.speed_score,
+            is_custom: false,
+            sha256: self.sha256.map(|hash| hash.to_lowercase()),
+            version: self.version,
+            languages: self.languages,
+            update_available: false,
+            expected_files: self.files.map(|files| {
+                files
+                    .into_iter()
+                    .map(|(path, digest)| {
+                        let sha256 = digest.sha256.to_lowercase();
+                        (path, FileDigest { sha256, ..digest })
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+fn verify_signature(data: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let public_key = PublicKey::from_base64(public_key)
+        .map_err(|e| anyhow::anyhow!("Invalid catalog public key: {}", e))?;
+
+    // `tauri signer` base64-encodes the minisign signature file
+    let signature = if signature.trim_start().starts_with("untrusted comment:") {
+        signature.to_string()
+    } else {
+        let decoded = base64::engine::general_purpose::STANDARD.decode(signature.trim())?;
+        String::from_utf8(decoded)?
+    };
+    let signature = Signature::decode(&signature)
+        .map_err(|e| anyhow::anyhow!("Invalid catalog signature: {}", e))?;
+
+    public_key
+        .verify(data, &signature, false)
+        .map_err(|e| anyhow::anyhow!("Model catalog signature check failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Throwaway key pair used only to sign the fixtures below
+    const TEST_PUBLIC_KEY: &str = "RWQBI0VniavN71DGbjM0L8GEt+d/vyMRomaRIkABISKZbn2FbXizyRWi";
+    const SIGNED_JSON: &str = r#"{"revision":2,"models":[]}"#;
+    const SIGNATURE: &str = "untrusted comment: signature from tauri secret key
+RUQBI0VniavN7wImhp1PCvufLNKeLZ0JYJ0CRde5msP1QjPWZwrAOnefVggx16TxHmsToDJvm9dCrZW7qQ+LxlzeNSYGRsMF/Qk=
+trusted comment: timestamp:1760000000\tfile:catalog.json
+FwTFrtkh1dbvRWAQ3HDkain29X7YMj48HBLQ6GKKbKDEFk+EXsq+cGJO1RTMCbMQph+G57souOIh7MfoQy9pAg==
+";
+
+    #[test]
+    fn test_bundled_catalog_parses_with_unique_ids_and_filenames() {
+        let catalog = Catalog::bundled();
+        assert!(!catalog.models.is_empty());
+
+        let mut ids = HashSet::new();
+        let mut filenames = HashSet::new();
+        for model in &catalog.models {
+            assert!(ids.insert(&model.id), "duplicate id {}", model.id);
+            assert!(
+                filenames.insert(&model.filename),
+                "duplicate filename {}",
+                model.filename
+            );
+        }
+    }
+
+    fn is_sha256_hex(hash: &str) -> bool {
+        hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    #[test]
+    fn test_bundled_downloads_have_checksums() {
+        for model in Catalog::bundled().models {
+            if model.url.is_none() {
+                continue;
+            }
+            let sha256 = model.sha256.as_deref().unwrap_or_default();
+            assert!(
+                is_sha256_hex(sha256),
+                "model {} has no SHA-256 for its download",
+                model.id
+            );
+
+            if model.is_directory {
+                let files = model.files.unwrap_or_default();
+                assert!(!files.is_empty(), "model {} has no file digests", model.id);
+                for (path, digest) in files {
+                    assert!(
+                        is_sha256_hex(&digest.sha256),
+                        "model {} has no SHA-256 for {}",
+                        model.id,
+                        path
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_accepts_raw_and_base64_signatures() {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(SIGNATURE);
+
+        assert!(verify_signature(SIGNED_JSON.as_bytes(), SIGNATURE, TEST_PUBLIC_KEY).is_ok());
+        assert!(verify_signature(SIGNED_JSON.as_bytes(), &encoded, TEST_PUBLIC_KEY).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_tampered_body() {
+        let tampered = SIGNED_JSON.replace("2", "3");
+
+        assert!(verify_signature(tampered.as_bytes(), SIGNATURE, TEST_PUBLIC_KEY).is_err());
+    }
+
+    #[test]
+    fn test_rejects_tampered_signature() {
+        let tampered = SIGNATURE.replace("wImhp1P", "wImhq1P");
+
+        assert!(verify_signature(SIGNED_JSON.as_bytes(), &tampered, TEST_PUBLIC_KEY).is_err());
+    }
+
+    #[test]
+    fn test_rejects_signature_from_another_key() {
+        assert!(Catalog::parse_signed(SIGNED_JSON, SIGNATURE).is_err());
+    }
+}
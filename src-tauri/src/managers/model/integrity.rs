@@ -0,0 +1,283 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+/// What an installed model looked like right after it was downloaded, stored
+/// as `manifests/<model id>.json` in the models directory. Later checks
+/// compare the files on disk against it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ModelManifest {
+    /// SHA-256 of the downloaded file or archive
+    pub sha256: Option<String>,
+    /// Catalog version that was installed
+    #[serde(default)]
+    pub version: Option<u32>,
+    /// Whether the download was checked against checksums from the catalog.
+    /// Otherwise the files are only a snapshot of whatever was downloaded.
+    #[serde(default)]
+    pub verified: bool,
+    /// Every installed file, keyed by its path relative to the model directory
+    /// (or by the file name for single-file models)
+    pub files: BTreeMap<String, FileDigest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FileDigest {
+    pub size: u64,
+    pub sha256: String,
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+pub fn read_manifest(path: &Path) -> Result<Option<ModelManifest>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+pub fn write_manifest(path: &Path, manifest: &ModelManifest) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+/// Hashes an installed model: the file itself, or every file under the
+/// directory for directory-based models
+pub fn build_manifest(
+    model_path: &Path,
+    is_directory: bool,
+    sha256: Option<String>,
+) -> Result<ModelManifest> {
+    let mut files = BTreeMap::new();
+    if is_directory {
+        let mut paths = Vec::new();
+        collect_files(model_path, &mut paths)?;
+        for path in paths {
+            let relative = path
+                .strip_prefix(model_path)?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(relative, digest(&path)?);
+        }
+    } else {
+        files.insert(file_key(model_path), digest(model_path)?);
+    }
+
+    Ok(ModelManifest {
+        sha256,
+        version: None,
+        verified: false,
+        files,
+    })
+}
+
+/// Compares an installed model against a manifest, either the one recorded at
+/// install time or one built from the catalog's digests, and describes every
+/// missing or changed file. An empty result means the model is intact.
+pub fn check_manifest(
+    model_path: &Path,
+    is_directory: bool,
+    manifest: &ModelManifest,
+) -> Result<Vec<String>> {
+    let mut problems = Vec::new();
+    for (name, expected) in &manifest.files {
+        let path = if is_directory {
+            model_path.join(name)
+        } else {
+            model_path.to_path_buf()
+        };
+
+        let Ok(metadata) = path.metadata() else {
+            problems.push(format!("{} is missing", name));
+            continue;
+        };
+        // Sizes are cheap to compare, so only hash files that could still match
+        if metadata.len() != expected.size {
+            problems.push(format!(
+                "{} has {} bytes, expected {}",
+                name,
+                metadata.len(),
+                expected.size
+            ));
+        } else if sha256_file(&path)? != expected.sha256 {
+            problems.push(format!("{} does not match its checksum", name));
+        }
+    }
+    Ok(problems)
+}
+
+fn digest(path: &Path) -> Result<FileDigest> {
+    Ok(FileDigest {
+        size: path.metadata()?.len(),
+        sha256: sha256_file(path)?,
+    })
+}
+
+fn file_key(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // SHA-256 of "abc"
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "handy-integrity-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_build_manifest_for_file() {
+        let dir = test_dir("file");
+        let model = dir.join("ggml-small.bin");
+        fs::write(&model, "abc").unwrap();
+
+        let manifest = build_manifest(&model, false, Some("download-hash".to_string())).unwrap();
+        assert_eq!(manifest.sha256.as_deref(), Some("download-hash"));
+        assert_eq!(manifest.version, None);
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(
+            manifest.files["ggml-small.bin"],
+            FileDigest {
+                size: 3,
+                sha256: ABC_SHA256.to_string(),
+            }
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_build_manifest_for_directory() {
+        let dir = test_dir("directory");
+        let model = dir.join("parakeet");
+        fs::create_dir_all(model.join("nested")).unwrap();
+        fs::write(model.join("vocab.txt"), "abc").unwrap();
+        fs::write(model.join("nested").join("encoder.onnx"), "").unwrap();
+
+        let manifest = build_manifest(&model, true, None).unwrap();
+        assert_eq!(manifest.sha256, None);
+        let names: Vec<_> = manifest.files.keys().map(String::as_str).collect();
+        assert_eq!(names, ["nested/encoder.onnx", "vocab.txt"]);
+        assert_eq!(manifest.files["vocab.txt"].sha256, ABC_SHA256);
+        assert_eq!(manifest.files["nested/encoder.onnx"].size, 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_manifest_intact() {
+        let dir = test_dir("intact");
+        let model = dir.join("model");
+        fs::create_dir_all(&model).unwrap();
+        fs::write(model.join("a.bin"), "abc").unwrap();
+        fs::write(model.join("b.bin"), "def").unwrap();
+
+        let manifest = build_manifest(&model, true, None).unwrap();
+        assert!(check_manifest(&model, true, &manifest).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_manifest_reports_problems() {
+        let dir = test_dir("problems");
+        let model = dir.join("model");
+        fs::create_dir_all(&model).unwrap();
+        fs::write(model.join("changed.bin"), "abc").unwrap();
+        fs::write(model.join("resized.bin"), "abc").unwrap();
+        fs::write(model.join("missing.bin"), "abc").unwrap();
+        let manifest = build_manifest(&model, true, None).unwrap();
+
+        fs::write(model.join("changed.bin"), "abd").unwrap();
+        fs::write(model.join("resized.bin"), "abcd").unwrap();
+        fs::remove_file(model.join("missing.bin")).unwrap();
+        // Files the manifest does not list are not checked
+        fs::write(model.join("extra.bin"), "abc").unwrap();
+
+        let problems = check_manifest(&model, true, &manifest).unwrap();
+        assert_eq!(
+            problems,
+            [
+                "changed.bin does not match its checksum",
+                "missing.bin is missing",
+                "resized.bin has 4 bytes, expected 3",
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_manifest_for_file() {
+        let dir = test_dir("single");
+        let model = dir.join("ggml-small.bin");
+        fs::write(&model, "abc").unwrap();
+        let manifest = build_manifest(&model, false, None).unwrap();
+        assert!(check_manifest(&model, false, &manifest).unwrap().is_empty());
+
+        fs::remove_file(&model).unwrap();
+        assert_eq!(
+            check_manifest(&model, false, &manifest).unwrap(),
+            ["ggml-small.bin is missing"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let dir = test_dir("round-trip");
+        let model = dir.join("ggml-small.bin");
+        fs::write(&model, "abc").unwrap();
+        let path = dir.join("manifests").join("small.json");
+        assert!(read_manifest(&path).unwrap().is_none());
+
+        let manifest = build_manifest(&model, false, Some(ABC_SHA256.to_string())).unwrap();
+        write_manifest(&path, &manifest).unwrap();
+        let read = read_manifest(&path).unwrap().unwrap();
+        assert_eq!(read.sha256.as_deref(), Some(ABC_SHA256));
+        assert_eq!(read.files, manifest.files);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
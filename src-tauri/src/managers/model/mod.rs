@@ -0,0 +1,1741 @@
+mod catalog;
+mod integrity;
+mod queue;
+
+use crate::settings::{get_settings, write_settings};
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use futures_util::StreamExt;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tar::Archive;
+use tauri::{AppHandle, Emitter, Manager};
+
+use catalog::Catalog;
+use integrity::{
+    build_manifest, check_manifest, read_manifest, sha256_file, write_manifest, FileDigest,
+    ModelManifest,
+};
+use queue::{
+    backoff, is_transient, wait_for_retry, DownloadControl, DownloadOutcome, DownloadQueue,
+    TransientError, MAX_RETRIES,
+};
+
+pub use queue::DownloadState;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub enum EngineType {
+    Whisper,
+    Parakeet,
+    /// Sent to an OpenAI-compatible server; nothing is stored locally
+    Remote,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub filename: String, // Relative to the models dir, or absolute for local custom models
+    pub url: Option<String>,
+    pub size_mb: u64,
+    pub is_downloaded: bool,
+    pub is_downloading: bool,
+    pub partial_size: u64,
+    pub is_directory: bool,
+    pub engine_type: EngineType,
+    pub accuracy_score: f32, // 0.0 to 1.0, higher is more accurate
+    pub speed_score: f32,    // 0.0 to 1.0, higher is faster
+    pub is_custom: bool,
+    pub sha256: Option<String>, // Expected SHA-256 of the downloaded file, if known
+    pub version: u32,
+    pub languages: Option<Vec<String>>, // None if every language of the engine is supported
+    pub update_available: bool,         // A newer version than the installed one is in the catalog
+    /// Expected digests of a directory model's files, keyed by their path in
+    /// the directory, when the catalog publishes them
+    #[serde(skip)]
+    pub expected_files: Option<BTreeMap<String, FileDigest>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    /// Every file matches the expected checksums
+    Verified,
+    /// Nothing to compare against yet; the current files were recorded as
+    /// the reference for later checks
+    Unverified,
+    Damaged,
+}
+
+/// Result of checking a model's files, also the payload of the
+/// `model-verification-failed` event
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelVerification {
+    pub model_id: String,
+    pub status: VerificationStatus,
+    pub problems: Vec<String>,
+    /// Whether `repair_model` can restore the model by downloading it again
+    pub can_repair: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DownloadProgress {
+    pub model_id: String,
+    pub downloaded: u64,
+    pub total: u64,
+    pub percentage: f64,
+    pub state: DownloadState,
+    // Bytes downloaded and expected across every download in the queue
+    pub queue_downloaded: u64,
+    pub queue_total: u64,
+}
+
+const CATALOG_CACHE_FILE: &str = "catalog.json";
+const CATALOG_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+/// An imported file may be up to a tenth larger or smaller than `size_mb`
+const IMPORT_SIZE_TOLERANCE_DIVISOR: u64 = 10;
+
+/// The models in the bundled catalog plus the remote model, keyed by model id.
+/// Download status fields are left at their defaults; `ModelManager` fills them
+/// in from the models directory.
+pub fn builtin_models() -> HashMap<String, ModelInfo> {
+    let mut available_models = Catalog::bundled().into_models();
+
+    // Transcribes with the remote provider configured in settings
+    available_models.insert(
+        "remote".to_string(),
+        ModelInfo {
+            id: "remote".to_string(),
+            name: "Remote Server".to_string(),
+            description: "Sends recordings to an OpenAI-compatible transcription API.".to_string(),
+            filename: String::new(),
+            url: None,
+            size_mb: 0,
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory: false,
+            engine_type: EngineType::Remote,
+            accuracy_score: 0.0,
+            speed_score: 0.0,
+            is_custom: false,
+            sha256: None,
+            version: 1,
+            languages: None,
+            update_available: false,
+            expected_files: None,
+        },
+    );
+
+    available_models
+}
+
+const CUSTOM_MODELS_FILE: &str = "custom_models.json";
+
+/// A model registered by the user, persisted in `custom_models.json` in the
+/// models directory. Exactly one of `path` (a local GGML file or Parakeet
+/// directory, used in place) and `url` (downloaded like a built-in model) is
+/// set.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CustomModel {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub engine_type: EngineType,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub size_mb: u64,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl CustomModel {
+    /// Checks the definition itself; whether a local path exists is checked
+    /// when the model is added.
+    fn validate(&self) -> Result<()> {
+        if self.id.is_empty()
+            || !self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(anyhow::anyhow!(
+                "Model id '{}' may only contain letters, digits, '-', '_' and '.'",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Model name cannot be empty"));
+        }
+        if matches!(self.engine_type, EngineType::Remote) {
+            return Err(anyhow::anyhow!(
+                "Custom models must use the Whisper or Parakeet engine"
+            ));
+        }
+
+        match (&self.path, &self.url) {
+            (Some(path), None) => {
+                if !Path::new(path).is_absolute() {
+                    return Err(anyhow::anyhow!("Model path must be absolute: {}", path));
+                }
+            }
+            (None, Some(url)) => {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    return Err(anyhow::anyhow!("Model URL must be http(s): {}", url));
+                }
+                // Directory models are installed by extracting an archive
+                if matches!(self.engine_type, EngineType::Parakeet) && !url.ends_with(".tar.gz") {
+                    return Err(anyhow::anyhow!(
+                        "Parakeet models must be downloaded as a .tar.gz archive"
+                    ));
+                }
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "A custom model needs either a local path or a download URL"
+                ))
+            }
+        }
+
+        if let Some(sha256) = &self.sha256 {
+            if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(anyhow::anyhow!("'{}' is not a SHA-256 hex digest", sha256));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_model_info(&self) -> ModelInfo {
+        // Parakeet models are directories of ONNX files, Whisper models a single file
+        let is_directory = matches!(self.engine_type, EngineType::Parakeet);
+        let filename = match &self.path {
+            Some(path) => path.clone(),
+            None if is_directory => format!("custom-{}", self.id),
+            None => format!("custom-{}.bin", self.id),
+        };
+
+        ModelInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            filename,
+            url: self.url.clone(),
+            size_mb: self.size_mb,
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory,
+            engine_type: self.engine_type.clone(),
+            accuracy_score: 0.0,
+            speed_score: 0.0,
+            is_custom: true,
+            sha256: self.sha256.as_ref().map(|hash| hash.to_lowercase()),
+            version: 1,
+            languages: None,
+            update_available: false,
+            expected_files: None,
+        }
+    }
+}
+
+/// Reads the custom models registered in `models_dir`. A missing file means
+/// there are none.
+pub fn read_custom_models(models_dir: &Path) -> Result<Vec<CustomModel>> {
+    let path = models_dir.join(CUSTOM_MODELS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(&path)?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid {}: {}", path.display(), e))
+}
+
+fn write_custom_models(models_dir: &Path, models: &[CustomModel]) -> Result<()> {
+    let contents = serde_json::to_string_pretty(models)?;
+    fs::write(models_dir.join(CUSTOM_MODELS_FILE), contents)?;
+    Ok(())
+}
+
+pub struct ModelManager {
+    app_handle: AppHandle,
+    models_dir: PathBuf,
+    available_models: Mutex<HashMap<String, ModelInfo>>,
+    catalog_revision: Mutex<u64>,
+    downloads: DownloadQueue,
+    /// Installed models being replaced by a newer version, which are
+    /// downloaded even though their files exist
+    updating: Mutex<HashSet<String>>,
+}
+
+impl ModelManager {
+    pub fn new(app_handle: &AppHandle) -> Result<Self> {
+        // Create models directory in app data
+        let models_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
+            .join("models");
+
+        if !models_dir.exists() {
+            fs::create_dir_all(&models_dir)?;
+        }
+
+        let mut available_models = builtin_models();
+        match read_custom_models(&models_dir) {
+            Ok(custom_models) => {
+                for model in custom_models {
+                    if available_models.contains_key(&model.id) {
+                        warn!(
+                            "Ignoring custom model '{}': the id is already in use",
+                            model.id
+                        );
+                        continue;
+                    }
+                    available_models.insert(model.id.clone(), model.to_model_info());
+                }
+            }
+            Err(e) => warn!("Failed to read custom models: {}", e),
+        }
+
+        let manager = Self {
+            app_handle: app_handle.clone(),
+            models_dir,
+            available_models: Mutex::new(available_models),
+            catalog_revision: Mutex::new(Catalog::bundled().revision),
+            downloads: DownloadQueue::default(),
+            updating: Mutex::new(HashSet::new()),
+        };
+
+        // Use the last downloaded catalog if it is newer than the bundled one
+        manager.load_cached_catalog();
+
+        // Migrate any bundled models to user directory
+        manager.migrate_bundled_models()?;
+
+        // Check which models are already downloaded
+        manager.update_download_status()?;
+
+        // Auto-select a model if none is currently selected
+        manager.auto_select_model_if_needed()?;
+
+        Ok(manager)
+    }
+
+    pub fn get_available_models(&self) -> Vec<ModelInfo> {
+        let models = self.available_models.lock().unwrap();
+        models.values().cloned().collect()
+    }
+
+    pub fn get_model_info(&self, model_id: &str) -> Option<ModelInfo> {
+        let models = self.available_models.lock().unwrap();
+        models.get(model_id).cloned()
+    }
+
+    fn migrate_bundled_models(&self) -> Result<()> {
+        // Check for bundled models and copy them to user directory
+        let bundled_models = ["ggml-small.bin"]; // Add other bundled models here if any
+
+        for filename in &bundled_models {
+            let bundled_path = self.app_handle.path().resolve(
+                &format!("resources/models/{}", filename),
+                tauri::path::BaseDirectory::Resource,
+            );
+
+            if let Ok(bundled_path) = bundled_path {
+                if bundled_path.exists() {
+                    let user_path = self.models_dir.join(filename);
+
+                    // Only copy if user doesn't already have the model
+                    if !user_path.exists() {
+                        info!("Migrating bundled model {} to user directory", filename);
+                        fs::copy(&bundled_path, &user_path)?;
+                        info!("Successfully migrated {}", filename);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_download_status(&self) -> Result<()> {
+        let active_downloads = self.downloads.active_ids();
+        let mut models = self.available_models.lock().unwrap();
+
+        for model in models.values_mut() {
+            if matches!(model.engine_type, EngineType::Remote) {
+                // Always available; there is nothing to download
+                model.is_downloaded = true;
+                continue;
+            }
+
+            if model.is_custom && model.url.is_none() {
+                // Local models are used in place, so there are no partial or
+                // extraction leftovers of ours next to them
+                let model_path = Path::new(&model.filename);
+                model.is_downloaded = if model.is_directory {
+                    model_path.is_dir()
+                } else {
+                    model_path.is_file()
+                };
+                model.is_downloading = false;
+                model.partial_size = 0;
+                continue;
+            }
+
+            if model.is_directory {
+                // For directory-based models, check if the directory exists
+                let model_path = self.models_dir.join(&model.filename);
+                let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
+                let extracting_path = self
+                    .models_dir
+                    .join(format!("{}.extracting", &model.filename));
+
+                // Clean up any leftover .extracting directories from interrupted extractions
+                if extracting_path.exists() {
+                    warn!("Cleaning up interrupted extraction for model: {}", model.id);
+                    let _ = fs::remove_dir_all(&extracting_path);
+                }
+
+                model.is_downloaded = model_path.exists() && model_path.is_dir();
+                model.is_downloading = active_downloads.contains(&model.id);
+
+                // Get partial file size if it exists (for the .tar.gz being downloaded)
+                if partial_path.exists() {
+                    model.partial_size = partial_path.metadata().map(|m| m.len()).unwrap_or(0);
+                } else {
+                    model.partial_size = 0;
+                }
+            } else {
+                // For file-based models (existing logic)
+                let model_path = self.models_dir.join(&model.filename);
+                let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
+
+                model.is_downloaded = model_path.exists();
+                model.is_downloading = active_downloads.contains(&model.id);
+
+                // Get partial file size if it exists
+                if partial_path.exists() {
+                    model.partial_size = partial_path.metadata().map(|m| m.len()).unwrap_or(0);
+                } else {
+                    model.partial_size = 0;
+                }
+            }
+        }
+        drop(models);
+
+        self.check_for_updates();
+        Ok(())
+    }
+
+    fn auto_select_model_if_needed(&self) -> Result<()> {
+        // Check if we have a selected model in settings
+        let settings = get_settings(&self.app_handle);
+
+        // If no model is selected or selected model is empty
+        if settings.selected_model.is_empty() {
+            // Find the first available (downloaded) model
+            let models = self.available_models.lock().unwrap();
+            if let Some(available_model) = models.values().find(|model| {
+                model.is_downloaded && !matches!(model.engine_type, EngineType::Remote)
+            }) {
+                info!(
+                    "Auto-selecting model: {} ({})",
+                    available_model.id, available_model.name
+                );
+
+                // Update settings with the selected model
+                let mut updated_settings = settings;
+                updated_settings.selected_model = available_model.id.clone();
+                write_settings(&self.app_handle, updated_settings);
+
+                info!("Successfully auto-selected model: {}", available_model.id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Downloads a model through the queue and waits until it is installed,
+    /// has failed or was cancelled, which is reported as the error
+    /// `"cancelled"`. Pausing does not end the wait.
+    pub async fn download_model(self: &Arc<Self>, model_id: &str) -> Result<()> {
+        let done = self.queue_download(model_id)?;
+        queue::wait(done).await.map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Adds a model to the download queue, resuming from its partial file if
+    /// there is one, and starts downloads up to the concurrency limit
+    pub fn queue_download(
+        self: &Arc<Self>,
+        model_id: &str,
+    ) -> Result<tokio::sync::watch::Receiver<Option<Result<(), String>>>> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if model_info.url.is_none() {
+            return Err(anyhow::anyhow!("No download URL for model"));
+        }
+
+        let done = self.downloads.enqueue(model_id);
+        let _ = self.app_handle.emit(
+            "model-download-progress",
+            self.downloads.progress(model_id, None),
+        );
+        self.start_queued_downloads();
+        Ok(done)
+    }
+
+    pub fn pause_download(&self, model_id: &str) -> Result<()> {
+        self.downloads
+            .pause(model_id)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        info!("Download paused for: {}", model_id);
+        let _ = self.app_handle.emit(
+            "model-download-progress",
+            self.downloads.progress(model_id, None),
+        );
+        Ok(())
+    }
+
+    pub fn resume_download(self: &Arc<Self>, model_id: &str) -> Result<()> {
+        info!("Download resumed for: {}", model_id);
+        self.queue_download(model_id).map(|_| ())
+    }
+
+    pub fn get_download_queue(&self) -> Vec<DownloadProgress> {
+        self.downloads.snapshot()
+    }
+
+    /// Starts queued downloads up to the concurrency limit in the settings
+    pub fn start_queued_downloads(self: &Arc<Self>) {
+        let max_active = get_settings(&self.app_handle)
+            .model_download_concurrency
+            .max(1) as usize;
+        for (model_id, control) in self.downloads.start_next(max_active) {
+            let manager = Arc::clone(self);
+            tauri::async_runtime::spawn(async move {
+                manager.run_download(model_id, control).await;
+            });
+        }
+    }
+
+    /// Runs one queued download to the end, retrying transient errors with
+    /// exponential backoff
+    async fn run_download(self: Arc<Self>, model_id: String, control: Arc<DownloadControl>) {
+        let mut attempt = 0;
+        let result = loop {
+            match self.fetch_model(&model_id, &control).await {
+                Ok(outcome) => break Ok(outcome),
+                Err(e) if is_transient(&e) && attempt < MAX_RETRIES => {
+                    attempt += 1;
+                    let delay = backoff(attempt);
+                    warn!(
+                        "Download of model {} failed: {}. Retrying in {:?} ({}/{})",
+                        model_id, e, delay, attempt, MAX_RETRIES
+                    );
+                    self.downloads.set_state(&model_id, DownloadState::Retrying);
+                    let _ = self.app_handle.emit(
+                        "model-download-progress",
+                        self.downloads.progress(&model_id, None),
+                    );
+
+                    if !wait_for_retry(&control, delay).await {
+                        break Ok(control.stop_outcome());
+                    }
+                    self.downloads
+                        .set_state(&model_id, DownloadState::Downloading);
+                }
+                Err(e) => break Err(e),
+            }
+        };
+
+        let state = match result {
+            // Asked to continue again before the download stopped
+            Ok(DownloadOutcome::Paused | DownloadOutcome::Cancelled) if !control.should_stop() => {
+                self.downloads.set_state(&model_id, DownloadState::Queued);
+                DownloadState::Queued
+            }
+            Ok(DownloadOutcome::Paused) => {
+                self.downloads.set_state(&model_id, DownloadState::Paused);
+                DownloadState::Paused
+            }
+            Ok(DownloadOutcome::Completed) => DownloadState::Completed,
+            Ok(DownloadOutcome::Cancelled) => DownloadState::Cancelled,
+            Err(ref e) => {
+                error!("Failed to download model {}: {}", model_id, e);
+                DownloadState::Failed
+            }
+        };
+        let _ = self.app_handle.emit(
+            "model-download-progress",
+            self.downloads.progress(&model_id, Some(state)),
+        );
+
+        match result {
+            Ok(_) if matches!(state, DownloadState::Queued | DownloadState::Paused) => {}
+            Ok(DownloadOutcome::Cancelled) => self
+                .downloads
+                .finish(&model_id, Err(queue::CANCELLED.to_string())),
+            Ok(_) => self.downloads.finish(&model_id, Ok(())),
+            Err(e) => self.downloads.finish(&model_id, Err(e.to_string())),
+        }
+
+        // Clears the downloading flag and picks up any partial file left behind
+        let _ = self.update_download_status();
+        self.start_queued_downloads();
+    }
+
+    /// Makes one attempt at downloading and installing a model, stopping early
+    /// if the download is paused or cancelled
+    async fn fetch_model(
+        &self,
+        model_id: &str,
+        control: &DownloadControl,
+    ) -> Result<DownloadOutcome> {
+        let model_info = {
+            let models = self.available_models.lock().unwrap();
+            models.get(model_id).cloned()
+        };
+
+        let model_info =
+            model_info.ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        let url = model_info
+            .url
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No download URL for model"))?;
+        let model_path = self.models_dir.join(&model_info.filename);
+        let partial_path = self
+            .models_dir
+            .join(format!("{}.partial", &model_info.filename));
+
+        // Don't download if complete version already exists, unless it is
+        // being updated
+        if model_path.exists() && !self.updating.lock().unwrap().contains(model_id) {
+            // Clean up any partial file that might exist
+            if partial_path.exists() {
+                let _ = fs::remove_file(&partial_path);
+            }
+            self.update_download_status()?;
+            return Ok(DownloadOutcome::Completed);
+        }
+
+        // Check if we have a partial download to resume
+        let mut resume_from = if partial_path.exists() {
+            let size = partial_path.metadata()?.len();
+            info!("Resuming download of model {} from byte {}", model_id, size);
+            size
+        } else {
+            info!("Starting fresh download of model {} from {}", model_id, url);
+            0
+        };
+
+        // Mark as downloading
+        {
+            let mut models = self.available_models.lock().unwrap();
+            if let Some(model) = models.get_mut(model_id) {
+                model.is_downloading = true;
+            }
+        }
+
+        // Try the configured mirrors before the model's own URL
+        let mirrors = get_settings(&self.app_handle).model_download_mirrors;
+        let client = reqwest::Client::new();
+        let mut opened = None;
+        let mut last_error = None;
+        for candidate in download_urls(&url, &mirrors) {
+            match request_download(&client, &candidate, resume_from).await {
+                Ok(response) => {
+                    opened = Some((candidate, response));
+                    break;
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to download model {} from {}: {}",
+                        model_id, candidate, e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        let (url, mut response) = match opened {
+            Some(opened) => opened,
+            None => {
+                return Err(
+                    last_error.unwrap_or_else(|| anyhow::anyhow!("No download URL for model"))
+                )
+            }
+        };
+        if model_info.url.as_deref() != Some(url.as_str()) {
+            info!("Downloading model {} from mirror {}", model_id, url);
+        }
+
+        // If we tried to resume but server returned 200 (not 206 Partial Content),
+        // the server doesn't support range requests. Delete partial file and restart
+        // fresh to avoid file corruption (appending full file to partial).
+        if resume_from > 0 && response.status() == reqwest::StatusCode::OK {
+            warn!(
+                "Server doesn't support range requests for model {}, restarting download",
+                model_id
+            );
+            drop(response);
+            let _ = fs::remove_file(&partial_path);
+
+            // Reset resume_from since we're starting fresh
+            resume_from = 0;
+
+            // Restart download without range header
+            response = request_download(&client, &url, 0).await?;
+        }
+
+        let total_size = if resume_from > 0 {
+            // For resumed downloads, add the resume point to content length
+            resume_from + response.content_length().unwrap_or(0)
+        } else {
+            response.content_length().unwrap_or(0)
+        };
+
+        let mut downloaded = resume_from;
+        let mut stream = response.bytes_stream();
+
+        // Open file for appending if resuming, or create new if starting fresh
+        let mut file = if resume_from > 0 {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&partial_path)?
+        } else {
+            std::fs::File::create(&partial_path)?
+        };
+
+        // Emit initial progress
+        let max_bytes_per_sec =
+            u64::from(get_settings(&self.app_handle).model_download_max_kib_per_sec) * 1024;
+        let _ = self.app_handle.emit(
+            "model-download-progress",
+            self.downloads
+                .update_progress(model_id, downloaded, total_size),
+        );
+
+        // Download with progress
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+
+            file.write_all(&chunk)?;
+            downloaded += chunk.len() as u64;
+
+            // Emit progress event
+            let progress = self
+                .downloads
+                .update_progress(model_id, downloaded, total_size);
+            let _ = self.app_handle.emit("model-download-progress", &progress);
+
+            if control.should_stop() {
+                file.flush()?;
+                info!(
+                    "Download of model {} stopped at byte {}",
+                    model_id, downloaded
+                );
+                return Ok(control.stop_outcome());
+            }
+
+            if max_bytes_per_sec > 0 {
+                let delay = self.downloads.throttle(chunk.len(), max_bytes_per_sec);
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        file.flush()?;
+        drop(file); // Ensure file is closed before moving
+
+        // Verify downloaded file size matches expected size
+        if total_size > 0 {
+            let actual_size = partial_path.metadata()?.len();
+            if actual_size != total_size {
+                // Download is incomplete/corrupted - delete partial and return error
+                let _ = fs::remove_file(&partial_path);
+                return Err(TransientError(format!(
+                    "Download incomplete: expected {} bytes, got {} bytes",
+                    total_size, actual_size
+                ))
+                .into());
+            }
+        }
+
+        self.install_download(&model_info).await?;
+        Ok(DownloadOutcome::Completed)
+    }
+
+    /// Installs a model from its complete `.partial` file: checks it, extracts
+    /// or moves it into place and records its manifest
+    async fn install_download(&self, model_info: &ModelInfo) -> Result<()> {
+        let model_id = model_info.id.as_str();
+        let model_path = self.models_dir.join(&model_info.filename);
+        let partial_path = self
+            .models_dir
+            .join(format!("{}.partial", &model_info.filename));
+
+        // Check the download against its expected checksum before installing it
+        let path = partial_path.clone();
+        let download_sha256 =
+            tauri::async_runtime::spawn_blocking(move || sha256_file(&path)).await??;
+        if let Some(expected) = &model_info.sha256 {
+            if &download_sha256 != expected {
+                let _ = fs::remove_file(&partial_path);
+                let error_msg = format!(
+                    "Checksum mismatch for model {}: expected {}, got {}",
+                    model_id, expected, download_sha256
+                );
+                self.emit_verification_failed(ModelVerification {
+                    model_id: model_id.to_string(),
+                    status: VerificationStatus::Damaged,
+                    problems: vec![error_msg.clone()],
+                    can_repair: true,
+                });
+                return Err(anyhow::anyhow!(error_msg));
+            }
+        } else {
+            warn!(
+                "Model {} has no published checksum; installing download with SHA-256 {} unverified",
+                model_id, download_sha256
+            );
+        }
+
+        // Record the installed files so later damage can be detected. When the
+        // catalog lists the extracted files, they are checked against it and
+        // its digests become the reference.
+        let is_directory = model_info.is_directory;
+        let version = model_info.version;
+        let verified = model_info.sha256.is_some() || model_info.expected_files.is_some();
+        let expected_files = model_info.expected_files.clone();
+        let record_manifest = move |installed_path: PathBuf| {
+            tauri::async_runtime::spawn_blocking(move || -> Result<ModelManifest> {
+                let mut manifest = match expected_files {
+                    Some(files) => {
+                        let manifest = ModelManifest {
+                            sha256: Some(download_sha256),
+                            files,
+                            ..Default::default()
+                        };
+                        let problems = check_manifest(&installed_path, is_directory, &manifest)?;
+                        if !problems.is_empty() {
+                            return Err(anyhow::anyhow!(problems.join("; ")));
+                        }
+                        manifest
+                    }
+                    None => build_manifest(&installed_path, is_directory, Some(download_sha256))?,
+                };
+                manifest.version = Some(version);
+                manifest.verified = verified;
+                if manifest.files.is_empty() {
+                    return Err(anyhow::anyhow!("The downloaded archive contained no files"));
+                }
+                Ok(manifest)
+            })
+        };
+        let verification_failed = |e: anyhow::Error| {
+            let error_msg = format!("Failed to verify model {}: {}", model_id, e);
+            self.emit_verification_failed(ModelVerification {
+                model_id: model_id.to_string(),
+                status: VerificationStatus::Damaged,
+                problems: vec![error_msg.clone()],
+                can_repair: true,
+            });
+            anyhow::anyhow!(error_msg)
+        };
+
+        // Handle directory-based models (extract tar.gz) vs file-based models.
+        // An installed copy is only replaced once the new one has been checked,
+        // so a bad download or update leaves it untouched.
+        let manifest = if model_info.is_directory {
+            // Emit extraction started event
+            let _ = self.app_handle.emit("model-extraction-started", model_id);
+            info!("Extracting archive for directory-based model: {}", model_id);
+
+            // Use a temporary extraction directory to ensure atomic operations
+            let temp_extract_dir = self
+                .models_dir
+                .join(format!("{}.extracting", &model_info.filename));
+            let final_model_dir = self.models_dir.join(&model_info.filename);
+
+            // Clean up any previous incomplete extraction
+            if temp_extract_dir.exists() {
+                let _ = fs::remove_dir_all(&temp_extract_dir);
+            }
+
+            // Create temporary extraction directory
+            fs::create_dir_all(&temp_extract_dir)?;
+
+            // Open the downloaded tar.gz file
+            let tar_gz = File::open(&partial_path)?;
+            let tar = GzDecoder::new(tar_gz);
+            let mut archive = Archive::new(tar);
+
+            // Extract to the temporary directory first
+            archive.unpack(&temp_extract_dir).map_err(|e| {
+                let error_msg = format!("Failed to extract archive: {}", e);
+                // Clean up failed extraction
+                let _ = fs::remove_dir_all(&temp_extract_dir);
+                let _ = self.app_handle.emit(
+                    "model-extraction-failed",
+                    &serde_json::json!({
+                        "model_id": model_id,
+                        "error": error_msg
+                    }),
+                );
+                anyhow::anyhow!(error_msg)
+            })?;
+
+            // Find the actual extracted directory (archive might have a nested structure)
+            let extracted_dirs: Vec<_> = fs::read_dir(&temp_extract_dir)?
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+                .collect();
+            // A single directory is the model itself; otherwise the temp
+            // directory is
+            let source_dir = if extracted_dirs.len() == 1 {
+                extracted_dirs[0].path()
+            } else {
+                temp_extract_dir.clone()
+            };
+
+            let manifest = match record_manifest(source_dir.clone()).await? {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    let _ = fs::remove_dir_all(&temp_extract_dir);
+                    let _ = fs::remove_file(&partial_path);
+                    return Err(verification_failed(e));
+                }
+            };
+
+            if final_model_dir.exists() {
+                fs::remove_dir_all(&final_model_dir)?;
+            }
+            fs::rename(&source_dir, &final_model_dir)?;
+            // Clean up temp directory
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+
+            info!("Successfully extracted archive for model: {}", model_id);
+            // Emit extraction completed event
+            let _ = self.app_handle.emit("model-extraction-completed", model_id);
+
+            // Remove the downloaded tar.gz file
+            let _ = fs::remove_file(&partial_path);
+            manifest
+        } else {
+            // The file's checksum was checked above. Renaming replaces an
+            // installed copy in one step.
+            fs::rename(&partial_path, &model_path)?;
+            record_manifest(model_path.clone())
+                .await?
+                .map_err(verification_failed)?
+        };
+        write_manifest(&self.manifest_path(model_id), &manifest)?;
+
+        // Update download status
+        {
+            let mut models = self.available_models.lock().unwrap();
+            if let Some(model) = models.get_mut(model_id) {
+                model.is_downloading = false;
+                model.is_downloaded = true;
+                model.partial_size = 0;
+                model.update_available = false;
+            }
+        }
+
+        // Emit completion event
+        let _ = self.app_handle.emit("model-download-complete", model_id);
+
+        info!(
+            "Successfully downloaded model {} to {:?}",
+            model_id, model_path
+        );
+
+        Ok(())
+    }
+
+    pub fn delete_model(&self, model_id: &str) -> Result<()> {
+        debug!("ModelManager: delete_model called for: {}", model_id);
+
+        let model_info = {
+            let models = self.available_models.lock().unwrap();
+            models.get(model_id).cloned()
+        };
+
+        let model_info =
+            model_info.ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        debug!("ModelManager: Found model info: {:?}", model_info);
+
+        if matches!(model_info.engine_type, EngineType::Remote) {
+            return Err(anyhow::anyhow!("Remote models have no files to delete"));
+        }
+        if model_info.is_custom && model_info.url.is_none() {
+            return Err(anyhow::anyhow!(
+                "Model {} is used from its own location; remove it instead of deleting it",
+                model_id
+            ));
+        }
+
+        let model_path = self.models_dir.join(&model_info.filename);
+        let partial_path = self
+            .models_dir
+            .join(format!("{}.partial", &model_info.filename));
+        debug!("ModelManager: Model path: {:?}", model_path);
+        debug!("ModelManager: Partial path: {:?}", partial_path);
+
+        let mut deleted_something = false;
+
+        if model_info.is_directory {
+            // Delete complete model directory if it exists
+            if model_path.exists() && model_path.is_dir() {
+                info!("Deleting model directory at: {:?}", model_path);
+                fs::remove_dir_all(&model_path)?;
+                info!("Model directory deleted successfully");
+                deleted_something = true;
+            }
+        } else {
+            // Delete complete model file if it exists
+            if model_path.exists() {
+                info!("Deleting model file at: {:?}", model_path);
+                fs::remove_file(&model_path)?;
+                info!("Model file deleted successfully");
+                deleted_something = true;
+            }
+        }
+
+        // Delete partial file if it exists (same for both types)
+        if partial_path.exists() {
+            info!("Deleting partial file at: {:?}", partial_path);
+            fs::remove_file(&partial_path)?;
+            info!("Partial file deleted successfully");
+            deleted_something = true;
+        }
+
+        let _ = fs::remove_file(self.manifest_path(model_id));
+
+        if !deleted_something {
+            return Err(anyhow::anyhow!("No model files found to delete"));
+        }
+
+        // Update download status
+        self.update_download_status()?;
+        debug!("ModelManager: download status updated");
+
+        Ok(())
+    }
+
+    /// Registers a user-supplied model and persists it in `custom_models.json`
+    pub fn add_custom_model(&self, model: CustomModel) -> Result<ModelInfo> {
+        model.validate()?;
+        if let Some(path) = &model.path {
+            let path = Path::new(path);
+            match model.engine_type {
+                EngineType::Parakeet if !path.is_dir() => {
+                    return Err(anyhow::anyhow!(
+                        "Parakeet models must be a directory: {}",
+                        path.display()
+                    ))
+                }
+                EngineType::Whisper if !path.is_file() => {
+                    return Err(anyhow::anyhow!(
+                        "Whisper models must be a GGML file: {}",
+                        path.display()
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        if self.get_model_info(&model.id).is_some() {
+            return Err(anyhow::anyhow!(
+                "A model with id '{}' already exists",
+                model.id
+            ));
+        }
+
+        let mut custom_models = read_custom_models(&self.models_dir)?;
+        custom_models.push(model.clone());
+        write_custom_models(&self.models_dir, &custom_models)?;
+
+        {
+            let mut models = self.available_models.lock().unwrap();
+            models.insert(model.id.clone(), model.to_model_info());
+        }
+        self.update_download_status()?;
+
+        info!("Registered custom model {} ({})", model.id, model.name);
+        self.get_model_info(&model.id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model.id))
+    }
+
+    /// Unregisters a custom model. Files Handy downloaded for it are deleted;
+    /// local models are left where they are.
+    pub fn remove_custom_model(&self, model_id: &str) -> Result<()> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if !model_info.is_custom {
+            return Err(anyhow::anyhow!("Only custom models can be removed"));
+        }
+        if model_info.is_downloading {
+            return Err(anyhow::anyhow!(
+                "Model is currently downloading: {}",
+                model_id
+            ));
+        }
+
+        if model_info.url.is_some() && (model_info.is_downloaded || model_info.partial_size > 0) {
+            self.delete_model(model_id)?;
+        }
+
+        let mut custom_models = read_custom_models(&self.models_dir)?;
+        custom_models.retain(|model| model.id != model_id);
+        write_custom_models(&self.models_dir, &custom_models)?;
+
+        let _ = fs::remove_file(self.manifest_path(model_id));
+        self.available_models.lock().unwrap().remove(model_id);
+        info!("Removed custom model {}", model_id);
+        Ok(())
+    }
+
+    /// Checks an installed model's files against the catalog's digests or, when
+    /// it has none, against the manifest recorded when it was installed. Files
+    /// that match a manifest that was never checked against the catalog are
+    /// only `Unverified`. Emits `model-verification-failed` if anything is
+    /// missing or changed.
+    pub fn verify_model(&self, model_id: &str) -> Result<ModelVerification> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if matches!(model_info.engine_type, EngineType::Remote) {
+            return Err(anyhow::anyhow!("Remote models have no files to verify"));
+        }
+        if !model_info.is_downloaded || model_info.is_downloading {
+            return Err(anyhow::anyhow!("Model not available: {}", model_id));
+        }
+
+        let model_path = self.models_dir.join(&model_info.filename);
+        let manifest_path = self.manifest_path(model_id);
+        let stored_manifest = read_manifest(&manifest_path)?;
+        let reference = match (&model_info.expected_files, stored_manifest) {
+            (Some(files), _) if model_info.is_directory => Some(ModelManifest {
+                files: files.clone(),
+                verified: true,
+                ..Default::default()
+            }),
+            (_, stored) => stored,
+        };
+        let (status, problems) = match reference {
+            Some(manifest) => {
+                let problems = check_manifest(&model_path, model_info.is_directory, &manifest)?;
+                if !problems.is_empty() {
+                    (VerificationStatus::Damaged, problems)
+                } else if manifest.verified
+                    || (model_info.sha256.is_some() && manifest.sha256 == model_info.sha256)
+                {
+                    (VerificationStatus::Verified, problems)
+                } else {
+                    (VerificationStatus::Unverified, problems)
+                }
+            }
+            None => {
+                // Installed before manifests were recorded, or by hand, so the
+                // current files become the reference. A single file can still
+                // be checked against a known checksum.
+                let mut manifest = build_manifest(&model_path, model_info.is_directory, None)?;
+                if !model_info.is_directory {
+                    manifest.sha256 = manifest.files.values().next().map(|f| f.sha256.clone());
+                }
+                match (&model_info.sha256, &manifest.sha256) {
+                    (Some(expected), Some(actual)) if expected != actual => (
+                        VerificationStatus::Damaged,
+                        vec![format!(
+                            "{} does not match its checksum",
+                            model_info.filename
+                        )],
+                    ),
+                    (expected, actual) => {
+                        manifest.verified = expected.is_some() && actual.is_some();
+                        write_manifest(&manifest_path, &manifest)?;
+                        let status = if manifest.verified {
+                            VerificationStatus::Verified
+                        } else {
+                            VerificationStatus::Unverified
+                        };
+                        (status, Vec::new())
+                    }
+                }
+            }
+        };
+
+        let verification = ModelVerification {
+            model_id: model_id.to_string(),
+            status,
+            problems,
+            can_repair: model_info.url.is_some(),
+        };
+        if verification.status == VerificationStatus::Damaged {
+            warn!(
+                "Model {} failed verification: {}",
+                model_id,
+                verification.problems.join("; ")
+            );
+            self.emit_verification_failed(verification.clone());
+        } else {
+            info!("Model {} verified: {:?}", model_id, verification.status);
+        }
+
+        Ok(verification)
+    }
+
+    /// Replaces a damaged model by deleting its files and downloading it again
+    pub async fn repair_model(self: &Arc<Self>, model_id: &str) -> Result<()> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if model_info.url.is_none() {
+            return Err(anyhow::anyhow!(
+                "Model {} has no download URL and must be reinstalled by hand",
+                model_id
+            ));
+        }
+        if model_info.is_downloading {
+            return Err(anyhow::anyhow!(
+                "Model is currently downloading: {}",
+                model_id
+            ));
+        }
+
+        info!("Repairing model {}", model_id);
+        if model_info.is_downloaded || model_info.partial_size > 0 {
+            self.delete_model(model_id)?;
+        }
+        self.download_model(model_id).await
+    }
+
+    /// Replaces an installed model with the newer version from the catalog
+    pub async fn update_model(self: &Arc<Self>, model_id: &str) -> Result<()> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if !model_info.update_available {
+            return Err(anyhow::anyhow!("Model {} is up to date", model_id));
+        }
+
+        if model_info.is_downloading {
+            return Err(anyhow::anyhow!(
+                "Model is currently downloading: {}",
+                model_id
+            ));
+        }
+
+        // Catalog versions keep the model's filename. The new version is
+        // downloaded next to the installed one, which is only replaced once
+        // the download has been verified.
+        info!(
+            "Updating model {} to version {}",
+            model_id, model_info.version
+        );
+        self.updating.lock().unwrap().insert(model_id.to_string());
+        let result = self.download_model(model_id).await;
+        self.updating.lock().unwrap().remove(model_id);
+        result
+    }
+
+    /// Installs a model from a local copy of its download, for machines that
+    /// cannot reach the download server. The file must be what the model's URL
+    /// serves (a GGML `.bin` file or a `.tar.gz` archive) and goes through the
+    /// same checks and extraction as a download.
+    pub async fn import_model_archive(&self, model_id: &str, archive_path: &Path) -> Result<()> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if model_info.url.is_none() {
+            return Err(anyhow::anyhow!(
+                "Model {} is not installed from a download",
+                model_id
+            ));
+        }
+        if model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model {} is already installed", model_id));
+        }
+        if self.downloads.contains(model_id) {
+            return Err(anyhow::anyhow!(
+                "Model {} is in the download queue; cancel the download first",
+                model_id
+            ));
+        }
+
+        info!(
+            "Importing model {} from {}",
+            model_id,
+            archive_path.display()
+        );
+        let source = archive_path.to_path_buf();
+        let is_directory = model_info.is_directory;
+        let size_mb = model_info.size_mb;
+        let expected_sha256 = model_info.sha256.clone();
+        let partial_path = self
+            .models_dir
+            .join(format!("{}.partial", &model_info.filename));
+        let copy_path = partial_path.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            check_download_format(&source, is_directory)?;
+            check_download_size(&source, size_mb)?;
+            // Reject a file that doesn't match the catalog before copying it
+            if let Some(expected) = expected_sha256 {
+                let actual = sha256_file(&source)?;
+                if actual != expected {
+                    return Err(anyhow::anyhow!(
+                        "{} does not match the model's checksum: expected {}, got {}",
+                        source.display(),
+                        expected,
+                        actual
+                    ));
+                }
+            }
+            fs::copy(&source, &copy_path)?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        if let Err(e) = self.install_download(&model_info).await {
+            let _ = fs::remove_file(&partial_path);
+            return Err(e);
+        }
+        self.update_download_status()
+    }
+
+    /// Fetches the signed catalog from the `model_catalog_url` setting and, if
+    /// it is newer than the current one, caches it and merges its models in.
+    /// Returns whether the catalog changed.
+    pub async fn refresh_catalog(&self) -> Result<bool> {
+        let url = get_settings(&self.app_handle).model_catalog_url;
+        if url.trim().is_empty() {
+            return Ok(false);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(CATALOG_FETCH_TIMEOUT)
+            .build()?;
+        let json = fetch_text(&client, &url).await?;
+        let signature = fetch_text(&client, &format!("{}.sig", url)).await?;
+        let catalog = Catalog::parse_signed(&json, &signature)?;
+
+        let current_revision = *self.catalog_revision.lock().unwrap();
+        if catalog.revision <= current_revision {
+            debug!(
+                "Model catalog revision {} is not newer than {}",
+                catalog.revision, current_revision
+            );
+            return Ok(false);
+        }
+
+        fs::write(self.models_dir.join(CATALOG_CACHE_FILE), &json)?;
+        fs::write(
+            self.models_dir.join(format!("{}.sig", CATALOG_CACHE_FILE)),
+            &signature,
+        )?;
+
+        info!("Updated model catalog to revision {}", catalog.revision);
+        self.apply_catalog(catalog);
+        let _ = self.app_handle.emit("model-catalog-updated", ());
+
+        let updates = self.check_for_updates();
+        if !updates.is_empty() {
+            info!("Model updates available: {}", updates.join(", "));
+            let _ = self.app_handle.emit("model-update-available", updates);
+        }
+        Ok(true)
+    }
+
+    fn load_cached_catalog(&self) {
+        let path = self.models_dir.join(CATALOG_CACHE_FILE);
+        if !path.exists() {
+            return;
+        }
+
+        // The cache is checked again so a modified file is never trusted
+        let cached = fs::read_to_string(&path).and_then(|json| {
+            let signature =
+                fs::read_to_string(self.models_dir.join(format!("{}.sig", CATALOG_CACHE_FILE)))?;
+            Ok((json, signature))
+        });
+        match cached
+            .map_err(anyhow::Error::from)
+            .and_then(|(json, signature)| Catalog::parse_signed(&json, &signature))
+        {
+            Ok(catalog) if catalog.revision > *self.catalog_revision.lock().unwrap() => {
+                debug!("Using cached model catalog revision {}", catalog.revision);
+                self.apply_catalog(catalog);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Ignoring cached model catalog: {}", e),
+        }
+    }
+
+    /// Merges a catalog into the available models, keeping the download state
+    /// of models that are already known. Models missing from the catalog stay
+    /// so installed files are never orphaned.
+    fn apply_catalog(&self, catalog: Catalog) {
+        *self.catalog_revision.lock().unwrap() = catalog.revision;
+
+        let mut models = self.available_models.lock().unwrap();
+        for (id, mut model) in catalog.into_models() {
+            if let Some(existing) = models.get(&id) {
+                if existing.is_custom {
+                    warn!(
+                        "Catalog model '{}' conflicts with a custom model and is ignored",
+                        id
+                    );
+                    continue;
+                }
+                model.is_downloaded = existing.is_downloaded;
+                model.is_downloading = existing.is_downloading;
+                model.partial_size = existing.partial_size;
+            }
+            models.insert(id, model);
+        }
+    }
+
+    /// Flags installed models whose catalog version is newer than the one on
+    /// disk and returns their ids
+    fn check_for_updates(&self) -> Vec<String> {
+        let mut models = self.available_models.lock().unwrap();
+        let mut updates = Vec::new();
+        for model in models.values_mut() {
+            model.update_available = model.is_downloaded
+                && !model.is_custom
+                && model.url.is_some()
+                && self.installed_version(&model.id) < model.version;
+            if model.update_available {
+                updates.push(model.id.clone());
+            }
+        }
+        updates.sort();
+        updates
+    }
+
+    /// Models installed before versions were recorded count as version 1
+    fn installed_version(&self, model_id: &str) -> u32 {
+        read_manifest(&self.manifest_path(model_id))
+            .ok()
+            .flatten()
+            .and_then(|manifest| manifest.version)
+            .unwrap_or(1)
+    }
+
+    fn manifest_path(&self, model_id: &str) -> PathBuf {
+        self.models_dir
+            .join("manifests")
+            .join(format!("{}.json", model_id))
+    }
+
+    fn emit_verification_failed(&self, verification: ModelVerification) {
+        let _ = self
+            .app_handle
+            .emit("model-verification-failed", verification);
+    }
+
+    pub fn get_model_path(&self, model_id: &str) -> Result<PathBuf> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not available: {}", model_id));
+        }
+
+        // Ensure we don't return partial files/directories
+        if model_info.is_downloading {
+            return Err(anyhow::anyhow!(
+                "Model is currently downloading: {}",
+                model_id
+            ));
+        }
+
+        let model_path = self.models_dir.join(&model_info.filename);
+        let partial_path = self
+            .models_dir
+            .join(format!("{}.partial", &model_info.filename));
+
+        if model_info.is_directory {
+            // For directory-based models, ensure the directory exists and is complete
+            if model_path.exists() && model_path.is_dir() && !partial_path.exists() {
+                Ok(model_path)
+            } else {
+                Err(anyhow::anyhow!(
+                    "Complete model directory not found: {}",
+                    model_id
+                ))
+            }
+        } else {
+            // For file-based models (existing logic)
+            if model_path.exists() && !partial_path.exists() {
+                Ok(model_path)
+            } else {
+                Err(anyhow::anyhow!(
+                    "Complete model file not found: {}",
+                    model_id
+                ))
+            }
+        }
+    }
+
+    pub fn cancel_download(&self, model_id: &str) -> Result<()> {
+        debug!("ModelManager: cancel_download called for: {}", model_id);
+
+        if self.get_model_info(model_id).is_none() {
+            return Err(anyhow::anyhow!("Model not found: {}", model_id));
+        }
+
+        // A running download stops at its next chunk and leaves the queue then.
+        // The partial file is kept so the download can be resumed later.
+        if self.downloads.cancel(model_id) {
+            let _ = self.app_handle.emit(
+                "model-download-progress",
+                self.downloads
+                    .progress(model_id, Some(DownloadState::Cancelled)),
+            );
+        }
+
+        // Update download status to reflect current state
+        self.update_download_status()?;
+
+        info!("Download cancelled for: {}", model_id);
+        Ok(())
+    }
+}
+
+/// Where to download a model from: each mirror in order, then the model's own
+/// URL. Mirrors serve files under the same name as the original server.
+fn download_urls(url: &str, mirrors: &[String]) -> Vec<String> {
+    let file_name = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .unwrap_or_default();
+
+    let mut urls: Vec<String> = mirrors
+        .iter()
+        .map(|mirror| mirror.trim())
+        .filter(|mirror| !mirror.is_empty())
+        .map(|mirror| format!("{}/{}", mirror.trim_end_matches('/'), file_name))
+        .collect();
+    urls.push(url.to_string());
+    urls.dedup();
+    urls
+}
+
+/// Requests a model file, from `resume_from` onwards when resuming
+async fn request_download(
+    client: &reqwest::Client,
+    url: &str,
+    resume_from: u64,
+) -> Result<reqwest::Response> {
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        request = request.header("Range", format!("bytes={}-", resume_from));
+    }
+    let response = request.send().await?;
+
+    // Check for success or partial content status
+    let status = response.status();
+    if !status.is_success() && status != reqwest::StatusCode::PARTIAL_CONTENT {
+        let error_msg = format!("Failed to download model: HTTP {}", status);
+        // Server errors and rate limiting are usually temporary
+        if status.is_server_error()
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        {
+            return Err(TransientError(error_msg).into());
+        }
+        return Err(anyhow::anyhow!(error_msg));
+    }
+    Ok(response)
+}
+
+/// Checks that an imported file is the kind of file the model is downloaded
+/// as, before it is copied into the models directory
+fn check_download_format(path: &Path, is_directory: bool) -> Result<()> {
+    let mut magic = [0u8; 4];
+    File::open(path)?
+        .read_exact(&mut magic)
+        .map_err(|_| anyhow::anyhow!("{} is too small to be a model", path.display()))?;
+
+    if is_directory {
+        if magic[..2] != [0x1f, 0x8b] {
+            return Err(anyhow::anyhow!(
+                "{} is not a .tar.gz archive",
+                path.display()
+            ));
+        }
+    } else if &magic != b"lmgg" && &magic != b"GGUF" {
+        return Err(anyhow::anyhow!(
+            "{} is not a GGML model file",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that an imported file is roughly the size the catalog gives for the
+/// model. `size_mb` is rounded, and 0 when the size isn't known.
+fn check_download_size(path: &Path, size_mb: u64) -> Result<()> {
+    if size_mb == 0 {
+        return Ok(());
+    }
+    let size = fs::metadata(path)?.len();
+    let expected = size_mb * 1_000_000;
+    if size.abs_diff(expected) > expected / IMPORT_SIZE_TOLERANCE_DIVISOR {
+        return Err(anyhow::anyhow!(
+            "{} is {} MB, but the model should be about {} MB",
+            path.display(),
+            size / 1_000_000,
+            size_mb
+        ));
+    }
+    Ok(())
+}
+
+async fn fetch_text(client: &reqwest::Client, url: &str) -> Result<String> {
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+    Ok(response.text().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_file(name: &str, contents: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("handy-model-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_download_urls_without_mirrors() {
+        assert_eq!(
+            download_urls("https://blob.handy.computer/ggml-small.bin", &[]),
+            ["https://blob.handy.computer/ggml-small.bin"]
+        );
+    }
+
+    #[test]
+    fn test_download_urls_tries_mirrors_in_order_then_original() {
+        let mirrors = [
+            "https://one.example.com/handy".to_string(),
+            "https://two.example.com".to_string(),
+        ];
+        assert_eq!(
+            download_urls("https://blob.handy.computer/ggml-small.bin", &mirrors),
+            [
+                "https://one.example.com/handy/ggml-small.bin",
+                "https://two.example.com/ggml-small.bin",
+                "https://blob.handy.computer/ggml-small.bin",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_download_urls_trims_mirrors() {
+        let mirrors = [
+            "https://one.example.com/handy/".to_string(),
+            " https://two.example.com// ".to_string(),
+            "  ".to_string(),
+        ];
+        assert_eq!(
+            download_urls("https://blob.handy.computer/ggml-small.bin", &mirrors),
+            [
+                "https://one.example.com/handy/ggml-small.bin",
+                "https://two.example.com/ggml-small.bin",
+                "https://blob.handy.computer/ggml-small.bin",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_download_urls_drops_query_from_mirror_file_name() {
+        let mirrors = ["https://mirror.example.com".to_string()];
+        assert_eq!(
+            download_urls(
+                "https://blob.handy.computer/parakeet-v3-int8.tar.gz?token=abc#top",
+                &mirrors
+            ),
+            [
+                "https://mirror.example.com/parakeet-v3-int8.tar.gz",
+                "https://blob.handy.computer/parakeet-v3-int8.tar.gz?token=abc#top",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_download_urls_skips_mirror_matching_original() {
+        let mirrors = ["https://blob.handy.computer/".to_string()];
+        assert_eq!(
+            download_urls("https://blob.handy.computer/ggml-small.bin", &mirrors),
+            ["https://blob.handy.computer/ggml-small.bin"]
+        );
+    }
+
+    #[test]
+    fn test_check_download_format() {
+        let gzip = test_file("model.tar.gz", &[0x1f, 0x8b, 0x08, 0x00, 0x00]);
+        let ggml = test_file("ggml.bin", b"lmgg\0\0");
+        let gguf = test_file("gguf.bin", b"GGUF\0\0");
+        let other = test_file("other.bin", b"PK\x03\x04\0\0");
+        let tiny = test_file("tiny.bin", b"GG");
+
+        assert!(check_download_format(&gzip, true).is_ok());
+        assert!(check_download_format(&ggml, true).is_err());
+        assert!(check_download_format(&other, true).is_err());
+
+        assert!(check_download_format(&ggml, false).is_ok());
+        assert!(check_download_format(&gguf, false).is_ok());
+        assert!(check_download_format(&gzip, false).is_err());
+        assert!(check_download_format(&other, false).is_err());
+        assert!(check_download_format(&tiny, false).is_err());
+    }
+
+    #[test]
+    fn test_check_download_size() {
+        let file = test_file("sized.bin", &vec![0u8; 1_050_000]);
+
+        assert!(check_download_size(&file, 1).is_ok());
+        assert!(check_download_size(&file, 0).is_ok());
+        assert!(check_download_size(&file, 2).is_err());
+
+        let small = test_file("small.bin", &vec![0u8; 850_000]);
+        assert!(check_download_size(&small, 1).is_err());
+    }
+}
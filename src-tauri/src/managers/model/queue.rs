@@ -0,0 +1,476 @@
+use super::DownloadProgress;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Attempts after the first before a download is marked as failed
+pub const MAX_RETRIES: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const RETRY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// The error everyone waiting on a download gets when it is cancelled
+pub const CANCELLED: &str = "cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadState {
+    Queued,
+    Downloading,
+    Paused,
+    /// Waiting to retry after a transient error
+    Retrying,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// How a download attempt ended when it did not fail
+pub enum DownloadOutcome {
+    Completed,
+    Paused,
+    Cancelled,
+}
+
+/// A network failure, server error or truncated download that is worth
+/// retrying
+#[derive(Debug)]
+pub struct TransientError(pub String);
+
+impl fmt::Display for TransientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TransientError {}
+
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<TransientError>().is_some()
+        || error.downcast_ref::<reqwest::Error>().is_some()
+}
+
+/// Exponential backoff before retry number `attempt` (starting at 1)
+pub fn backoff(attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(5);
+    (INITIAL_BACKOFF * factor).min(MAX_BACKOFF)
+}
+
+/// Flags a running download checks between chunks
+#[derive(Default)]
+pub struct DownloadControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+impl DownloadControl {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn should_stop(&self) -> bool {
+        self.paused.load(Ordering::Relaxed) || self.is_cancelled()
+    }
+
+    pub fn stop_outcome(&self) -> DownloadOutcome {
+        if self.is_cancelled() {
+            DownloadOutcome::Cancelled
+        } else {
+            DownloadOutcome::Paused
+        }
+    }
+}
+
+/// Sleeps for `delay` unless the download is paused or cancelled first.
+/// Returns whether the retry should go ahead.
+pub async fn wait_for_retry(control: &DownloadControl, delay: Duration) -> bool {
+    let deadline = Instant::now() + delay;
+    loop {
+        if control.should_stop() {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        tokio::time::sleep(RETRY_POLL_INTERVAL.min(deadline - now)).await;
+    }
+}
+
+type DownloadResult = Option<Result<(), String>>;
+
+struct QueuedDownload {
+    model_id: String,
+    state: DownloadState,
+    downloaded: u64,
+    total: u64,
+    control: Arc<DownloadControl>,
+    done: watch::Sender<DownloadResult>,
+}
+
+/// Downloads that are waiting, running or paused, in the order they were
+/// requested. Finished downloads leave the queue.
+#[derive(Default)]
+pub struct DownloadQueue {
+    entries: Mutex<Vec<QueuedDownload>>,
+    /// When the bandwidth limit allows the next chunk to be sent
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl DownloadQueue {
+    /// Adds a download, or returns the existing one's completion if the model
+    /// is already queued. A paused download is queued again.
+    pub fn enqueue(&self, model_id: &str) -> watch::Receiver<DownloadResult> {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.iter_mut().find(|e| e.model_id == model_id) {
+            // Also takes back a pause or cancel the download has not acted on yet
+            entry.control.paused.store(false, Ordering::Relaxed);
+            entry.control.cancelled.store(false, Ordering::Relaxed);
+            if entry.state == DownloadState::Paused {
+                entry.state = DownloadState::Queued;
+            }
+            return entry.done.subscribe();
+        }
+
+        let (done, receiver) = watch::channel(None);
+        entries.push(QueuedDownload {
+            model_id: model_id.to_string(),
+            state: DownloadState::Queued,
+            downloaded: 0,
+            total: 0,
+            control: Arc::new(DownloadControl::default()),
+            done,
+        });
+        receiver
+    }
+
+    /// Marks queued downloads as running until `max_active` are, returning
+    /// the ones that should be started
+    pub fn start_next(&self, max_active: usize) -> Vec<(String, Arc<DownloadControl>)> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut active = entries.iter().filter(|e| is_active(e.state)).count();
+        let mut started = Vec::new();
+        for entry in entries.iter_mut() {
+            if active >= max_active {
+                break;
+            }
+            if entry.state == DownloadState::Queued {
+                entry.state = DownloadState::Downloading;
+                started.push((entry.model_id.clone(), entry.control.clone()));
+                active += 1;
+            }
+        }
+        started
+    }
+
+    pub fn set_state(&self, model_id: &str, state: DownloadState) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.iter_mut().find(|e| e.model_id == model_id) {
+            entry.state = state;
+        }
+    }
+
+    /// Pauses a download. Running downloads stop at their next chunk and keep
+    /// their partial file.
+    pub fn pause(&self, model_id: &str) -> Result<(), String> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries
+            .iter_mut()
+            .find(|e| e.model_id == model_id)
+            .ok_or_else(|| format!("Model {} is not being downloaded", model_id))?;
+
+        entry.control.paused.store(true, Ordering::Relaxed);
+        if entry.state == DownloadState::Queued {
+            entry.state = DownloadState::Paused;
+        }
+        Ok(())
+    }
+
+    /// Stops a download. Returns true if it was not running and has been
+    /// removed from the queue; a running one is removed when it stops.
+    pub fn cancel(&self, model_id: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let Some(index) = entries.iter().position(|e| e.model_id == model_id) else {
+            return false;
+        };
+
+        entries[index]
+            .control
+            .cancelled
+            .store(true, Ordering::Relaxed);
+        if is_active(entries[index].state) {
+            return false;
+        }
+        let entry = entries.remove(index);
+        let _ = entry.done.send(Some(Err(CANCELLED.to_string())));
+        true
+    }
+
+    /// Removes a download and reports its result to everyone waiting on it
+    pub fn finish(&self, model_id: &str, result: Result<(), String>) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(index) = entries.iter().position(|e| e.model_id == model_id) {
+            let entry = entries.remove(index);
+            let _ = entry.done.send(Some(result));
+        }
+    }
+
+    pub fn contains(&self, model_id: &str) -> bool {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().any(|e| e.model_id == model_id)
+    }
+
+    /// Models whose download is running or waiting to retry
+    pub fn active_ids(&self) -> Vec<String> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|e| is_active(e.state))
+            .map(|e| e.model_id.clone())
+            .collect()
+    }
+
+    /// Records a download's progress and returns the event reporting it
+    pub fn update_progress(&self, model_id: &str, downloaded: u64, total: u64) -> DownloadProgress {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            if let Some(entry) = entries.iter_mut().find(|e| e.model_id == model_id) {
+                entry.downloaded = downloaded;
+                entry.total = total;
+            }
+        }
+        self.progress(model_id, None)
+    }
+
+    /// The progress event for a download, with `state` overriding the queued
+    /// state (needed once the download has left the queue)
+    pub fn progress(&self, model_id: &str, state: Option<DownloadState>) -> DownloadProgress {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.iter().find(|e| e.model_id == model_id);
+        let downloaded = entry.map_or(0, |e| e.downloaded);
+        let total = entry.map_or(0, |e| e.total);
+
+        DownloadProgress {
+            model_id: model_id.to_string(),
+            downloaded,
+            total,
+            percentage: percentage(downloaded, total),
+            state: state
+                .or(entry.map(|e| e.state))
+                .unwrap_or(DownloadState::Completed),
+            queue_downloaded: entries.iter().map(|e| e.downloaded).sum(),
+            queue_total: entries.iter().map(|e| e.total).sum(),
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<DownloadProgress> {
+        let ids: Vec<String> = {
+            let entries = self.entries.lock().unwrap();
+            entries.iter().map(|e| e.model_id.clone()).collect()
+        };
+        ids.iter().map(|id| self.progress(id, None)).collect()
+    }
+
+    /// Reserves time to transfer `bytes` under a limit shared by every
+    /// running download and returns how long the caller should wait
+    pub fn throttle(&self, bytes: usize, bytes_per_sec: u64) -> Duration {
+        let now = Instant::now();
+        let mut next_slot = self.next_slot.lock().unwrap();
+        let start = next_slot.filter(|slot| *slot > now).unwrap_or(now);
+        let end = start + Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64);
+        *next_slot = Some(end);
+        end - now
+    }
+}
+
+/// Waits until a download is installed, has failed or was cancelled
+pub async fn wait(mut done: watch::Receiver<DownloadResult>) -> Result<(), String> {
+    match done.wait_for(Option::is_some).await {
+        Ok(result) => result.clone().unwrap_or(Ok(())),
+        Err(_) => Err("Download was abandoned".to_string()),
+    }
+}
+
+fn is_active(state: DownloadState) -> bool {
+    matches!(state, DownloadState::Downloading | DownloadState::Retrying)
+}
+
+fn percentage(downloaded: u64, total: u64) -> f64 {
+    if total > 0 {
+        (downloaded as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn started_ids(queue: &DownloadQueue, max_active: usize) -> Vec<String> {
+        queue
+            .start_next(max_active)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    fn state(queue: &DownloadQueue, model_id: &str) -> DownloadState {
+        queue.progress(model_id, None).state
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0), Duration::from_secs(2));
+        assert_eq!(backoff(1), Duration::from_secs(2));
+        assert_eq!(backoff(2), Duration::from_secs(4));
+        assert_eq!(backoff(3), Duration::from_secs(8));
+        assert_eq!(backoff(5), Duration::from_secs(32));
+        assert_eq!(backoff(6), MAX_BACKOFF);
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_throttle_shares_the_limit() {
+        let queue = DownloadQueue::default();
+        let first = queue.throttle(1000, 1000);
+        assert!(first <= Duration::from_secs(1));
+        assert!(first > Duration::from_millis(900));
+
+        // The next chunk is scheduled after the first one, whoever sends it
+        let second = queue.throttle(500, 1000);
+        assert!(second <= Duration::from_millis(1500));
+        assert!(second > Duration::from_millis(1400));
+    }
+
+    #[test]
+    fn test_start_next_respects_concurrency() {
+        let queue = DownloadQueue::default();
+        for id in ["a", "b", "c", "d"] {
+            queue.enqueue(id);
+        }
+
+        assert_eq!(started_ids(&queue, 2), ["a", "b"]);
+        assert!(started_ids(&queue, 2).is_empty());
+        assert_eq!(state(&queue, "a"), DownloadState::Downloading);
+        assert_eq!(state(&queue, "c"), DownloadState::Queued);
+
+        // Downloads waiting to retry still take a slot
+        queue.set_state("b", DownloadState::Retrying);
+        assert!(started_ids(&queue, 2).is_empty());
+        assert_eq!(queue.active_ids(), ["a", "b"]);
+
+        queue.finish("a", Ok(()));
+        assert_eq!(started_ids(&queue, 2), ["c"]);
+        assert_eq!(started_ids(&queue, 3), ["d"]);
+    }
+
+    #[test]
+    fn test_enqueue_existing_download() {
+        let queue = DownloadQueue::default();
+        let first = queue.enqueue("a");
+        let second = queue.enqueue("a");
+        assert_eq!(queue.snapshot().len(), 1);
+
+        queue.finish("a", Err("boom".to_string()));
+        assert!(!queue.contains("a"));
+        assert_eq!(block_on(wait(first)), Err("boom".to_string()));
+        assert_eq!(block_on(wait(second)), Err("boom".to_string()));
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let queue = DownloadQueue::default();
+        queue.enqueue("a");
+        queue.enqueue("b");
+        assert!(queue.pause("missing").is_err());
+
+        // A queued download is paused right away and is not started
+        queue.pause("a").unwrap();
+        assert_eq!(state(&queue, "a"), DownloadState::Paused);
+        assert_eq!(started_ids(&queue, 2), ["b"]);
+
+        assert!(queue.start_next(2).is_empty());
+
+        // A running one keeps its state until it stops at the next chunk
+        queue.enqueue("a");
+        assert_eq!(state(&queue, "a"), DownloadState::Queued);
+        let (_, control) = queue.start_next(2).pop().unwrap();
+        queue.pause("a").unwrap();
+        assert_eq!(state(&queue, "a"), DownloadState::Downloading);
+        assert!(control.should_stop());
+        assert!(matches!(control.stop_outcome(), DownloadOutcome::Paused));
+
+        // Resuming before it stopped takes the pause back
+        queue.enqueue("a");
+        assert!(!control.should_stop());
+    }
+
+    #[test]
+    fn test_cancel_queued_download() {
+        let queue = DownloadQueue::default();
+        let done = queue.enqueue("a");
+        assert!(!queue.cancel("missing"));
+
+        assert!(queue.cancel("a"));
+        assert!(!queue.contains("a"));
+        assert_eq!(block_on(wait(done)), Err(CANCELLED.to_string()));
+    }
+
+    #[test]
+    fn test_cancel_running_download() {
+        let queue = DownloadQueue::default();
+        queue.enqueue("a");
+        let (_, control) = queue.start_next(1).pop().unwrap();
+
+        // The running download leaves the queue once it has stopped
+        assert!(!queue.cancel("a"));
+        assert!(queue.contains("a"));
+        assert!(control.is_cancelled());
+        assert!(matches!(control.stop_outcome(), DownloadOutcome::Cancelled));
+
+        queue.enqueue("a");
+        assert!(!control.should_stop());
+    }
+
+    #[test]
+    fn test_wait_for_retry() {
+        let control = DownloadControl::default();
+        assert!(block_on(wait_for_retry(
+            &control,
+            Duration::from_millis(10)
+        )));
+
+        control.paused.store(true, Ordering::Relaxed);
+        assert!(!block_on(wait_for_retry(&control, Duration::from_secs(60))));
+    }
+
+    #[test]
+    fn test_progress() {
+        let queue = DownloadQueue::default();
+        queue.enqueue("a");
+        queue.enqueue("b");
+        queue.update_progress("b", 300, 400);
+
+        let progress = queue.update_progress("a", 50, 200);
+        assert_eq!(progress.percentage, 25.0);
+        assert_eq!(progress.queue_downloaded, 350);
+        assert_eq!(progress.queue_total, 600);
+
+        queue.finish("a", Ok(()));
+        let progress = queue.progress("a", None);
+        assert_eq!(progress.state, DownloadState::Completed);
+        assert_eq!(progress.percentage, 0.0);
+    }
+}
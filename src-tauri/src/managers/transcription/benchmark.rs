@@ -0,0 +1,489 @@
+use super::engine::load_engine;
+use crate::audio_toolkit::read_audio_file;
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::settings::AppSettings;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use strsim::generic_levenshtein;
+
+const SAMPLE_RATE: usize = 16000;
+const CLIP_SECS: usize = 10;
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Measurements of one model on this machine
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelBenchmark {
+    pub model_id: String,
+    pub load_ms: u64,
+    /// Time to transcribe the reference clip
+    pub transcribe_ms: u64,
+    /// Transcription time divided by the clip length; below 1.0 is faster
+    /// than real time
+    pub real_time_factor: f64,
+    /// Memory the model added to the process while loading and transcribing
+    pub peak_memory_mb: u64,
+    /// Share of the reference transcript's words the model got wrong. None
+    /// when the model was measured on the synthetic fallback clip.
+    #[serde(default)]
+    pub word_error_rate: Option<f64>,
+    /// Unix timestamp in seconds
+    pub measured_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BenchmarkReport {
+    pub results: Vec<ModelBenchmark>,
+    pub target_latency_ms: u64,
+    /// The installed model with the lowest word error rate that transcribes
+    /// the clip within the target latency, or the fastest one if none does
+    pub recommended_model: Option<String>,
+    /// Some results were measured on the synthetic fallback clip. They have
+    /// no word error rate, and are ranked by the catalog's accuracy score.
+    pub synthetic_clip: bool,
+}
+
+/// The audio every model is benchmarked on, with what is said in it
+pub struct ReferenceClip {
+    pub samples: Vec<f32>,
+    /// None for the synthetic clip, which only measures speed and memory
+    pub transcript: Option<String>,
+}
+
+impl ReferenceClip {
+    /// The bundled recording and its transcript, or the synthetic clip if
+    /// the build does not include the recording
+    pub fn load(audio_path: &Path, transcript_path: &Path) -> Result<Self> {
+        if !audio_path.exists() {
+            return Ok(Self {
+                samples: synthetic_clip(),
+                transcript: None,
+            });
+        }
+
+        Ok(Self {
+            samples: read_audio_file(audio_path)?,
+            transcript: Some(fs::read_to_string(transcript_path)?),
+        })
+    }
+}
+
+/// Loads a model on its own, transcribes the reference clip with it and
+/// unloads it again. The model the app is using is left alone.
+pub fn measure(
+    model_info: &ModelInfo,
+    model_manager: &ModelManager,
+    settings: &AppSettings,
+    clip: &ReferenceClip,
+) -> Result<ModelBenchmark> {
+    let clip_secs = clip.samples.len() as f64 / SAMPLE_RATE as f64;
+    // The recording is in English and is compared word for word, so it is
+    // transcribed as plain English whatever the user's language settings are
+    let mut settings = settings.clone();
+    if clip.transcript.is_some() {
+        settings.selected_language = "en".to_string();
+        settings.translate_to_english = false;
+    }
+    let memory = PeakMemory::start();
+
+    let load_start = Instant::now();
+    let mut engine = load_engine(model_info, model_manager)?;
+    let load_ms = load_start.elapsed().as_millis() as u64;
+
+    let transcribe_start = Instant::now();
+    let result = engine.transcribe(clip.samples.clone(), &settings);
+    let transcribe_ms = transcribe_start.elapsed().as_millis() as u64;
+    engine.unload();
+    drop(engine);
+    let peak_memory = memory.finish();
+    let transcription = result?;
+
+    Ok(ModelBenchmark {
+        model_id: model_info.id.clone(),
+        load_ms,
+        transcribe_ms,
+        real_time_factor: transcribe_ms as f64 / 1000.0 / clip_secs,
+        peak_memory_mb: peak_memory / (1024 * 1024),
+        word_error_rate: clip
+            .transcript
+            .as_deref()
+            .map(|transcript| word_error_rate(transcript, &transcription.text)),
+        measured_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// Word-level edit distance between a transcription and its reference,
+/// divided by the number of reference words. Case and punctuation are
+/// ignored.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> f64 {
+    let reference = normalized_words(reference);
+    let hypothesis = normalized_words(hypothesis);
+    if reference.is_empty() {
+        return if hypothesis.is_empty() { 0.0 } else { 1.0 };
+    }
+    generic_levenshtein(&reference, &hypothesis) as f64 / reference.len() as f64
+}
+
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Picks the installed model with the lowest word error rate among those
+/// whose reference clip transcription took no longer than
+/// `target_latency_ms`. Results from the synthetic clip have no word error
+/// rate and are only ranked, by the catalog's accuracy score, when no fast
+/// enough model was measured on the recording. If none is fast enough, the
+/// fastest one is picked.
+pub fn recommend(
+    models: &[ModelInfo],
+    results: &[ModelBenchmark],
+    target_latency_ms: u64,
+) -> Option<String> {
+    let measured: Vec<(&ModelInfo, &ModelBenchmark)> = results
+        .iter()
+        .filter_map(|result| {
+            models
+                .iter()
+                .find(|m| m.id == result.model_id && is_candidate(m))
+                .map(|model| (model, result))
+        })
+        .collect();
+
+    let fast_enough: Vec<(&ModelInfo, &ModelBenchmark)> = measured
+        .iter()
+        .copied()
+        .filter(|(_, result)| result.transcribe_ms <= target_latency_ms)
+        .collect();
+
+    fast_enough
+        .iter()
+        .copied()
+        .filter(|(_, result)| result.word_error_rate.is_some())
+        .min_by(|(_, a), (_, b)| {
+            a.word_error_rate
+                .unwrap_or_default()
+                .total_cmp(&b.word_error_rate.unwrap_or_default())
+                .then(a.transcribe_ms.cmp(&b.transcribe_ms))
+        })
+        .or_else(|| {
+            fast_enough
+                .iter()
+                .copied()
+                .max_by(|(a, a_result), (b, b_result)| {
+                    a.accuracy_score
+                        .total_cmp(&b.accuracy_score)
+                        .then(b_result.transcribe_ms.cmp(&a_result.transcribe_ms))
+                })
+        })
+        .or_else(|| {
+            measured
+                .iter()
+                .copied()
+                .min_by_key(|(_, result)| result.transcribe_ms)
+        })
+        .map(|(model, _)| model.id.clone())
+}
+
+/// Installed models that run on this machine and can be benchmarked
+pub fn is_candidate(model: &ModelInfo) -> bool {
+    model.is_downloaded && !model.is_downloading && !matches!(model.engine_type, EngineType::Remote)
+}
+
+pub fn read_benchmarks(path: &Path) -> Result<Vec<ModelBenchmark>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+pub fn write_benchmarks(path: &Path, results: &[ModelBenchmark]) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(results)?)?;
+    Ok(())
+}
+
+/// Ten seconds of synthetic speech: voiced syllables with shifting pitch and
+/// vowel formants, consonant noise and pauses between words and phrases. It is
+/// not meant to be transcribed correctly, and the engines emit a different
+/// number of tokens on it than on speech, so its timings are only a rough
+/// guide. Used when the build has no reference recording.
+fn synthetic_clip() -> Vec<f32> {
+    // First three formants of a, e, i, o, u in Hz
+    const VOWELS: [[f32; 3]; 5] = [
+        [730.0, 1090.0, 2440.0],
+        [530.0, 1840.0, 2480.0],
+        [270.0, 2290.0, 3010.0],
+        [570.0, 840.0, 2410.0],
+        [300.0, 870.0, 2240.0],
+    ];
+
+    let total = SAMPLE_RATE * CLIP_SECS;
+    let mut clip = Vec::with_capacity(total);
+    let mut noise = 0x2545_f491_u32;
+    let mut next_noise = move || {
+        // xorshift, so the clip is identical on every run
+        noise ^= noise << 13;
+        noise ^= noise >> 17;
+        noise ^= noise << 5;
+        noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+    };
+
+    let mut syllable = 0usize;
+    let mut phase = 0.0f32;
+    while clip.len() < total {
+        // Words of two to four syllables, with a longer pause every fourth word
+        let word_len = 2 + syllable % 3;
+        for _ in 0..word_len {
+            let formants = VOWELS[(syllable * 7 + 3) % VOWELS.len()];
+            let pitch = 105.0 + 25.0 * ((syllable as f32) * 0.9).sin();
+
+            // A short burst of noise stands in for the consonant
+            let consonant = SAMPLE_RATE * (30 + (syllable % 4) * 15) / 1000;
+            clip.extend((0..consonant).map(|_| next_noise() * 0.05));
+
+            let vowel = SAMPLE_RATE * (140 + (syllable % 5) * 30) / 1000;
+            let mut filters = formants.map(|frequency| Resonator::new(frequency, 90.0));
+            for i in 0..vowel {
+                phase += pitch / SAMPLE_RATE as f32;
+                phase -= phase.floor();
+                // Sawtooth glottal source shaped by the vowel's formants
+                let mut sample = 1.0 - 2.0 * phase;
+                for filter in &mut filters {
+                    sample = filter.process(sample);
+                }
+                let envelope = (PI * i as f32 / vowel as f32).sin();
+                clip.push(sample * envelope);
+            }
+            syllable += 1;
+        }
+
+        let pause_ms = if syllable.is_multiple_of(4) { 400 } else { 90 };
+        clip.resize(clip.len() + SAMPLE_RATE * pause_ms / 1000, 0.0);
+    }
+    clip.truncate(total);
+
+    let peak = clip.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        clip.iter_mut().for_each(|s| *s *= 0.3 / peak);
+    }
+    clip
+}
+
+/// Two-pole resonant filter for one formant
+struct Resonator {
+    a1: f32,
+    a2: f32,
+    gain: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Resonator {
+    fn new(frequency: f32, bandwidth: f32) -> Self {
+        let r = (-PI * bandwidth / SAMPLE_RATE as f32).exp();
+        let theta = 2.0 * PI * frequency / SAMPLE_RATE as f32;
+        Self {
+            a1: 2.0 * r * theta.cos(),
+            a2: -r * r,
+            gain: 1.0 - r,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.gain * input + self.a1 * self.y1 + self.a2 * self.y2;
+        self.y2 = self.y1;
+        self.y1 = output;
+        output
+    }
+}
+
+/// Samples the process's resident memory in the background and reports the
+/// highest value above where it started
+struct PeakMemory {
+    baseline: u64,
+    stop: Arc<AtomicBool>,
+    sampler: thread::JoinHandle<u64>,
+}
+
+impl PeakMemory {
+    fn start() -> Self {
+        let baseline = resident_memory();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let sampler = thread::spawn(move || {
+            let mut peak = baseline;
+            while !stop_flag.load(Ordering::Relaxed) {
+                peak = peak.max(resident_memory());
+                thread::sleep(MEMORY_SAMPLE_INTERVAL);
+            }
+            peak
+        });
+        Self {
+            baseline,
+            stop,
+            sampler,
+        }
+    }
+
+    fn finish(self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        let peak = self.sampler.join().unwrap_or(self.baseline);
+        peak.saturating_sub(self.baseline)
+    }
+}
+
+fn resident_memory() -> u64 {
+    memory_stats::memory_stats()
+        .map(|stats| stats.physical_mem as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str, accuracy_score: f32) -> ModelInfo {
+        ModelInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            filename: format!("{}.bin", id),
+            url: None,
+            size_mb: 0,
+            is_downloaded: true,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory: false,
+            engine_type: EngineType::Whisper,
+            accuracy_score,
+            speed_score: 0.5,
+            is_custom: false,
+            sha256: None,
+            version: 1,
+            languages: None,
+            update_available: false,
+            expected_files: None,
+        }
+    }
+
+    fn result(model_id: &str, transcribe_ms: u64, word_error_rate: Option<f64>) -> ModelBenchmark {
+        ModelBenchmark {
+            model_id: model_id.to_string(),
+            load_ms: 100,
+            transcribe_ms,
+            real_time_factor: transcribe_ms as f64 / 10_000.0,
+            peak_memory_mb: 500,
+            word_error_rate,
+            measured_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_word_error_rate() {
+        let reference = "The birch canoe slid on the smooth planks.";
+        assert_eq!(word_error_rate(reference, reference), 0.0);
+        assert_eq!(
+            word_error_rate(reference, "the Birch canoe slid, on the smooth planks"),
+            0.0
+        );
+        // One substitution and one deletion out of eight words
+        assert_eq!(
+            word_error_rate(reference, "The bird canoe slid on smooth planks."),
+            0.25
+        );
+        // Insertions count too, so the rate can exceed 1
+        assert_eq!(word_error_rate("yes", "yes yes yes"), 2.0);
+        assert_eq!(word_error_rate(reference, ""), 1.0);
+        assert_eq!(word_error_rate("", ""), 0.0);
+        assert_eq!(word_error_rate("", "noise"), 1.0);
+    }
+
+    #[test]
+    fn test_recommend_ranks_on_measured_word_error_rate() {
+        // The catalog scores say large, but it made more mistakes on this machine
+        let models = [model("small", 0.6), model("large", 0.85)];
+        let results = [
+            result("small", 800, Some(0.05)),
+            result("large", 1500, Some(0.2)),
+        ];
+        assert_eq!(recommend(&models, &results, 2000).as_deref(), Some("small"));
+    }
+
+    #[test]
+    fn test_recommend_prefers_measured_results_over_synthetic_ones() {
+        let models = [model("small", 0.6), model("large", 0.85)];
+        let results = [result("small", 800, Some(0.3)), result("large", 1500, None)];
+        assert_eq!(recommend(&models, &results, 2000).as_deref(), Some("small"));
+    }
+
+    #[test]
+    fn test_recommend_falls_back_to_catalog_accuracy_for_synthetic_results() {
+        let models = [model("small", 0.6), model("large", 0.85)];
+        let results = [result("small", 800, None), result("large", 1500, None)];
+        assert_eq!(recommend(&models, &results, 2000).as_deref(), Some("large"));
+    }
+
+    #[test]
+    fn test_recommend_respects_target_latency() {
+        let models = [model("small", 0.6), model("large", 0.85)];
+        let results = [
+            result("small", 800, Some(0.1)),
+            result("large", 3000, Some(0.0)),
+        ];
+        assert_eq!(recommend(&models, &results, 2000).as_deref(), Some("small"));
+
+        // If nothing is fast enough, the fastest model is recommended
+        assert_eq!(recommend(&models, &results, 500).as_deref(), Some("small"));
+    }
+
+    #[test]
+    fn test_recommend_prefers_faster_model_on_equal_word_error_rate() {
+        let models = [model("a", 0.8), model("b", 0.8)];
+        let results = [result("a", 1200, Some(0.1)), result("b", 900, Some(0.1))];
+        assert_eq!(recommend(&models, &results, 2000).as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_recommend_skips_models_that_are_not_candidates() {
+        let mut removed = model("large", 0.85);
+        removed.is_downloaded = false;
+        let mut remote = model("remote", 0.9);
+        remote.engine_type = EngineType::Remote;
+        let models = [model("small", 0.6), removed, remote];
+        let results = [
+            result("small", 800, Some(0.3)),
+            result("large", 900, Some(0.0)),
+            result("remote", 100, Some(0.0)),
+            result("unknown", 100, Some(0.0)),
+        ];
+        assert_eq!(recommend(&models, &results, 2000).as_deref(), Some("small"));
+        assert_eq!(recommend(&models, &[], 2000), None);
+    }
+
+    #[test]
+    fn test_reference_clip_falls_back_to_synthetic_audio() {
+        let dir = std::env::temp_dir().join("handy-benchmark-missing-reference");
+        let clip =
+            ReferenceClip::load(&dir.join("reference.wav"), &dir.join("reference.txt")).unwrap();
+
+        assert!(clip.transcript.is_none());
+        assert_eq!(clip.samples.len(), SAMPLE_RATE * CLIP_SECS);
+    }
+}
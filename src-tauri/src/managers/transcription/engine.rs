@@ -0,0 +1,354 @@
+use super::remote::RemoteEngine;
+use super::{TranscriptSegment, TranscriptWord, Transcription};
+use crate::audio_toolkit::text::parse_custom_word;
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::settings::AppSettings;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use transcribe_rs::{
+    engines::{
+        parakeet::{
+            ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams, TimestampGranularity,
+        },
+        whisper::{WhisperEngine, WhisperInferenceParams},
+    },
+    TranscriptionEngine, TranscriptionResult,
+};
+
+// Word timestamps are grouped into segments at sentence ends, at pauses longer
+// than this, or once a segment reaches the word limit.
+const SEGMENT_PAUSE_SECS: f32 = 0.8;
+const SEGMENT_MAX_WORDS: usize = 24;
+
+// whisper.cpp keeps only the last 224 tokens of a prompt and drops its start,
+// which is where the context prompt goes. The prompt's estimated length is kept
+// under this many tokens so none of it is cut.
+const MAX_PROMPT_TOKENS: usize = 180;
+
+/// A loaded speech recognition backend. New backends implement this and add
+/// an arm to [`load_engine`]; nothing else in the manager matches on them.
+pub trait SpeechEngine: Send {
+    /// Transcribes 16 kHz mono samples. Times in the result are seconds from
+    /// the start of `audio`.
+    fn transcribe(&mut self, audio: Vec<f32>, settings: &AppSettings) -> Result<Transcription>;
+
+    /// Releases the model's resources before the engine is dropped
+    fn unload(&mut self) {}
+
+    /// Whether the engine is given [`transcription_prompt`], so custom words
+    /// can bias it instead of only being corrected afterwards
+    fn supports_prompt(&self) -> bool {
+        false
+    }
+}
+
+/// Creates and loads the engine for a model
+pub fn load_engine(
+    model_info: &ModelInfo,
+    model_manager: &ModelManager,
+) -> Result<Box<dyn SpeechEngine>> {
+    let model_path = match model_info.engine_type {
+        EngineType::Remote => PathBuf::new(),
+        _ => model_manager.get_model_path(&model_info.id)?,
+    };
+    load_engine_at(model_info, &model_path)
+}
+
+/// Creates and loads the engine for a model stored at `model_path`. Used
+/// where there is no [`ModelManager`], such as `handy-cli`; remote models
+/// ignore the path.
+pub fn load_engine_at(model_info: &ModelInfo, model_path: &Path) -> Result<Box<dyn SpeechEngine>> {
+    match model_info.engine_type {
+        EngineType::Whisper => {
+            let mut engine = WhisperEngine::new();
+            engine.load_model(model_path).map_err(|e| {
+                anyhow::anyhow!("Failed to load whisper model {}: {}", model_info.id, e)
+            })?;
+            Ok(Box::new(WhisperSpeechEngine(engine)))
+        }
+        EngineType::Parakeet => {
+            let mut engine = ParakeetEngine::new();
+            engine
+                .load_model_with_params(model_path, ParakeetModelParams::int8())
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to load parakeet model {}: {}", model_info.id, e)
+                })?;
+            Ok(Box::new(ParakeetSpeechEngine(engine)))
+        }
+        EngineType::Remote => Ok(Box::new(RemoteEngine::new())),
+    }
+}
+
+/// Whisper uses ISO 639-1 codes, so both Chinese variants map to `zh`.
+/// Returns `None` for automatic detection.
+pub fn whisper_language(language: &str) -> Option<String> {
+    match language {
+        "auto" => None,
+        "zh-Hans" | "zh-Hant" => Some("zh".to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// The text that primes engines taking a prompt: the context prompt followed by
+/// the custom words when they are used for biasing. `None` if there is neither.
+/// The prompt is at most [`MAX_PROMPT_TOKENS`] tokens by [`token_thirds`];
+/// words that don't fit are left out, and a longer context prompt is cut short.
+pub fn transcription_prompt(settings: &AppSettings) -> Option<String> {
+    let budget = MAX_PROMPT_TOKENS * 3;
+    let context = settings.transcription_context_prompt.trim();
+    let mut prompt = String::new();
+    let mut prompt_cost = 0;
+    for c in context.chars() {
+        let cost = token_thirds(c.encode_utf8(&mut [0; 4]));
+        if prompt_cost + cost > budget {
+            break;
+        }
+        prompt.push(c);
+        prompt_cost += cost;
+    }
+
+    if settings.custom_words_mode.biases() {
+        let mut has_words = false;
+        // Only the written form helps; the spoken form is what gets misheard
+        for word in settings.custom_words.iter().map(|w| parse_custom_word(w).1) {
+            if word.is_empty() {
+                continue;
+            }
+            let separator = match (has_words, prompt.is_empty()) {
+                (true, _) => ", ",
+                (false, true) => "",
+                (false, false) => " ",
+            };
+            let word_cost = token_thirds(separator) + token_thirds(word);
+            // Leave room for the full stop after the last word
+            if prompt_cost + word_cost + token_thirds(".") > budget {
+                break;
+            }
+            prompt.push_str(separator);
+            prompt.push_str(word);
+            prompt_cost += word_cost;
+            has_words = true;
+        }
+        if has_words {
+            prompt.push('.');
+        }
+    }
+
+    if prompt.is_empty() {
+        None
+    } else {
+        Some(prompt)
+    }
+}
+
+/// A conservative estimate of how many whisper tokens `text` takes, in thirds
+/// of a token. English averages about four characters per token, so ASCII
+/// characters count a third. Other scripts can take a token per UTF-8 byte, so
+/// a CJK character counts three tokens.
+fn token_thirds(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c.is_ascii() { 1 } else { 3 * c.len_utf8() })
+        .sum()
+}
+
+struct WhisperSpeechEngine(WhisperEngine);
+
+impl SpeechEngine for WhisperSpeechEngine {
+    fn transcribe(&mut self, audio: Vec<f32>, settings: &AppSettings) -> Result<Transcription> {
+        let params = WhisperInferenceParams {
+            language: whisper_language(&settings.selected_language),
+            translate: settings.translate_to_english,
+            initial_prompt: transcription_prompt(settings),
+            ..Default::default()
+        };
+
+        let result = self
+            .0
+            .transcribe_samples(audio, Some(params))
+            .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))?;
+        Ok(segments_from_result(result))
+    }
+
+    fn unload(&mut self) {
+        self.0.unload_model();
+    }
+
+    fn supports_prompt(&self) -> bool {
+        true
+    }
+}
+
+// transcribe-rs has no hotword boosting for Parakeet, so custom words can
+// only be applied by correction
+struct ParakeetSpeechEngine(ParakeetEngine);
+
+impl SpeechEngine for ParakeetSpeechEngine {
+    fn transcribe(&mut self, audio: Vec<f32>, _settings: &AppSettings) -> Result<Transcription> {
+        let params = ParakeetInferenceParams {
+            timestamp_granularity: TimestampGranularity::Word,
+            ..Default::default()
+        };
+
+        let result = self
+            .0
+            .transcribe_samples(audio, Some(params))
+            .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))?;
+        Ok(segments_from_words(result))
+    }
+
+    fn unload(&mut self) {
+        self.0.unload_model();
+    }
+}
+
+/// Converts engine segments into transcript segments without word timings.
+fn segments_from_result(result: TranscriptionResult) -> Transcription {
+    let segments = result
+        .segments
+        .unwrap_or_default()
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            start: segment.start,
+            end: segment.end,
+            text: segment.text.trim().to_string(),
+            words: Vec::new(),
+        })
+        .filter(|segment| !segment.text.is_empty())
+        .collect();
+
+    Transcription {
+        text: result.text,
+        segments,
+    }
+}
+
+/// Groups word-level engine output into segments, keeping the words on each.
+fn segments_from_words(result: TranscriptionResult) -> Transcription {
+    let mut segments: Vec<TranscriptSegment> = Vec::new();
+    let mut current: Vec<TranscriptWord> = Vec::new();
+
+    let flush = |current: &mut Vec<TranscriptWord>, segments: &mut Vec<TranscriptSegment>| {
+        if let (Some(first), Some(last)) = (current.first(), current.last()) {
+            segments.push(TranscriptSegment {
+                start: first.start,
+                end: last.end,
+                text: current
+                    .iter()
+                    .map(|w| w.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                words: std::mem::take(current),
+            });
+        }
+    };
+
+    for word in result.segments.unwrap_or_default() {
+        let text = word.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(previous) = current.last() {
+            if word.start - previous.end > SEGMENT_PAUSE_SECS {
+                flush(&mut current, &mut segments);
+            }
+        }
+
+        let ends_sentence = text.ends_with(['.', '?', '!']);
+        current.push(TranscriptWord {
+            start: word.start,
+            end: word.end,
+            text: text.to_string(),
+            confidence: None,
+        });
+
+        if ends_sentence || current.len() >= SEGMENT_MAX_WORDS {
+            flush(&mut current, &mut segments);
+        }
+    }
+    flush(&mut current, &mut segments);
+
+    Transcription {
+        text: result.text,
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{get_default_settings, CustomWordsMode};
+
+    fn settings(mode: CustomWordsMode, context: &str, words: &[&str]) -> AppSettings {
+        let mut settings = get_default_settings();
+        settings.custom_words_mode = mode;
+        settings.transcription_context_prompt = context.to_string();
+        settings.custom_words = words.iter().map(|w| w.to_string()).collect();
+        settings
+    }
+
+    #[test]
+    fn test_transcription_prompt_modes() {
+        let words = ["Handy", "Tauri"];
+        assert_eq!(
+            transcription_prompt(&settings(CustomWordsMode::Correction, "", &words)),
+            None
+        );
+        assert_eq!(
+            transcription_prompt(&settings(CustomWordsMode::Correction, "A talk.", &words))
+                .as_deref(),
+            Some("A talk.")
+        );
+        assert_eq!(
+            transcription_prompt(&settings(CustomWordsMode::Biasing, "", &words)).as_deref(),
+            Some("Handy, Tauri.")
+        );
+        assert_eq!(
+            transcription_prompt(&settings(CustomWordsMode::Both, " A talk. ", &words)).as_deref(),
+            Some("A talk. Handy, Tauri.")
+        );
+    }
+
+    #[test]
+    fn test_transcription_prompt_skips_empty_words() {
+        let words = ["", "  ", "handy -> Handy", "Tauri"];
+        assert_eq!(
+            transcription_prompt(&settings(CustomWordsMode::Biasing, "", &words)).as_deref(),
+            Some("Handy, Tauri.")
+        );
+        assert_eq!(
+            transcription_prompt(&settings(CustomWordsMode::Biasing, "", &["", " "])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_transcription_prompt_fits_whisper_window_for_cjk() {
+        // Three characters but nine bytes each, and whisper's byte-level
+        // tokens can take one token per byte
+        let words = ["日本語"; 200];
+        let context = "Notes on Japanese.";
+        let prompt =
+            transcription_prompt(&settings(CustomWordsMode::Both, context, &words)).unwrap();
+
+        assert!(prompt.starts_with("Notes on Japanese. 日本語, 日本語"));
+        assert!(prompt.ends_with("日本語."));
+        // A token is at least one byte, so this holds for any tokenization
+        assert!(prompt.len() <= 224);
+        assert!(token_thirds(&prompt) <= MAX_PROMPT_TOKENS * 3);
+        assert!(token_thirds(&prompt) > (MAX_PROMPT_TOKENS * 3) - token_thirds(", 日本語"));
+    }
+
+    #[test]
+    fn test_transcription_prompt_cuts_long_context() {
+        let context = "a".repeat(MAX_PROMPT_TOKENS * 3 + 50);
+        let prompt =
+            transcription_prompt(&settings(CustomWordsMode::Both, &context, &["Handy"])).unwrap();
+        assert_eq!(prompt, "a".repeat(MAX_PROMPT_TOKENS * 3));
+
+        let context = "語".repeat(MAX_PROMPT_TOKENS);
+        let prompt = transcription_prompt(&settings(CustomWordsMode::Both, &context, &[])).unwrap();
+        assert_eq!(prompt, "語".repeat(MAX_PROMPT_TOKENS / 3));
+        assert!(prompt.len() <= 224);
+    }
+}
@@ -0,0 +1,861 @@
+mod benchmark;
+mod engine;
+mod remote;
+
+use crate::audio_toolkit::{apply_custom_words, inverse_normalize};
+use crate::managers::model::{ModelManager, VerificationStatus};
+use crate::settings::{get_settings, AppSettings, ModelUnloadTimeout};
+use crate::utils;
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub use benchmark::{BenchmarkReport, ModelBenchmark};
+pub use engine::{load_engine_at, SpeechEngine};
+
+// Whisper needs at least a second of audio, so shorter streaming chunks are
+// padded with silence the same way short recordings are.
+const MIN_CHUNK_SAMPLES: usize = 16000;
+const PADDED_CHUNK_SAMPLES: usize = 16000 * 5 / 4;
+
+const BENCHMARKS_FILE: &str = "model_benchmarks.json";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchmarkProgressEvent {
+    pub model_id: String,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ModelStateEvent {
+    pub event_type: String,
+    pub model_id: Option<String>,
+    pub model_name: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A single recognised word. Times are in seconds from the start of the recording.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptWord {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    pub confidence: Option<f32>,
+}
+
+/// A timed span of the transcript. `words` is empty for engines that only
+/// report segment-level timestamps.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptSegment {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    pub words: Vec<TranscriptWord>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Transcription {
+    pub text: String,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+impl Transcription {
+    /// Appends a transcription of audio that started `offset` seconds into the recording.
+    fn append(&mut self, other: Transcription, offset: f32) {
+        let text = other.text.trim();
+        if !text.is_empty() {
+            if !self.text.is_empty() {
+                self.text.push(' ');
+            }
+            self.text.push_str(text);
+        }
+
+        self.segments
+            .extend(other.segments.into_iter().map(|mut segment| {
+                segment.start += offset;
+                segment.end += offset;
+                for word in &mut segment.words {
+                    word.start += offset;
+                    word.end += offset;
+                }
+                segment
+            }));
+    }
+}
+
+/// A streaming transcription in progress. Speech segments are sent to a worker
+/// thread that transcribes them one by one while the recording continues.
+struct StreamingSession {
+    chunk_tx: mpsc::Sender<Vec<f32>>,
+    worker: thread::JoinHandle<Transcription>,
+    settings: AppSettings,
+    /// Set on cancel so the worker skips the chunks still queued and stops
+    /// emitting partial transcripts
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Clone)]
+pub struct TranscriptionManager {
+    engine: Arc<Mutex<Option<Box<dyn SpeechEngine>>>>,
+    model_manager: Arc<ModelManager>,
+    app_handle: AppHandle,
+    current_model_id: Arc<Mutex<Option<String>>>,
+    last_activity: Arc<AtomicU64>,
+    shutdown_signal: Arc<AtomicBool>,
+    watcher_handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
+    is_loading: Arc<Mutex<bool>>,
+    loading_condvar: Arc<Condvar>,
+    /// A model requested while another was loading, loaded right after it
+    pending_model: Arc<Mutex<Option<String>>>,
+    streaming: Arc<Mutex<Option<StreamingSession>>>,
+}
+
+impl TranscriptionManager {
+    pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Result<Self> {
+        let manager = Self {
+            engine: Arc::new(Mutex::new(None)),
+            model_manager,
+            app_handle: app_handle.clone(),
+            current_model_id: Arc::new(Mutex::new(None)),
+            last_activity: Arc::new(AtomicU64::new(
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+            )),
+            shutdown_signal: Arc::new(AtomicBool::new(false)),
+            watcher_handle: Arc::new(Mutex::new(None)),
+            is_loading: Arc::new(Mutex::new(false)),
+            loading_condvar: Arc::new(Condvar::new()),
+            pending_model: Arc::new(Mutex::new(None)),
+            streaming: Arc::new(Mutex::new(None)),
+        };
+
+        // Start the idle watcher
+        {
+            let app_handle_cloned = app_handle.clone();
+            let manager_cloned = manager.clone();
+            let shutdown_signal = manager.shutdown_signal.clone();
+            let handle = thread::spawn(move || {
+                while !shutdown_signal.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_secs(10)); // Check every 10 seconds
+
+                    // Check shutdown signal again after sleep
+                    if shutdown_signal.load(Ordering::Relaxed) {
+                        break;
+                    }
+
+                    let settings = get_settings(&app_handle_cloned);
+                    let timeout_seconds = settings.model_unload_timeout.to_seconds();
+
+                    if let Some(limit_seconds) = timeout_seconds {
+                        // Skip polling-based unloading for immediate timeout since it's handled directly in transcribe()
+                        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately {
+                            continue;
+                        }
+
+                        let last = manager_cloned.last_activity.load(Ordering::Relaxed);
+                        let now_ms = SystemTime::now()
+                            .duration_since(SystemTime::UNIX_EPOCH)
+                            .unwrap()
+                            .as_millis() as u64;
+
+                        if now_ms.saturating_sub(last) > limit_seconds * 1000 {
+                            // idle -> unload
+                            if manager_cloned.is_model_loaded() {
+                                let unload_start = std::time::Instant::now();
+                                debug!("Starting to unload model due to inactivity");
+
+                                if let Ok(()) = manager_cloned.unload_model() {
+                                    let _ = app_handle_cloned.emit(
+                                        "model-state-changed",
+                                        ModelStateEvent {
+                                            event_type: "unloaded".to_string(),
+                                            model_id: None,
+                                            model_name: None,
+                                            error: None,
+                                        },
+                                    );
+                                    let unload_duration = unload_start.elapsed();
+                                    debug!(
+                                        "Model unloaded due to inactivity (took {}ms)",
+                                        unload_duration.as_millis()
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+                debug!("Idle watcher thread shutting down gracefully");
+            });
+            *manager.watcher_handle.lock().unwrap() = Some(handle);
+        }
+
+        Ok(manager)
+    }
+
+    pub fn is_model_loaded(&self) -> bool {
+        let engine = self.engine.lock().unwrap();
+        engine.is_some()
+    }
+
+    pub fn unload_model(&self) -> Result<()> {
+        let unload_start = std::time::Instant::now();
+        debug!("Starting to unload model");
+
+        {
+            let mut engine = self.engine.lock().unwrap();
+            if let Some(loaded_engine) = engine.as_mut() {
+                loaded_engine.unload();
+            }
+            *engine = None; // Drop the engine to free memory
+        }
+        {
+            let mut current_model = self.current_model_id.lock().unwrap();
+            *current_model = None;
+        }
+
+        // Emit unloaded event
+        let _ = self.app_handle.emit(
+            "model-state-changed",
+            ModelStateEvent {
+                event_type: "unloaded".to_string(),
+                model_id: None,
+                model_name: None,
+                error: None,
+            },
+        );
+
+        let unload_duration = unload_start.elapsed();
+        debug!(
+            "Model unloaded manually (took {}ms)",
+            unload_duration.as_millis()
+        );
+        Ok(())
+    }
+
+    pub fn load_model(&self, model_id: &str) -> Result<()> {
+        let load_start = std::time::Instant::now();
+        debug!("Starting to load model: {}", model_id);
+
+        // Emit loading started event
+        let _ = self.app_handle.emit(
+            "model-state-changed",
+            ModelStateEvent {
+                event_type: "loading_started".to_string(),
+                model_id: Some(model_id.to_string()),
+                model_name: None,
+                error: None,
+            },
+        );
+
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if !model_info.is_downloaded {
+            let error_msg = "Model not downloaded";
+            let _ = self.app_handle.emit(
+                "model-state-changed",
+                ModelStateEvent {
+                    event_type: "loading_failed".to_string(),
+                    model_id: Some(model_id.to_string()),
+                    model_name: Some(model_info.name.clone()),
+                    error: Some(error_msg.to_string()),
+                },
+            );
+            return Err(anyhow::anyhow!(error_msg));
+        }
+
+        let loaded_engine = engine::load_engine(&model_info, &self.model_manager).map_err(|e| {
+            let mut error_msg = e.to_string();
+            // A damaged download otherwise only shows up as an obscure engine error
+            if let Ok(verification) = self.model_manager.verify_model(model_id) {
+                if verification.status == VerificationStatus::Damaged {
+                    error_msg = format!(
+                        "{}. The model files are damaged ({}); repair or re-download the model.",
+                        error_msg,
+                        verification.problems.join("; ")
+                    );
+                }
+            }
+            let _ = self.app_handle.emit(
+                "model-state-changed",
+                ModelStateEvent {
+                    event_type: "loading_failed".to_string(),
+                    model_id: Some(model_id.to_string()),
+                    model_name: Some(model_info.name.clone()),
+                    error: Some(error_msg.clone()),
+                },
+            );
+            anyhow::anyhow!(error_msg)
+        })?;
+
+        // Update the current engine and model ID
+        {
+            let mut engine = self.engine.lock().unwrap();
+            *engine = Some(loaded_engine);
+        }
+        {
+            let mut current_model = self.current_model_id.lock().unwrap();
+            *current_model = Some(model_id.to_string());
+        }
+
+        // Emit loading completed event
+        let _ = self.app_handle.emit(
+            "model-state-changed",
+            ModelStateEvent {
+                event_type: "loading_completed".to_string(),
+                model_id: Some(model_id.to_string()),
+                model_name: Some(model_info.name.clone()),
+                error: None,
+            },
+        );
+
+        let load_duration = load_start.elapsed();
+        debug!(
+            "Successfully loaded transcription model: {} (took {}ms)",
+            model_id,
+            load_duration.as_millis()
+        );
+        Ok(())
+    }
+
+    /// Kicks off the model loading in a background thread if it's not already loaded
+    pub fn initiate_model_load(&self) {
+        let mut is_loading = self.is_loading.lock().unwrap();
+        if *is_loading || self.is_model_loaded() {
+            return;
+        }
+
+        *is_loading = true;
+        self.spawn_model_load(get_settings(&self.app_handle).selected_model);
+    }
+
+    /// Kicks off loading a specific model in a background thread, replacing a
+    /// different loaded model. Used when a binding or app profile overrides
+    /// the model. If another model is loading, this one is loaded after it.
+    pub fn initiate_model_load_for(&self, model_id: &str) {
+        let mut is_loading = self.is_loading.lock().unwrap();
+        if *is_loading {
+            debug!("Model {} will be loaded after the current load", model_id);
+            *self.pending_model.lock().unwrap() = Some(model_id.to_string());
+            return;
+        }
+        if self.get_current_model().as_deref() == Some(model_id) {
+            return;
+        }
+
+        *is_loading = true;
+        self.spawn_model_load(model_id.to_string());
+    }
+
+    /// Loads the model in a background thread, then any model requested
+    /// meanwhile, and clears `is_loading` once done. The caller sets
+    /// `is_loading`.
+    fn spawn_model_load(&self, model_id: String) {
+        let self_clone = self.clone();
+        thread::spawn(move || {
+            let mut model_id = model_id;
+            loop {
+                if let Err(e) = self_clone.load_model(&model_id) {
+                    error!("Failed to load model {}: {}", model_id, e);
+                }
+
+                let mut is_loading = self_clone.is_loading.lock().unwrap();
+                let pending = self_clone.pending_model.lock().unwrap().take();
+                match pending {
+                    Some(next) if self_clone.get_current_model().as_deref() != Some(&next) => {
+                        // Transcriptions keep waiting until the requested model is in
+                        model_id = next;
+                    }
+                    _ => {
+                        *is_loading = false;
+                        self_clone.loading_condvar.notify_all();
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn get_current_model(&self) -> Option<String> {
+        let current_model = self.current_model_id.lock().unwrap();
+        current_model.clone()
+    }
+
+    pub fn transcribe(&self, audio: Vec<f32>) -> Result<Transcription> {
+        // Get current settings for configuration
+        let settings = get_settings(&self.app_handle);
+        self.transcribe_with_settings(audio, &settings)
+    }
+
+    /// Transcribes with the currently loaded model, using the given settings
+    /// instead of the stored ones (e.g. to override the language). The loaded
+    /// model is expected to be `settings.selected_model`.
+    pub fn transcribe_with_settings(
+        &self,
+        audio: Vec<f32>,
+        settings: &AppSettings,
+    ) -> Result<Transcription> {
+        self.transcribe_loaded(audio, settings, &settings.selected_model)
+    }
+
+    /// Transcribes with the loaded model, which is expected to be `expected_model`
+    fn transcribe_loaded(
+        &self,
+        audio: Vec<f32>,
+        settings: &AppSettings,
+        expected_model: &str,
+    ) -> Result<Transcription> {
+        self.touch_activity();
+
+        let st = std::time::Instant::now();
+
+        debug!("Audio vector length: {}", audio.len());
+
+        if audio.len() == 0 {
+            debug!("Empty audio vector");
+            return Ok(Transcription::default());
+        }
+
+        let result = self.run_engine(audio, settings, expected_model)?;
+
+        let et = std::time::Instant::now();
+        let translation_note = if settings.translate_to_english {
+            " (translated)"
+        } else {
+            ""
+        };
+        info!(
+            "Transcription completed in {}ms{}",
+            (et - st).as_millis(),
+            translation_note
+        );
+
+        Ok(self.finalize_transcription(result, settings))
+    }
+
+    /// Transcribes with a specific model. A model other than the loaded one
+    /// is loaded into an engine of its own for this transcription only, so
+    /// recordings made meanwhile keep using the user's selected model.
+    pub fn transcribe_with_model(
+        &self,
+        audio: Vec<f32>,
+        model_id: &str,
+        settings: &AppSettings,
+    ) -> Result<Transcription> {
+        if self.get_current_model().as_deref() == Some(model_id) {
+            return self.transcribe_loaded(audio, settings, model_id);
+        }
+
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not downloaded: {}", model_id));
+        }
+        if audio.is_empty() {
+            return Ok(Transcription::default());
+        }
+
+        let st = std::time::Instant::now();
+        info!("Loading model {} separately for transcription", model_id);
+        let mut engine = engine::load_engine(&model_info, &self.model_manager)?;
+        let result = engine.transcribe(audio, settings);
+        let supports_prompt = engine.supports_prompt();
+        engine.unload();
+        drop(engine);
+        info!(
+            "Transcription with model {} completed in {}ms",
+            model_id,
+            st.elapsed().as_millis()
+        );
+
+        Ok(correct_transcription(result?, settings, supports_prompt))
+    }
+
+    /// Benchmarks every installed local model on the reference clip, one at a
+    /// time, and records the results. Emits `model-benchmark-progress` before
+    /// each model. A model that fails to load is logged and skipped.
+    pub fn benchmark_models(&self) -> Result<BenchmarkReport> {
+        let settings = get_settings(&self.app_handle);
+        let candidates: Vec<_> = self
+            .model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(benchmark::is_candidate)
+            .collect();
+
+        let clip = self.reference_clip()?;
+        if clip.transcript.is_none() {
+            warn!("No reference recording bundled; benchmarking on the synthetic clip without word error rates");
+        }
+
+        let path = self.benchmarks_path()?;
+        let mut results = benchmark::read_benchmarks(&path).unwrap_or_default();
+        for (completed, model_info) in candidates.iter().enumerate() {
+            let _ = self.app_handle.emit(
+                "model-benchmark-progress",
+                BenchmarkProgressEvent {
+                    model_id: model_info.id.clone(),
+                    completed,
+                    total: candidates.len(),
+                },
+            );
+
+            info!("Benchmarking model {}", model_info.id);
+            match benchmark::measure(model_info, &self.model_manager, &settings, &clip) {
+                Ok(result) => {
+                    info!(
+                        "Model {}: loaded in {}ms, real-time factor {:.2}, {} MB, word error rate {:?}",
+                        result.model_id,
+                        result.load_ms,
+                        result.real_time_factor,
+                        result.peak_memory_mb,
+                        result.word_error_rate
+                    );
+                    results.retain(|r| r.model_id != result.model_id);
+                    results.push(result);
+                }
+                Err(e) => warn!("Failed to benchmark model {}: {}", model_info.id, e),
+            }
+        }
+        benchmark::write_benchmarks(&path, &results)?;
+
+        self.get_benchmark_report()
+    }
+
+    /// The recorded benchmarks of installed models and the model they point to
+    pub fn get_benchmark_report(&self) -> Result<BenchmarkReport> {
+        let target_latency_ms = get_settings(&self.app_handle).benchmark_target_latency_ms;
+        let models = self.model_manager.get_available_models();
+        let mut results = benchmark::read_benchmarks(&self.benchmarks_path()?)?;
+        results.retain(|result| {
+            models
+                .iter()
+                .any(|m| m.id == result.model_id && benchmark::is_candidate(m))
+        });
+        results.sort_by_key(|result| result.transcribe_ms);
+
+        Ok(BenchmarkReport {
+            recommended_model: benchmark::recommend(&models, &results, target_latency_ms),
+            target_latency_ms,
+            synthetic_clip: results
+                .iter()
+                .any(|result| result.word_error_rate.is_none()),
+            results,
+        })
+    }
+
+    fn reference_clip(&self) -> Result<benchmark::ReferenceClip> {
+        let resolve = |path: &str| {
+            self.app_handle
+                .path()
+                .resolve(path, tauri::path::BaseDirectory::Resource)
+        };
+        benchmark::ReferenceClip::load(
+            &resolve("resources/benchmark/reference.wav")?,
+            &resolve("resources/benchmark/reference.txt")?,
+        )
+    }
+
+    fn benchmarks_path(&self) -> Result<std::path::PathBuf> {
+        Ok(self.app_handle.path().app_data_dir()?.join(BENCHMARKS_FILE))
+    }
+
+    /// Starts a streaming session. Speech segments pushed with
+    /// `push_streaming_chunk` are transcribed in the background and the text so
+    /// far is emitted as a `partial-transcript` event after every segment.
+    pub fn start_streaming_session(&self, settings: AppSettings) {
+        let mut streaming = self.streaming.lock().unwrap();
+        if streaming.is_some() {
+            debug!("Streaming session already active");
+            return;
+        }
+
+        let (chunk_tx, chunk_rx) = mpsc::channel::<Vec<f32>>();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let self_clone = self.clone();
+        let worker_settings = settings.clone();
+        let worker_cancelled = cancelled.clone();
+        let worker = thread::spawn(move || {
+            let settings = worker_settings;
+            let mut transcription = Transcription::default();
+            let mut offset_samples = 0usize;
+            for mut chunk in chunk_rx {
+                if worker_cancelled.load(Ordering::Relaxed) {
+                    break;
+                }
+                let offset = offset_samples as f32 / 16000.0;
+                offset_samples += chunk.len();
+                if chunk.len() < MIN_CHUNK_SAMPLES {
+                    chunk.resize(PADDED_CHUNK_SAMPLES, 0.0);
+                }
+
+                self_clone.touch_activity();
+                let chunk_start = std::time::Instant::now();
+                match self_clone.run_engine(chunk, &settings, &settings.selected_model) {
+                    Ok(result) => {
+                        debug!(
+                            "Streaming chunk transcribed in {}ms",
+                            chunk_start.elapsed().as_millis()
+                        );
+                        let has_text = !result.text.trim().is_empty();
+                        transcription.append(result, offset);
+                        if has_text && !worker_cancelled.load(Ordering::Relaxed) {
+                            utils::emit_partial_transcript(
+                                &self_clone.app_handle,
+                                &transcription.text,
+                            );
+                        }
+                    }
+                    Err(e) => error!("Failed to transcribe streaming chunk: {}", e),
+                }
+            }
+            transcription
+        });
+
+        *streaming = Some(StreamingSession {
+            chunk_tx,
+            worker,
+            settings,
+            cancelled,
+        });
+        debug!("Streaming session started");
+    }
+
+    /// Queues a speech segment for the active streaming session, if any.
+    pub fn push_streaming_chunk(&self, chunk: Vec<f32>) {
+        if let Some(session) = self.streaming.lock().unwrap().as_ref() {
+            debug!("Queueing streaming chunk of {} samples", chunk.len());
+            if session.chunk_tx.send(chunk).is_err() {
+                warn!("Streaming worker is no longer receiving chunks");
+            }
+        }
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.streaming.lock().unwrap().is_some()
+    }
+
+    /// Waits for every queued segment to be transcribed and returns the
+    /// assembled transcription, post-processed the same way as `transcribe`.
+    pub fn finish_streaming_session(&self) -> Result<Transcription> {
+        let session = self
+            .streaming
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("No streaming session is active"))?;
+
+        let st = std::time::Instant::now();
+        drop(session.chunk_tx);
+        let transcription = session
+            .worker
+            .join()
+            .map_err(|_| anyhow::anyhow!("Streaming transcription worker panicked"))?;
+        info!(
+            "Streaming transcription finished {}ms after stop ({} segments)",
+            st.elapsed().as_millis(),
+            transcription.segments.len()
+        );
+
+        Ok(self.finalize_transcription(transcription, &session.settings))
+    }
+
+    /// Drops the active streaming session without waiting for its result.
+    /// The worker stops once the chunk it is transcribing, if any, is done.
+    pub fn cancel_streaming_session(&self) {
+        let Some(session) = self.streaming.lock().unwrap().take() else {
+            return;
+        };
+        session.cancelled.store(true, Ordering::Relaxed);
+        drop(session.chunk_tx);
+        // Detach the worker rather than block the caller on its current chunk
+        drop(session.worker);
+        debug!("Streaming session cancelled");
+    }
+
+    fn touch_activity(&self) {
+        self.last_activity.store(
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Runs the loaded engine on the given audio, waiting for a model load in
+    /// progress to finish first. If the loaded model isn't `expected_model`,
+    /// e.g. because a binding's or profile's model failed to load, the audio
+    /// is still transcribed and a `fallback` model state event says so.
+    fn run_engine(
+        &self,
+        audio: Vec<f32>,
+        settings: &AppSettings,
+        expected_model: &str,
+    ) -> Result<Transcription> {
+        // Check if model is loaded, if not try to load it
+        {
+            // If the model is loading, wait for it to complete.
+            let mut is_loading = self.is_loading.lock().unwrap();
+            while *is_loading {
+                is_loading = self.loading_condvar.wait(is_loading).unwrap();
+            }
+
+            let engine_guard = self.engine.lock().unwrap();
+            if engine_guard.is_none() {
+                return Err(anyhow::anyhow!("Model is not loaded for transcription."));
+            }
+        }
+
+        if let Some(loaded_model) = self.get_current_model() {
+            if loaded_model != expected_model {
+                let message = format!(
+                    "Model {} is not loaded, transcribing with {} instead",
+                    expected_model, loaded_model
+                );
+                warn!("{}", message);
+                let _ = self.app_handle.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "fallback".to_string(),
+                        model_name: self
+                            .model_manager
+                            .get_model_info(&loaded_model)
+                            .map(|info| info.name),
+                        model_id: Some(loaded_model),
+                        error: Some(message),
+                    },
+                );
+            }
+        }
+
+        // Perform transcription with the appropriate engine
+        let mut engine_guard = self.engine.lock().unwrap();
+        let engine = engine_guard.as_mut().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Model failed to load after auto-load attempt. Please check your model settings."
+            )
+        })?;
+
+        engine.transcribe(audio, settings)
+    }
+
+    fn engine_supports_prompt(&self) -> bool {
+        let engine = self.engine.lock().unwrap();
+        engine
+            .as_ref()
+            .is_some_and(|engine| engine.supports_prompt())
+    }
+
+    /// Applies custom word correction and inverse text normalization to raw
+    /// engine output and handles the immediate-unload setting.
+    fn finalize_transcription(
+        &self,
+        transcription: Transcription,
+        settings: &AppSettings,
+    ) -> Transcription {
+        let transcription =
+            correct_transcription(transcription, settings, self.engine_supports_prompt());
+
+        // Check if we should immediately unload the model after transcription
+        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately {
+            info!("Immediately unloading model after transcription");
+            if let Err(e) = self.unload_model() {
+                error!("Failed to immediately unload model: {}", e);
+            }
+        }
+
+        transcription
+    }
+}
+
+/// Applies custom word correction and inverse text normalization to raw
+/// engine output. `engine_supports_prompt` tells whether the custom words
+/// already biased the engine.
+pub fn correct_transcription(
+    transcription: Transcription,
+    settings: &AppSettings,
+    engine_supports_prompt: bool,
+) -> Transcription {
+    // Words the engine could not be biased towards are still corrected
+    let use_correction = settings.custom_words_mode.corrects() || !engine_supports_prompt;
+    // The language of the text, which picks the phonetic and number rules
+    let language = if settings.translate_to_english {
+        "en"
+    } else {
+        settings.selected_language.as_str()
+    };
+    let correct = |text: &str| {
+        // Apply word correction if custom words are configured
+        let corrected = if use_correction && !settings.custom_words.is_empty() {
+            apply_custom_words(
+                text,
+                &settings.custom_words,
+                settings.word_correction_threshold,
+                language,
+            )
+        } else {
+            text.to_string()
+        };
+        // Numbers are rewritten after correction so custom words can
+        // still match their spoken form
+        let normalized = if settings.inverse_text_normalization {
+            inverse_normalize(&corrected, language)
+        } else {
+            corrected
+        };
+        normalized.trim().to_string()
+    };
+
+    let final_result = correct(&transcription.text);
+    let segments = transcription
+        .segments
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            text: correct(&segment.text),
+            ..segment
+        })
+        .collect();
+
+    if final_result.is_empty() {
+        info!("Transcription result is empty");
+    } else {
+        info!("Transcription result: {}", final_result);
+    }
+
+    Transcription {
+        text: final_result,
+        segments,
+    }
+}
+
+impl Drop for TranscriptionManager {
+    fn drop(&mut self) {
+        debug!("Shutting down TranscriptionManager");
+
+        // Signal the watcher thread to shutdown
+        self.shutdown_signal.store(true, Ordering::Relaxed);
+
+        // Wait for the thread to finish gracefully
+        if let Some(handle) = self.watcher_handle.lock().unwrap().take() {
+            if let Err(e) = handle.join() {
+                warn!("Failed to join idle watcher thread: {:?}", e);
+            } else {
+                debug!("Idle watcher thread joined successfully");
+            }
+        }
+    }
+}
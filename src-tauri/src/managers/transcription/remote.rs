@@ -0,0 +1,379 @@
+use super::engine::{transcription_prompt, whisper_language, SpeechEngine};
+use super::{TranscriptSegment, TranscriptWord, Transcription};
+use crate::audio_toolkit::encode_wav;
+use crate::settings::AppSettings;
+use anyhow::{anyhow, Result};
+use log::debug;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use std::thread;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Deserialize)]
+struct RemoteResponse {
+    text: String,
+    #[serde(default)]
+    segments: Option<Vec<RemoteSegment>>,
+    #[serde(default)]
+    words: Option<Vec<RemoteWord>>,
+}
+
+#[derive(Deserialize)]
+struct RemoteSegment {
+    start: f32,
+    end: f32,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct RemoteWord {
+    start: f32,
+    end: f32,
+    word: String,
+}
+
+struct RemoteRequest {
+    url: String,
+    api_key: String,
+    model: String,
+    language: Option<String>,
+    prompt: Option<String>,
+    audio: Vec<u8>,
+}
+
+/// Sends audio to an OpenAI-compatible `/audio/transcriptions` endpoint. The
+/// provider, API key and model are read from the settings on every request,
+/// so changing them needs no reload.
+#[derive(Default)]
+pub struct RemoteEngine;
+
+impl RemoteEngine {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SpeechEngine for RemoteEngine {
+    fn transcribe(&mut self, audio: Vec<f32>, settings: &AppSettings) -> Result<Transcription> {
+        let provider = settings
+            .active_remote_transcription_provider()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Remote transcription provider '{}' not found",
+                    settings.remote_transcription_provider_id
+                )
+            })?;
+        let model = settings
+            .remote_transcription_models
+            .get(&provider.id)
+            .cloned()
+            .unwrap_or_default();
+        if model.trim().is_empty() {
+            return Err(anyhow!(
+                "No remote transcription model configured for {}",
+                provider.label
+            ));
+        }
+
+        // The translations endpoint always outputs English and takes no language
+        let (endpoint, language) = if settings.translate_to_english {
+            ("audio/translations", None)
+        } else {
+            (
+                "audio/transcriptions",
+                whisper_language(&settings.selected_language),
+            )
+        };
+
+        let request = RemoteRequest {
+            url: format!("{}/{}", provider.base_url.trim_end_matches('/'), endpoint),
+            api_key: settings
+                .remote_transcription_api_keys
+                .get(&provider.id)
+                .cloned()
+                .unwrap_or_default(),
+            model,
+            language,
+            prompt: transcription_prompt(settings),
+            audio: encode_wav(&audio)?,
+        };
+        debug!(
+            "Sending {} bytes of audio to {} ({})",
+            request.audio.len(),
+            request.url,
+            request.model
+        );
+
+        // The manager is called from plain threads as well as async tasks, so
+        // the request gets its own thread and runtime rather than blocking on
+        // the app's.
+        thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?
+                .block_on(send(request))
+        })
+        .join()
+        .map_err(|_| anyhow!("Remote transcription thread panicked"))?
+    }
+
+    fn supports_prompt(&self) -> bool {
+        true
+    }
+}
+
+async fn send(request: RemoteRequest) -> Result<Transcription> {
+    let file = Part::bytes(request.audio)
+        .file_name("audio.wav")
+        .mime_str("audio/wav")?;
+    let mut form = Form::new()
+        .part("file", file)
+        .text("model", request.model)
+        .text("response_format", "verbose_json")
+        .text("timestamp_granularities[]", "segment");
+    if let Some(language) = request.language {
+        form = form.text("language", language);
+    }
+    if let Some(prompt) = request.prompt {
+        form = form.text("prompt", prompt);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+    let mut builder = client.post(&request.url).multipart(form);
+    if !request.api_key.is_empty() {
+        builder = builder.bearer_auth(&request.api_key);
+    }
+
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| anyhow!("Remote transcription request failed: {}", e))?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!(
+            "Remote transcription failed ({}): {}",
+            status,
+            error_message(&body)
+        ));
+    }
+
+    let response: RemoteResponse = serde_json::from_str(&body)
+        .map_err(|e| anyhow!("Unexpected remote transcription response: {}", e))?;
+    Ok(to_transcription(response))
+}
+
+/// The `error.message` of an OpenAI-style error body, or the body itself
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| value["error"]["message"].as_str().map(String::from))
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+fn to_transcription(response: RemoteResponse) -> Transcription {
+    let mut words: Vec<TranscriptWord> = response
+        .words
+        .unwrap_or_default()
+        .into_iter()
+        .map(|word| TranscriptWord {
+            start: word.start,
+            end: word.end,
+            text: word.word.trim().to_string(),
+            confidence: None,
+        })
+        .collect();
+
+    let segments = response
+        .segments
+        .unwrap_or_default()
+        .into_iter()
+        .map(|segment| {
+            // Attach the words that fall inside each segment
+            let split = words
+                .iter()
+                .position(|word| word.start >= segment.end)
+                .unwrap_or(words.len());
+            TranscriptSegment {
+                start: segment.start,
+                end: segment.end,
+                text: segment.text.trim().to_string(),
+                words: words.drain(..split).collect(),
+            }
+        })
+        .filter(|segment| !segment.text.is_empty())
+        .collect();
+
+    Transcription {
+        text: response.text,
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Answers a single HTTP request with `status` and `body`, and returns
+    /// the raw request it received
+    fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut payload = vec![0; content_length];
+            reader.read_exact(&mut payload).unwrap();
+            request.push_str(&String::from_utf8_lossy(&payload));
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+        (url, server)
+    }
+
+    fn request(url: String) -> RemoteRequest {
+        RemoteRequest {
+            url: format!("{}/v1/audio/transcriptions", url),
+            api_key: "test-key".to_string(),
+            model: "whisper-large-v3".to_string(),
+            language: Some("de".to_string()),
+            prompt: None,
+            audio: vec![0; 16],
+        }
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_send_posts_multipart_request() {
+        let (url, server) = serve_once(
+            "200 OK",
+            r#"{"text": " Hallo Welt", "segments": [{"start": 0.0, "end": 1.2, "text": " Hallo Welt"}]}"#,
+        );
+
+        let transcription = block_on(send(request(url))).unwrap();
+        assert_eq!(transcription.text, " Hallo Welt");
+        assert_eq!(transcription.segments.len(), 1);
+        assert_eq!(transcription.segments[0].text, "Hallo Welt");
+
+        let received = server.join().unwrap();
+        assert!(received.starts_with("POST /v1/audio/transcriptions HTTP/1.1\r\n"));
+        assert!(received
+            .to_ascii_lowercase()
+            .contains("authorization: bearer test-key\r\n"));
+        assert!(received.contains("name=\"model\"\r\n\r\nwhisper-large-v3\r\n"));
+        assert!(received.contains("name=\"language\"\r\n\r\nde\r\n"));
+        assert!(received.contains("name=\"response_format\"\r\n\r\nverbose_json\r\n"));
+        assert!(received.contains("filename=\"audio.wav\""));
+        assert!(!received.contains("name=\"prompt\""));
+    }
+
+    #[test]
+    fn test_send_reports_error_message() {
+        let (url, server) = serve_once(
+            "401 Unauthorized",
+            r#"{"error": {"message": "Invalid API key", "type": "invalid_request_error"}}"#,
+        );
+
+        let error = block_on(send(request(url))).unwrap_err().to_string();
+        server.join().unwrap();
+        assert!(error.contains("401"));
+        assert!(error.ends_with("Invalid API key"));
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            error_message(r#"{"error": {"message": "Rate limit reached"}}"#),
+            "Rate limit reached"
+        );
+        assert_eq!(error_message("  Bad Gateway\n"), "Bad Gateway");
+        assert_eq!(
+            error_message(r#"{"detail": "nope"}"#),
+            r#"{"detail": "nope"}"#
+        );
+        assert_eq!(error_message(""), "");
+    }
+
+    #[test]
+    fn test_to_transcription_attaches_words_to_segments() {
+        let response: RemoteResponse = serde_json::from_str(
+            r#"{
+                "text": "Hello there. General Kenobi.",
+                "segments": [
+                    {"start": 0.0, "end": 1.0, "text": " Hello there."},
+                    {"start": 1.0, "end": 1.5, "text": "  "},
+                    {"start": 1.5, "end": 3.0, "text": " General Kenobi."}
+                ],
+                "words": [
+                    {"start": 0.0, "end": 0.4, "word": " Hello"},
+                    {"start": 0.5, "end": 0.9, "word": " there."},
+                    {"start": 1.6, "end": 2.1, "word": " General"},
+                    {"start": 2.2, "end": 2.9, "word": " Kenobi."}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let transcription = to_transcription(response);
+        assert_eq!(transcription.text, "Hello there. General Kenobi.");
+        assert_eq!(transcription.segments.len(), 2);
+
+        let first = &transcription.segments[0];
+        assert_eq!(first.text, "Hello there.");
+        let words: Vec<_> = first.words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, ["Hello", "there."]);
+
+        let second = &transcription.segments[1];
+        assert_eq!((second.start, second.end), (1.5, 3.0));
+        let words: Vec<_> = second.words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, ["General", "Kenobi."]);
+        assert!(second.words.iter().all(|w| w.confidence.is_none()));
+    }
+
+    #[test]
+    fn test_to_transcription_without_segments() {
+        let response: RemoteResponse = serde_json::from_str(r#"{"text": "Just text"}"#).unwrap();
+
+        let transcription = to_transcription(response);
+        assert_eq!(transcription.text, "Just text");
+        assert!(transcription.segments.is_empty());
+    }
+}
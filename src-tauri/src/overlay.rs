@@ -278,6 +278,15 @@ pub fn hide_recording_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Sends the text transcribed so far while a streaming transcription is running
+pub fn emit_partial_transcript(app_handle: &AppHandle, text: &str) {
+    let _ = app_handle.emit("partial-transcript", text);
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("partial-transcript", text);
+    }
+}
+
 pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
     // emit levels to main app
     let _ = app_handle.emit("mic-level", levels);
@@ -1,3 +1,4 @@
+use crate::audio_toolkit::ReplacementRule;
 use log::{debug, warn};
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -76,6 +77,13 @@ impl From<LogLevel> for tauri_plugin_log::LogLevel {
     }
 }
 
+/// Prefix for user-created bindings that run the transcribe action with their own options
+pub const CUSTOM_TRANSCRIBE_BINDING_PREFIX: &str = "transcribe_";
+
+/// Pauses and resumes hands-free listening. Only registered while hands-free
+/// mode is on.
+pub const HANDS_FREE_BINDING_ID: &str = "hands_free";
+
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
 pub struct ShortcutBinding {
     pub id: String,
@@ -83,6 +91,40 @@ pub struct ShortcutBinding {
     pub description: String,
     pub default_binding: String,
     pub current_binding: String,
+    #[serde(default)]
+    pub transcribe_options: Option<TranscribeOptions>,
+}
+
+/// Where a binding delivers its transcription
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputTarget {
+    /// Paste into the focused application using the configured paste method
+    Paste,
+    /// Only copy to the clipboard
+    Clipboard,
+    /// Only keep it in history
+    History,
+}
+
+/// Per-binding overrides for the transcribe action. `None` fields fall back
+/// to the global settings (or the focused app's profile).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Type)]
+pub struct TranscribeOptions {
+    #[serde(default)]
+    pub post_process_prompt_id: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub translate_to_english: Option<bool>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub output_target: Option<OutputTarget>,
+    #[serde(default)]
+    pub custom_words_mode: Option<CustomWordsMode>,
+    #[serde(default)]
+    pub inverse_text_normalization: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
@@ -92,6 +134,39 @@ pub struct LLMPrompt {
     pub prompt: String,
 }
 
+/// Settings overrides applied while a matching application is focused.
+/// `None` fields fall back to the global settings.
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct AppProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Process names, X11 window classes or macOS bundle identifiers, compared case-insensitively
+    pub apps: Vec<String>,
+    #[serde(default)]
+    pub selected_model: Option<String>,
+    #[serde(default)]
+    pub selected_language: Option<String>,
+    #[serde(default)]
+    pub post_process_selected_prompt_id: Option<String>,
+    #[serde(default)]
+    pub paste_method: Option<PasteMethod>,
+    #[serde(default)]
+    pub custom_words: Option<Vec<String>>,
+}
+
+impl AppProfile {
+    pub fn matches(&self, identifiers: &[String]) -> bool {
+        self.enabled
+            && self.apps.iter().any(|app| {
+                identifiers
+                    .iter()
+                    .any(|identifier| identifier.eq_ignore_ascii_case(app.trim()))
+            })
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
 pub struct PostProcessProvider {
     pub id: String,
@@ -141,6 +216,18 @@ pub enum ClipboardHandling {
     CopyToClipboard,
 }
 
+/// How custom words are used
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomWordsMode {
+    /// Replace similar words in the transcript afterwards
+    Correction,
+    /// Prompt the engine with the words so it favours them while transcribing.
+    /// Engines that take no prompt fall back to correction.
+    Biasing,
+    Both,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum RecordingRetentionPeriod {
@@ -173,6 +260,22 @@ impl Default for ClipboardHandling {
     }
 }
 
+impl Default for CustomWordsMode {
+    fn default() -> Self {
+        CustomWordsMode::Correction
+    }
+}
+
+impl CustomWordsMode {
+    pub fn biases(self) -> bool {
+        matches!(self, CustomWordsMode::Biasing | CustomWordsMode::Both)
+    }
+
+    pub fn corrects(self) -> bool {
+        matches!(self, CustomWordsMode::Correction | CustomWordsMode::Both)
+    }
+}
+
 impl ModelUnloadTimeout {
     pub fn to_minutes(self) -> Option<u64> {
         match self {
@@ -265,6 +368,45 @@ pub struct AppSettings {
     pub model_unload_timeout: ModelUnloadTimeout,
     #[serde(default = "default_word_correction_threshold")]
     pub word_correction_threshold: f64,
+    #[serde(default)]
+    pub custom_words_mode: CustomWordsMode,
+    /// Free-form text, such as the topic or names, given to engines that take
+    /// a prompt
+    #[serde(default)]
+    pub transcription_context_prompt: String,
+    /// Interpret spoken punctuation and formatting commands such as "comma"
+    /// or "new paragraph"
+    #[serde(default)]
+    pub voice_commands_enabled: bool,
+    /// Command set language, or "auto" to follow the transcription language
+    #[serde(default = "default_voice_commands_language")]
+    pub voice_commands_language: String,
+    /// Ids of commands to leave as plain words
+    #[serde(default)]
+    pub voice_commands_disabled: Vec<String>,
+    /// Write spoken numbers, dates, times, currency and units in written form
+    #[serde(default)]
+    pub inverse_text_normalization: bool,
+    /// Listen continuously and transcribe every utterance without a shortcut
+    #[serde(default)]
+    pub hands_free_enabled: bool,
+    /// Silence that ends a hands-free utterance
+    #[serde(default = "default_hands_free_end_silence_ms")]
+    pub hands_free_end_silence_ms: u32,
+    /// Hands-free utterances shorter than this are ignored
+    #[serde(default = "default_hands_free_min_utterance_ms")]
+    pub hands_free_min_utterance_ms: u32,
+    /// Stop recording after this many seconds without speech once speech was
+    /// heard, 0 to never
+    #[serde(default)]
+    pub auto_stop_silence_secs: u32,
+    /// Stop recording after this many seconds if no speech has been heard at
+    /// all, 0 to never
+    #[serde(default = "default_no_speech_timeout_secs")]
+    pub no_speech_timeout_secs: u32,
+    /// Stop recording after this many seconds, 0 for no limit
+    #[serde(default)]
+    pub max_recording_secs: u32,
     #[serde(default = "default_history_limit")]
     pub history_limit: usize,
     #[serde(default = "default_recording_retention_period")]
@@ -293,6 +435,45 @@ pub struct AppSettings {
     pub append_trailing_space: bool,
     #[serde(default = "default_app_language")]
     pub app_language: String,
+    #[serde(default)]
+    pub streaming_transcription: bool,
+    #[serde(default)]
+    pub app_profiles: Vec<AppProfile>,
+    #[serde(default)]
+    pub api_server_enabled: bool,
+    #[serde(default = "default_api_server_bind_address")]
+    pub api_server_bind_address: String,
+    #[serde(default)]
+    pub api_server_token: Option<String>,
+    #[serde(default)]
+    pub control_socket_enabled: bool,
+    #[serde(default = "default_remote_transcription_provider_id")]
+    pub remote_transcription_provider_id: String,
+    #[serde(default = "default_remote_transcription_providers")]
+    pub remote_transcription_providers: Vec<PostProcessProvider>,
+    #[serde(default = "default_remote_transcription_api_keys")]
+    pub remote_transcription_api_keys: HashMap<String, String>,
+    #[serde(default = "default_remote_transcription_models")]
+    pub remote_transcription_models: HashMap<String, String>,
+    /// Signed model catalog to refresh from; empty keeps the bundled catalog
+    #[serde(default = "default_model_catalog_url")]
+    pub model_catalog_url: String,
+    #[serde(default = "default_model_download_concurrency")]
+    pub model_download_concurrency: u32,
+    /// Bandwidth shared by all model downloads; 0 means unlimited
+    #[serde(default)]
+    pub model_download_max_kib_per_sec: u32,
+    /// Base URLs tried in order before a model's own download URL
+    #[serde(default)]
+    pub model_download_mirrors: Vec<String>,
+    /// How long transcribing the benchmark clip may take for a model to be
+    /// recommended
+    #[serde(default = "default_benchmark_target_latency_ms")]
+    pub benchmark_target_latency_ms: u64,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 fn default_model() -> String {
@@ -334,6 +515,22 @@ fn default_debug_mode() -> bool {
     false
 }
 
+fn default_api_server_bind_address() -> String {
+    "127.0.0.1:8178".to_string()
+}
+
+fn default_model_catalog_url() -> String {
+    String::new()
+}
+
+fn default_model_download_concurrency() -> u32 {
+    2
+}
+
+fn default_benchmark_target_latency_ms() -> u64 {
+    2000
+}
+
 fn default_log_level() -> LogLevel {
     LogLevel::Debug
 }
@@ -342,6 +539,22 @@ fn default_word_correction_threshold() -> f64 {
     0.18
 }
 
+fn default_voice_commands_language() -> String {
+    "auto".to_string()
+}
+
+fn default_hands_free_end_silence_ms() -> u32 {
+    800
+}
+
+fn default_hands_free_min_utterance_ms() -> u32 {
+    400
+}
+
+fn default_no_speech_timeout_secs() -> u32 {
+    60
+}
+
 fn default_history_limit() -> usize {
     5
 }
@@ -446,6 +659,58 @@ fn default_post_process_models() -> HashMap<String, String> {
     map
 }
 
+fn default_remote_transcription_provider_id() -> String {
+    "openai".to_string()
+}
+
+/// OpenAI-compatible speech-to-text services used by the remote model
+fn default_remote_transcription_providers() -> Vec<PostProcessProvider> {
+    vec![
+        PostProcessProvider {
+            id: "openai".to_string(),
+            label: "OpenAI".to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            allow_base_url_edit: false,
+            models_endpoint: None,
+        },
+        PostProcessProvider {
+            id: "groq".to_string(),
+            label: "Groq".to_string(),
+            base_url: "https://api.groq.com/openai/v1".to_string(),
+            allow_base_url_edit: false,
+            models_endpoint: None,
+        },
+        PostProcessProvider {
+            id: "custom".to_string(),
+            label: "Custom".to_string(),
+            base_url: "http://localhost:8000/v1".to_string(),
+            allow_base_url_edit: true,
+            models_endpoint: None,
+        },
+    ]
+}
+
+fn default_remote_transcription_api_keys() -> HashMap<String, String> {
+    default_remote_transcription_providers()
+        .into_iter()
+        .map(|provider| (provider.id, String::new()))
+        .collect()
+}
+
+fn default_remote_transcription_models() -> HashMap<String, String> {
+    default_remote_transcription_providers()
+        .into_iter()
+        .map(|provider| {
+            let model = match provider.id.as_str() {
+                "openai" => "whisper-1",
+                "groq" => "whisper-large-v3-turbo",
+                _ => "",
+            };
+            (provider.id, model.to_string())
+        })
+        .collect()
+}
+
 fn default_post_process_prompts() -> Vec<LLMPrompt> {
     vec![LLMPrompt {
         id: "default_improve_transcriptions".to_string(),
@@ -494,6 +759,9 @@ fn ensure_post_process_defaults(settings: &mut AppSettings) -> bool {
 }
 
 pub const SETTINGS_STORE_PATH: &str = "settings_store.json";
+/// Replacement rules are kept apart from the settings so they can be shared
+/// and imported on their own
+pub const REPLACEMENTS_STORE_PATH: &str = "replacements_store.json";
 
 pub fn get_default_settings() -> AppSettings {
     #[cfg(target_os = "windows")]
@@ -504,6 +772,7 @@ pub fn get_default_settings() -> AppSettings {
     let default_shortcut = "ctrl+space";
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     let default_shortcut = "alt+space";
+    let hands_free_shortcut = default_shortcut.replacen('+', "+shift+", 1);
 
     let mut bindings = HashMap::new();
     bindings.insert(
@@ -514,6 +783,7 @@ pub fn get_default_settings() -> AppSettings {
             description: "Converts your speech into text.".to_string(),
             default_binding: default_shortcut.to_string(),
             current_binding: default_shortcut.to_string(),
+            transcribe_options: None,
         },
     );
     bindings.insert(
@@ -524,6 +794,18 @@ pub fn get_default_settings() -> AppSettings {
             description: "Cancels the current recording.".to_string(),
             default_binding: "escape".to_string(),
             current_binding: "escape".to_string(),
+            transcribe_options: None,
+        },
+    );
+    bindings.insert(
+        HANDS_FREE_BINDING_ID.to_string(),
+        ShortcutBinding {
+            id: HANDS_FREE_BINDING_ID.to_string(),
+            name: "Pause Hands-free".to_string(),
+            description: "Pauses or resumes hands-free listening.".to_string(),
+            default_binding: hands_free_shortcut.clone(),
+            current_binding: hands_free_shortcut,
+            transcribe_options: None,
         },
     );
 
@@ -549,6 +831,18 @@ pub fn get_default_settings() -> AppSettings {
         custom_words: Vec::new(),
         model_unload_timeout: ModelUnloadTimeout::Never,
         word_correction_threshold: default_word_correction_threshold(),
+        custom_words_mode: CustomWordsMode::default(),
+        transcription_context_prompt: String::new(),
+        voice_commands_enabled: false,
+        voice_commands_language: default_voice_commands_language(),
+        voice_commands_disabled: Vec::new(),
+        inverse_text_normalization: false,
+        hands_free_enabled: false,
+        hands_free_end_silence_ms: default_hands_free_end_silence_ms(),
+        hands_free_min_utterance_ms: default_hands_free_min_utterance_ms(),
+        auto_stop_silence_secs: 0,
+        no_speech_timeout_secs: default_no_speech_timeout_secs(),
+        max_recording_secs: 0,
         history_limit: default_history_limit(),
         recording_retention_period: default_recording_retention_period(),
         paste_method: PasteMethod::default(),
@@ -563,6 +857,21 @@ pub fn get_default_settings() -> AppSettings {
         mute_while_recording: false,
         append_trailing_space: false,
         app_language: default_app_language(),
+        streaming_transcription: false,
+        app_profiles: Vec::new(),
+        api_server_enabled: false,
+        api_server_bind_address: default_api_server_bind_address(),
+        api_server_token: None,
+        control_socket_enabled: false,
+        remote_transcription_provider_id: default_remote_transcription_provider_id(),
+        remote_transcription_providers: default_remote_transcription_providers(),
+        remote_transcription_api_keys: default_remote_transcription_api_keys(),
+        remote_transcription_models: default_remote_transcription_models(),
+        model_catalog_url: default_model_catalog_url(),
+        model_download_concurrency: default_model_download_concurrency(),
+        model_download_max_kib_per_sec: 0,
+        model_download_mirrors: Vec::new(),
+        benchmark_target_latency_ms: default_benchmark_target_latency_ms(),
     }
 }
 
@@ -579,6 +888,89 @@ impl AppSettings {
             .find(|provider| provider.id == provider_id)
     }
 
+    /// The first enabled profile matching any of the focused app's identifiers
+    pub fn find_app_profile(&self, identifiers: &[String]) -> Option<&AppProfile> {
+        self.app_profiles
+            .iter()
+            .find(|profile| profile.matches(identifiers))
+    }
+
+    /// A copy of these settings with the profile's overrides applied
+    pub fn with_profile(&self, profile: &AppProfile) -> AppSettings {
+        let mut settings = self.clone();
+        if let Some(model) = &profile.selected_model {
+            settings.selected_model = model.clone();
+        }
+        if let Some(language) = &profile.selected_language {
+            settings.selected_language = language.clone();
+        }
+        if let Some(prompt_id) = &profile.post_process_selected_prompt_id {
+            settings.post_process_selected_prompt_id = Some(prompt_id.clone());
+        }
+        if let Some(paste_method) = profile.paste_method {
+            settings.paste_method = paste_method;
+        }
+        if let Some(custom_words) = &profile.custom_words {
+            settings.custom_words = custom_words.clone();
+        }
+        settings
+    }
+
+    /// A copy of these settings with a binding's transcribe options applied
+    pub fn with_transcribe_options(&self, options: &TranscribeOptions) -> AppSettings {
+        let mut settings = self.clone();
+        if let Some(prompt_id) = &options.post_process_prompt_id {
+            settings.post_process_enabled = true;
+            settings.post_process_selected_prompt_id = Some(prompt_id.clone());
+        }
+        if let Some(language) = &options.language {
+            settings.selected_language = language.clone();
+        }
+        if let Some(translate) = options.translate_to_english {
+            settings.translate_to_english = translate;
+        }
+        if let Some(model) = &options.model {
+            settings.selected_model = model.clone();
+        }
+        if let Some(mode) = options.custom_words_mode {
+            settings.custom_words_mode = mode;
+        }
+        if let Some(enabled) = options.inverse_text_normalization {
+            settings.inverse_text_normalization = enabled;
+        }
+        match options.output_target {
+            Some(OutputTarget::Clipboard) => {
+                settings.paste_method = PasteMethod::None;
+                settings.clipboard_handling = ClipboardHandling::CopyToClipboard;
+            }
+            Some(OutputTarget::History) => {
+                settings.paste_method = PasteMethod::None;
+                settings.clipboard_handling = ClipboardHandling::DontModify;
+            }
+            Some(OutputTarget::Paste) | None => {}
+        }
+        settings
+    }
+
+    pub fn active_remote_transcription_provider(&self) -> Option<&PostProcessProvider> {
+        self.remote_transcription_provider(&self.remote_transcription_provider_id)
+    }
+
+    pub fn remote_transcription_provider(&self, provider_id: &str) -> Option<&PostProcessProvider> {
+        self.remote_transcription_providers
+            .iter()
+            .find(|provider| provider.id == provider_id)
+    }
+
+    pub fn remote_transcription_provider_mut(
+        &mut self,
+        provider_id: &str,
+    ) -> Option<&mut PostProcessProvider> {
+        self.remote_transcription_providers
+            .iter_mut()
+            .find(|provider| provider.id == provider_id)
+    }
+
     pub fn post_process_provider_mut(
         &mut self,
         provider_id: &str,
@@ -672,6 +1064,28 @@ pub fn write_settings(app: &AppHandle, settings: AppSettings) {
     store.set("settings", serde_json::to_value(&settings).unwrap());
 }
 
+pub fn get_replacement_rules(app: &AppHandle) -> Vec<ReplacementRule> {
+    let store = app
+        .store(REPLACEMENTS_STORE_PATH)
+        .expect("Failed to initialize store");
+
+    match store.get("rules") {
+        Some(rules_value) => serde_json::from_value(rules_value).unwrap_or_else(|e| {
+            warn!("Failed to parse replacement rules: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    }
+}
+
+pub fn write_replacement_rules(app: &AppHandle, rules: &[ReplacementRule]) {
+    let store = app
+        .store(REPLACEMENTS_STORE_PATH)
+        .expect("Failed to initialize store");
+
+    store.set("rules", serde_json::to_value(rules).unwrap());
+}
+
 pub fn get_bindings(app: &AppHandle) -> HashMap<String, ShortcutBinding> {
     let settings = get_settings(app);
 
@@ -695,3 +1109,88 @@ pub fn get_recording_retention_period(app: &AppHandle) -> RecordingRetentionPeri
     let settings = get_settings(app);
     settings.recording_retention_period
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(apps: &[&str]) -> AppProfile {
+        AppProfile {
+            id: "profile_1".to_string(),
+            name: "Terminal".to_string(),
+            enabled: true,
+            apps: apps.iter().map(|app| app.to_string()).collect(),
+            selected_model: None,
+            selected_language: None,
+            post_process_selected_prompt_id: None,
+            paste_method: None,
+            custom_words: None,
+        }
+    }
+
+    fn identifiers(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_app_profile_matches() {
+        let profile = profile(&["Alacritty", " com.apple.Terminal "]);
+        // Any identifier may match, ignoring case and surrounding whitespace
+        assert!(profile.matches(&identifiers(&["alacritty"])));
+        assert!(profile.matches(&identifiers(&["Terminal", "com.apple.terminal"])));
+        assert!(!profile.matches(&identifiers(&["Code", "com.microsoft.VSCode"])));
+        assert!(!profile.matches(&[]));
+        // Partial names don't match
+        assert!(!profile.matches(&identifiers(&["alacritty-dev"])));
+    }
+
+    #[test]
+    fn test_disabled_app_profile_never_matches() {
+        let mut profile = profile(&["alacritty"]);
+        profile.enabled = false;
+        assert!(!profile.matches(&identifiers(&["alacritty"])));
+    }
+
+    #[test]
+    fn test_find_app_profile_takes_first_match() {
+        let mut settings = get_default_settings();
+        let mut first = profile(&["alacritty"]);
+        first.enabled = false;
+        let mut second = profile(&["alacritty", "kitty"]);
+        second.id = "profile_2".to_string();
+        settings.app_profiles = vec![first, second];
+
+        let found = settings.find_app_profile(&identifiers(&["Alacritty"]));
+        assert_eq!(found.map(|p| p.id.as_str()), Some("profile_2"));
+        assert!(settings
+            .find_app_profile(&identifiers(&["firefox"]))
+            .is_none());
+    }
+
+    #[test]
+    fn test_with_profile_overrides_only_set_fields() {
+        let mut settings = get_default_settings();
+        settings.selected_model = "small".to_string();
+        settings.selected_language = "en".to_string();
+        settings.custom_words = vec!["Handy".to_string()];
+        settings.paste_method = PasteMethod::CtrlV;
+
+        let mut overrides = profile(&["alacritty"]);
+        overrides.selected_language = Some("de".to_string());
+        overrides.paste_method = Some(PasteMethod::ShiftInsert);
+        overrides.custom_words = Some(vec!["kubectl".to_string()]);
+        let applied = settings.with_profile(&overrides);
+
+        assert_eq!(applied.selected_language, "de");
+        assert_eq!(applied.paste_method, PasteMethod::ShiftInsert);
+        assert_eq!(applied.custom_words, vec!["kubectl".to_string()]);
+        // Unset fields keep the global settings
+        assert_eq!(applied.selected_model, "small");
+        assert_eq!(
+            applied.post_process_selected_prompt_id,
+            settings.post_process_selected_prompt_id
+        );
+        // The global settings are left untouched
+        assert_eq!(settings.selected_language, "en");
+    }
+}
@@ -2,16 +2,22 @@ use log::{error, warn};
 use serde::Serialize;
 use specta::Type;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-use crate::actions::ACTION_MAP;
+use crate::actions::action_for_binding;
+use crate::audio_toolkit::voice_commands::SUPPORTED_LANGUAGES;
+use crate::managers::api_server::ApiServerManager;
 use crate::managers::audio::AudioRecordingManager;
-use crate::settings::ShortcutBinding;
+use crate::managers::control_socket::ControlSocketManager;
+use crate::managers::model::ModelManager;
 use crate::settings::{
-    self, get_settings, ClipboardHandling, LLMPrompt, OverlayPosition, PasteMethod, SoundTheme,
-    APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
+    self, get_settings, AppProfile, ClipboardHandling, CustomWordsMode, LLMPrompt, OverlayPosition,
+    PasteMethod, SoundTheme, APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
+};
+use crate::settings::{
+    ShortcutBinding, TranscribeOptions, CUSTOM_TRANSCRIBE_BINDING_PREFIX, HANDS_FREE_BINDING_ID,
 };
 use crate::tray;
 use crate::ManagedToggleState;
@@ -25,6 +31,9 @@ pub fn init_shortcuts(app: &AppHandle) {
         if id == "cancel" {
             continue; // Skip cancel shortcut, it will be registered dynamically
         }
+        if id == HANDS_FREE_BINDING_ID && !user_settings.hands_free_enabled {
+            continue; // Registered when hands-free mode is turned on
+        }
         let binding = user_settings
             .bindings
             .get(&id)
@@ -35,6 +44,16 @@ pub fn init_shortcuts(app: &AppHandle) {
             error!("Failed to register shortcut {} during init: {}", id, e);
         }
     }
+
+    // Register user-created transcribe bindings
+    for (id, binding) in user_settings.bindings {
+        if !id.starts_with(CUSTOM_TRANSCRIBE_BINDING_PREFIX) {
+            continue;
+        }
+        if let Err(e) = register_shortcut(app, binding) {
+            error!("Failed to register shortcut {} during init: {}", id, e);
+        }
+    }
 }
 
 #[derive(Serialize, Type)]
@@ -66,9 +85,10 @@ pub fn change_binding(
             });
         }
     };
-    // If this is the cancel binding, just update the settings and return
-    // It's managed dynamically, so we don't register/unregister here
-    if id == "cancel" {
+    // If this is the cancel binding, or the hands-free binding while hands-free
+    // mode is off, just update the settings and return
+    // They're managed dynamically, so we don't register/unregister here
+    if id == "cancel" || (id == HANDS_FREE_BINDING_ID && !settings.hands_free_enabled) {
         if let Some(mut b) = settings.bindings.get(&id).cloned() {
             b.current_binding = binding;
             settings.bindings.insert(id.clone(), b.clone());
@@ -122,6 +142,94 @@ pub fn change_binding(
     })
 }
 
+/// Creates a new shortcut that runs the transcribe action with its own options
+#[tauri::command]
+#[specta::specta]
+pub fn add_transcribe_binding(
+    app: AppHandle,
+    name: String,
+    binding: String,
+    options: TranscribeOptions,
+) -> Result<BindingResponse, String> {
+    let mut settings = settings::get_settings(&app);
+
+    let id = format!(
+        "{}{}",
+        CUSTOM_TRANSCRIBE_BINDING_PREFIX,
+        chrono::Utc::now().timestamp_millis()
+    );
+    let new_binding = ShortcutBinding {
+        id: id.clone(),
+        description: format!("Transcribes with the options of '{}'.", name),
+        name,
+        default_binding: binding.clone(),
+        current_binding: binding,
+        transcribe_options: Some(options),
+    };
+
+    if let Err(e) = register_shortcut(&app, new_binding.clone()) {
+        warn!("add_transcribe_binding error: {}", e);
+        return Ok(BindingResponse {
+            success: false,
+            binding: None,
+            error: Some(e),
+        });
+    }
+
+    settings.bindings.insert(id, new_binding.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(BindingResponse {
+        success: true,
+        binding: Some(new_binding),
+        error: None,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_transcribe_binding(
+    app: AppHandle,
+    id: String,
+    name: String,
+    options: TranscribeOptions,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    match settings.bindings.get_mut(&id) {
+        Some(binding) if id.starts_with(CUSTOM_TRANSCRIBE_BINDING_PREFIX) => {
+            binding.description = format!("Transcribes with the options of '{}'.", name);
+            binding.name = name;
+            binding.transcribe_options = Some(options);
+        }
+        _ => return Err(format!("Transcribe binding with id '{}' not found", id)),
+    }
+
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn remove_transcribe_binding(app: AppHandle, id: String) -> Result<(), String> {
+    if !id.starts_with(CUSTOM_TRANSCRIBE_BINDING_PREFIX) {
+        return Err(format!("Binding '{}' cannot be removed", id));
+    }
+
+    let mut settings = settings::get_settings(&app);
+    let binding = settings
+        .bindings
+        .remove(&id)
+        .ok_or_else(|| format!("Transcribe binding with id '{}' not found", id))?;
+
+    if let Err(e) = unregister_shortcut(&app, binding) {
+        warn!("remove_transcribe_binding: {}", e);
+    }
+
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn reset_binding(app: AppHandle, id: String) -> Result<BindingResponse, String> {
@@ -324,6 +432,172 @@ pub fn change_word_correction_threshold_setting(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_custom_words_mode_setting(app: AppHandle, mode: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let parsed = match mode.as_str() {
+        "correction" => CustomWordsMode::Correction,
+        "biasing" => CustomWordsMode::Biasing,
+        "both" => CustomWordsMode::Both,
+        other => {
+            warn!(
+                "Invalid custom words mode '{}', defaulting to correction",
+                other
+            );
+            CustomWordsMode::Correction
+        }
+    };
+    settings.custom_words_mode = parsed;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_transcription_context_prompt_setting(
+    app: AppHandle,
+    prompt: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.transcription_context_prompt = prompt.trim().to_string();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_inverse_text_normalization_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.inverse_text_normalization = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_voice_commands_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.voice_commands_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_voice_commands_language_setting(
+    app: AppHandle,
+    language: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let language = if language == "auto" || SUPPORTED_LANGUAGES.contains(&language.as_str()) {
+        language
+    } else {
+        warn!(
+            "Unsupported voice commands language '{}', defaulting to auto",
+            language
+        );
+        "auto".to_string()
+    };
+    settings.voice_commands_language = language;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_hands_free_enabled_setting(
+    app: AppHandle,
+    audio_manager: State<'_, Arc<AudioRecordingManager>>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.hands_free_enabled = enabled;
+    let binding = settings.bindings.get(HANDS_FREE_BINDING_ID).cloned();
+    settings::write_settings(&app, settings);
+
+    // The pause shortcut is only registered while hands-free mode is on
+    if let Some(binding) = binding {
+        let result = if enabled {
+            register_shortcut(&app, binding)
+        } else {
+            unregister_shortcut(&app, binding)
+        };
+        if let Err(e) = result {
+            warn!("Failed to update the hands-free shortcut: {}", e);
+        }
+    }
+
+    audio_manager
+        .set_hands_free(enabled)
+        .map_err(|e| e.to_string())
+}
+
+/// Sets how long a pause ends a hands-free utterance, between 200 ms and 5 s
+#[tauri::command]
+#[specta::specta]
+pub fn change_hands_free_end_silence_setting(
+    app: AppHandle,
+    audio_manager: State<'_, Arc<AudioRecordingManager>>,
+    ms: u32,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.hands_free_end_silence_ms = ms.clamp(200, 5000);
+    settings::write_settings(&app, settings);
+
+    audio_manager.update_listening().map_err(|e| e.to_string())
+}
+
+/// Sets the shortest hands-free utterance that is transcribed, up to 5 s
+#[tauri::command]
+#[specta::specta]
+pub fn change_hands_free_min_utterance_setting(
+    app: AppHandle,
+    audio_manager: State<'_, Arc<AudioRecordingManager>>,
+    ms: u32,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.hands_free_min_utterance_ms = ms.min(5000);
+    settings::write_settings(&app, settings);
+
+    audio_manager.update_listening().map_err(|e| e.to_string())
+}
+
+/// Sets how many seconds without speech stop a recording, up to 5 minutes,
+/// or 0 to never stop on silence
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_stop_silence_setting(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.auto_stop_silence_secs = seconds.min(300);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Sets how many seconds a recording without any speech may run, up to 10
+/// minutes, or 0 to never stop it for that
+#[tauri::command]
+#[specta::specta]
+pub fn change_no_speech_timeout_setting(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.no_speech_timeout_secs = seconds.min(600);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Sets the longest a recording may run, in seconds, or 0 for no limit
+#[tauri::command]
+#[specta::specta]
+pub fn change_max_recording_setting(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.max_recording_secs = seconds;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(), String> {
@@ -455,6 +729,90 @@ pub fn set_post_process_provider(app: AppHandle, provider_id: String) -> Result<
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn set_remote_transcription_provider(
+    app: AppHandle,
+    provider_id: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if settings
+        .remote_transcription_provider(&provider_id)
+        .is_none()
+    {
+        return Err(format!("Provider '{}' not found", provider_id));
+    }
+    settings.remote_transcription_provider_id = provider_id;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_remote_transcription_base_url_setting(
+    app: AppHandle,
+    provider_id: String,
+    base_url: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let provider = settings
+        .remote_transcription_provider_mut(&provider_id)
+        .ok_or_else(|| format!("Provider '{}' not found", provider_id))?;
+
+    if !provider.allow_base_url_edit {
+        return Err(format!(
+            "Provider '{}' does not allow editing the base URL",
+            provider.label
+        ));
+    }
+
+    provider.base_url = base_url;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_remote_transcription_api_key_setting(
+    app: AppHandle,
+    provider_id: String,
+    api_key: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if settings
+        .remote_transcription_provider(&provider_id)
+        .is_none()
+    {
+        return Err(format!("Provider '{}' not found", provider_id));
+    }
+    settings
+        .remote_transcription_api_keys
+        .insert(provider_id, api_key);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_remote_transcription_model_setting(
+    app: AppHandle,
+    provider_id: String,
+    model: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if settings
+        .remote_transcription_provider(&provider_id)
+        .is_none()
+    {
+        return Err(format!("Provider '{}' not found", provider_id));
+    }
+    settings
+        .remote_transcription_models
+        .insert(provider_id, model);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn add_post_process_prompt(
@@ -531,6 +889,67 @@ pub fn delete_post_process_prompt(app: AppHandle, id: String) -> Result<(), Stri
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn add_app_profile(
+    app: AppHandle,
+    name: String,
+    apps: Vec<String>,
+) -> Result<AppProfile, String> {
+    let mut settings = settings::get_settings(&app);
+
+    let profile = AppProfile {
+        id: format!("profile_{}", chrono::Utc::now().timestamp_millis()),
+        name,
+        enabled: true,
+        apps,
+        selected_model: None,
+        selected_language: None,
+        post_process_selected_prompt_id: None,
+        paste_method: None,
+        custom_words: None,
+    };
+
+    settings.app_profiles.push(profile.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(profile)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_app_profile(app: AppHandle, profile: AppProfile) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    if let Some(existing_profile) = settings
+        .app_profiles
+        .iter_mut()
+        .find(|p| p.id == profile.id)
+    {
+        *existing_profile = profile;
+        settings::write_settings(&app, settings);
+        Ok(())
+    } else {
+        Err(format!("Profile with id '{}' not found", profile.id))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_app_profile(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    let original_len = settings.app_profiles.len();
+    settings.app_profiles.retain(|p| p.id != id);
+
+    if settings.app_profiles.len() == original_len {
+        return Err(format!("Profile with id '{}' not found", id));
+    }
+
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn fetch_post_process_models(
@@ -718,6 +1137,184 @@ pub fn change_append_trailing_space_setting(app: AppHandle, enabled: bool) -> Re
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_streaming_transcription_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.streaming_transcription = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn change_api_server_enabled_setting(
+    app: AppHandle,
+    api_server_manager: State<'_, Arc<ApiServerManager>>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.api_server_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    api_server_manager
+        .restart()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn change_api_server_bind_address_setting(
+    app: AppHandle,
+    api_server_manager: State<'_, Arc<ApiServerManager>>,
+    address: String,
+) -> Result<(), String> {
+    if address.parse::<std::net::SocketAddr>().is_err() {
+        return Err(format!(
+            "Invalid address '{}', expected host:port such as 127.0.0.1:8178",
+            address
+        ));
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.api_server_bind_address = address;
+    settings::write_settings(&app, settings);
+
+    api_server_manager
+        .restart()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn change_api_server_token_setting(
+    app: AppHandle,
+    api_server_manager: State<'_, Arc<ApiServerManager>>,
+    token: Option<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.api_server_token = token.filter(|t| !t.trim().is_empty());
+    settings::write_settings(&app, settings);
+
+    api_server_manager
+        .restart()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_control_socket_enabled_setting(
+    app: AppHandle,
+    control_socket_manager: State<'_, Arc<ControlSocketManager>>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.control_socket_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    control_socket_manager.stop();
+    control_socket_manager.start().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn change_model_catalog_url_setting(
+    app: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    url: String,
+) -> Result<(), String> {
+    let url = url.trim().to_string();
+    if !url.is_empty() && !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(format!("Catalog URL must be http(s): {}", url));
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.model_catalog_url = url;
+    settings::write_settings(&app, settings);
+
+    model_manager
+        .refresh_catalog()
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_model_download_concurrency_setting(
+    app: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    concurrency: u32,
+) -> Result<(), String> {
+    if concurrency == 0 {
+        return Err("At least one download must be allowed at a time".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.model_download_concurrency = concurrency;
+    settings::write_settings(&app, settings);
+
+    // Raising the limit starts waiting downloads right away
+    model_manager.start_queued_downloads();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_model_download_max_kib_per_sec_setting(
+    app: AppHandle,
+    max_kib_per_sec: u32,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.model_download_max_kib_per_sec = max_kib_per_sec;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_model_download_mirrors_setting(
+    app: AppHandle,
+    mirrors: Vec<String>,
+) -> Result<(), String> {
+    let mirrors: Vec<String> = mirrors
+        .iter()
+        .map(|mirror| mirror.trim().trim_end_matches('/').to_string())
+        .filter(|mirror| !mirror.is_empty())
+        .collect();
+    if let Some(mirror) = mirrors
+        .iter()
+        .find(|m| !m.starts_with("https://") && !m.starts_with("http://"))
+    {
+        return Err(format!("Mirror URL must be http(s): {}", mirror));
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.model_download_mirrors = mirrors;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_benchmark_target_latency_setting(
+    app: AppHandle,
+    latency_ms: u64,
+) -> Result<(), String> {
+    if latency_ms == 0 {
+        return Err("Target latency must be greater than zero".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.benchmark_target_latency_ms = latency_ms;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_app_language_setting(app: AppHandle, language: String) -> Result<(), String> {
@@ -856,13 +1453,19 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
                 let shortcut_string = scut.into_string();
                 let settings = get_settings(ah);
 
-                if let Some(action) = ACTION_MAP.get(&binding_id_for_closure) {
+                if let Some(action) = action_for_binding(&binding_id_for_closure) {
                     if binding_id_for_closure == "cancel" {
                         let audio_manager = ah.state::<Arc<AudioRecordingManager>>();
                         if audio_manager.is_recording() && event.state == ShortcutState::Pressed {
                             action.start(ah, &binding_id_for_closure, &shortcut_string);
                         }
                         return;
+                    } else if binding_id_for_closure == HANDS_FREE_BINDING_ID {
+                        // Pauses or resumes on every press, whatever the shortcut mode
+                        if event.state == ShortcutState::Pressed {
+                            action.start(ah, &binding_id_for_closure, &shortcut_string);
+                        }
+                        return;
                     } else if settings.push_to_talk {
                         if event.state == ShortcutState::Pressed {
                             action.start(ah, &binding_id_for_closure, &shortcut_string);
@@ -901,14 +1504,17 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
                     }
                 } else {
                     warn!(
-                        "No action defined in ACTION_MAP for shortcut ID '{}'. Shortcut: '{}', State: {:?}",
+                        "No action defined for shortcut ID '{}'. Shortcut: '{}', State: {:?}",
                         binding_id_for_closure, shortcut_string, event.state
                     );
                 }
             }
         })
         .map_err(|e| {
-            let error_msg = format!("Couldn't register shortcut '{}': {}", binding.current_binding, e);
+            let error_msg = format!(
+                "Couldn't register shortcut '{}': {}",
+                binding.current_binding, e
+            );
             error!("_register_shortcut registration error: {}", error_msg);
             error_msg
         })?;
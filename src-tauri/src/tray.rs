@@ -1,3 +1,4 @@
+use crate::control;
 use crate::settings;
 use crate::tray_i18n::get_tray_translations;
 use tauri::image::Image;
@@ -73,6 +74,8 @@ pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
 
     // Update menu based on state
     update_tray_menu(app, &icon, None);
+
+    control::set_recording_state(app, (&icon).into());
 }
 
 pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&str>) {
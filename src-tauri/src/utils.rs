@@ -1,4 +1,5 @@
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::transcription::TranscriptionManager;
 use crate::shortcut;
 use crate::ManagedToggleState;
 use log::{info, warn};
@@ -32,6 +33,10 @@ pub fn cancel_current_operation(app: &AppHandle) {
     let audio_manager = app.state::<Arc<AudioRecordingManager>>();
     audio_manager.cancel_recording();
 
+    // Drop any streaming transcription that was fed by the cancelled recording
+    let transcription_manager = app.state::<Arc<TranscriptionManager>>();
+    transcription_manager.cancel_streaming_session();
+
     // Update tray icon and hide overlay
     change_tray_icon(app, crate::tray::TrayIconState::Idle);
     hide_recording_overlay(app);
@@ -21,6 +21,33 @@ async resetBinding(id: string) : Promise<Result<BindingResponse, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Creates a new shortcut that runs the transcribe action with its own options
+ */
+async addTranscribeBinding(name: string, binding: string, options: TranscribeOptions) : Promise<Result<BindingResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_transcribe_binding", { name, binding, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateTranscribeBinding(id: string, name: string, options: TranscribeOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_transcribe_binding", { id, name, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeTranscribeBinding(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_transcribe_binding", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePttSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_ptt_setting", { enabled }) };
@@ -109,6 +136,111 @@ async changeWordCorrectionThresholdSetting(threshold: number) : Promise<Result<n
     else return { status: "error", error: e  as any };
 }
 },
+async changeCustomWordsModeSetting(mode: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_custom_words_mode_setting", { mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeTranscriptionContextPromptSetting(prompt: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_transcription_context_prompt_setting", { prompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeInverseTextNormalizationSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_inverse_text_normalization_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVoiceCommandsEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_voice_commands_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVoiceCommandsLanguageSetting(language: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_voice_commands_language_setting", { language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeHandsFreeEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_hands_free_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets how long a pause ends a hands-free utterance, between 200 ms and 5 s
+ */
+async changeHandsFreeEndSilenceSetting(ms: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_hands_free_end_silence_setting", { ms }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the shortest hands-free utterance that is transcribed, up to 5 s
+ */
+async changeHandsFreeMinUtteranceSetting(ms: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_hands_free_min_utterance_setting", { ms }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets how many seconds without speech stop a recording, up to 5 minutes,
+ * or 0 to never stop on silence
+ */
+async changeAutoStopSilenceSetting(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_auto_stop_silence_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets how many seconds a recording without any speech may run, up to 10
+ * minutes, or 0 to never stop it for that
+ */
+async changeNoSpeechTimeoutSetting(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_no_speech_timeout_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the longest a recording may run, in seconds, or 0 for no limit
+ */
+async changeMaxRecordingSetting(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_max_recording_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePasteMethodSetting(method: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_paste_method_setting", { method }) };
@@ -197,6 +329,30 @@ async deletePostProcessPrompt(id: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async addAppProfile(name: string, apps: string[]) : Promise<Result<AppProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_app_profile", { name, apps }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateAppProfile(profile: AppProfile) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_app_profile", { profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteAppProfile(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_app_profile", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setPostProcessSelectedPrompt(id: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_post_process_selected_prompt", { id }) };
@@ -252,6 +408,118 @@ async changeAppendTrailingSpaceSetting(enabled: boolean) : Promise<Result<null,
     else return { status: "error", error: e  as any };
 }
 },
+async changeStreamingTranscriptionSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_streaming_transcription_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeApiServerEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_api_server_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeApiServerBindAddressSetting(address: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_api_server_bind_address_setting", { address }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeApiServerTokenSetting(token: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_api_server_token_setting", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeControlSocketEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_control_socket_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeModelCatalogUrlSetting(url: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_model_catalog_url_setting", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeModelDownloadConcurrencySetting(concurrency: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_model_download_concurrency_setting", { concurrency }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeModelDownloadMaxKibPerSecSetting(maxKibPerSec: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_model_download_max_kib_per_sec_setting", { maxKibPerSec }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeModelDownloadMirrorsSetting(mirrors: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_model_download_mirrors_setting", { mirrors }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeBenchmarkTargetLatencySetting(latencyMs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_benchmark_target_latency_setting", { latencyMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setRemoteTranscriptionProvider(providerId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_remote_transcription_provider", { providerId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeRemoteTranscriptionBaseUrlSetting(providerId: string, baseUrl: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_remote_transcription_base_url_setting", { providerId, baseUrl }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeRemoteTranscriptionApiKeySetting(providerId: string, apiKey: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_remote_transcription_api_key_setting", { providerId, apiKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeRemoteTranscriptionModelSetting(providerId: string, model: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_remote_transcription_model_setting", { providerId, model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAppLanguageSetting(language: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_app_language_setting", { language }) };
@@ -343,6 +611,77 @@ async openAppDataDir() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getReplacementRules() : Promise<Result<ReplacementRule[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_replacement_rules") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces every rule. The order of `rules` is the order they are applied in.
+ */
+async setReplacementRules(rules: ReplacementRule[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_replacement_rules", { rules }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Imports rules exported from Handy. With `merge`, imported rules replace
+ * existing ones with the same id and the rest are appended; otherwise they
+ * replace all rules.
+ */
+async importReplacementRules(json: string, merge: boolean) : Promise<Result<ReplacementRule[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_replacement_rules", { json, merge }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportReplacementRules() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_replacement_rules") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows what the current rules make of `text`, for trying rules out
+ */
+async previewReplacements(text: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_replacements", { text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the commands for the configured language, as they would be
+ * recognized in the next transcription
+ */
+async getVoiceCommands() : Promise<Result<VoiceCommandSet, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_voice_commands") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setVoiceCommandEnabled(id: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_voice_command_enabled", { id, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getAvailableModels() : Promise<Result<ModelInfo[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_available_models") };
@@ -375,6 +714,62 @@ async deleteModel(modelId: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async verifyModel(modelId: string) : Promise<Result<ModelVerification, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("verify_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async repairModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("repair_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async refreshModelCatalog() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("refresh_model_catalog") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async addCustomModel(model: CustomModel) : Promise<Result<ModelInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_custom_model", { model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeCustomModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_custom_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importModelArchive(modelId: string, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_model_archive", { modelId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async cancelDownload(modelId: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("cancel_download", { modelId }) };
@@ -383,6 +778,30 @@ async cancelDownload(modelId: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async pauseDownload(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pause_download", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resumeDownload(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_download", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDownloadQueue() : Promise<Result<DownloadProgress[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_download_queue") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setActiveModel(modelId: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_active_model", { modelId }) };
@@ -431,6 +850,26 @@ async hasAnyModelsOrDownloads() : Promise<Result<boolean, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Benchmarks the installed models on this machine. With `auto_select`, the
+ * recommended model is loaded and selected.
+ */
+async benchmarkModels(autoSelect: boolean) : Promise<Result<BenchmarkReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("benchmark_models", { autoSelect }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getModelBenchmarks() : Promise<Result<BenchmarkReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_model_benchmarks") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getRecommendedFirstModel() : Promise<Result<string, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_recommended_first_model") };
@@ -528,6 +967,20 @@ async getClamshellMicrophone() : Promise<Result<string, string>> {
 async isRecording() : Promise<boolean> {
     return await TAURI_INVOKE("is_recording");
 },
+async getHandsFreeState() : Promise<HandsFreeState> {
+    return await TAURI_INVOKE("get_hands_free_state");
+},
+/**
+ * Pauses or resumes hands-free listening, returning whether it is paused
+ */
+async toggleHandsFreePaused() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_hands_free_paused") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setModelUnloadTimeout(timeout: ModelUnloadTimeout) : Promise<void> {
     await TAURI_INVOKE("set_model_unload_timeout", { timeout });
 },
@@ -555,6 +1008,22 @@ async getHistoryEntries() : Promise<Result<HistoryEntry[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getHistoryEntriesPage(limit: number, offset: number) : Promise<Result<HistoryPage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_entries_page", { limit, offset }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchHistory(query: string, limit: number, offset: number) : Promise<Result<HistorySearchResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_history", { query, limit, offset }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async toggleHistoryEntrySaved(id: number) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("toggle_history_entry_saved", { id }) };
@@ -563,6 +1032,76 @@ async toggleHistoryEntrySaved(id: number) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getHistoryEntrySegments(id: number) : Promise<Result<TranscriptSegment[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_entry_segments", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryEntryRevisions(id: number) : Promise<Result<TranscriptionRevision[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_entry_revisions", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Runs an entry's saved recording through transcription and post-processing
+ * again, optionally with a different model, language or prompt, and stores
+ * the result as a new revision of the entry.
+ */
+async retranscribeHistoryEntry(id: number, modelId: string | null, language: string | null, promptId: string | null) : Promise<Result<TranscriptionRevision, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("retranscribe_history_entry", { id, modelId, language, promptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves the user's correction of an entry's text, or reverts the entry to
+ * its original text when `text` is `None`. Words the edit replaced are
+ * collected as correction proposals.
+ */
+async updateHistoryEntryText(id: number, text: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_history_entry_text", { id, text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCorrectionProposals() : Promise<Result<CorrectionProposal[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_correction_proposals") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Learns a correction as a custom word or an exact replacement rule,
+ * defaulting to the proposal's suggested kind
+ */
+async acceptCorrectionProposal(id: number, kind: CorrectionKind | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("accept_correction_proposal", { id, kind }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async dismissCorrectionProposal(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dismiss_correction_proposal", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getAudioFilePath(fileName: string) : Promise<Result<string, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_audio_file_path", { fileName }) };
@@ -579,6 +1118,14 @@ async deleteHistoryEntry(id: number) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async exportHistory(selection: ExportSelection, format: ExportFormat, archive: ExportArchive, destinationDir: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_history", { selection, format, archive, destinationDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateHistoryLimit(limit: number) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_history_limit", { limit }) };
@@ -621,24 +1168,256 @@ async isLaptop() : Promise<Result<boolean, string>> {
 
 /** user-defined types **/
 
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string }
+/**
+ * Settings overrides applied while a matching application is focused.
+ * `None` fields fall back to the global settings.
+ */
+export type AppProfile = { id: string; name: string; enabled?: boolean; 
+/**
+ * Process names, X11 window classes or macOS bundle identifiers, compared case-insensitively
+ */
+apps: string[]; selected_model?: string | null; selected_language?: string | null; post_process_selected_prompt_id?: string | null; paste_method?: PasteMethod | null; custom_words?: string[] | null }
+export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; custom_words_mode?: CustomWordsMode; 
+/**
+ * Free-form text, such as the topic or names, given to engines that take
+ * a prompt
+ */
+transcription_context_prompt?: string; 
+/**
+ * Interpret spoken punctuation and formatting commands such as "comma"
+ * or "new paragraph"
+ */
+voice_commands_enabled?: boolean; 
+/**
+ * Command set language, or "auto" to follow the transcription language
+ */
+voice_commands_language?: string; 
+/**
+ * Ids of commands to leave as plain words
+ */
+voice_commands_disabled?: string[]; 
+/**
+ * Write spoken numbers, dates, times, currency and units in written form
+ */
+inverse_text_normalization?: boolean; 
+/**
+ * Listen continuously and transcribe every utterance without a shortcut
+ */
+hands_free_enabled?: boolean; 
+/**
+ * Silence that ends a hands-free utterance
+ */
+hands_free_end_silence_ms?: number; 
+/**
+ * Hands-free utterances shorter than this are ignored
+ */
+hands_free_min_utterance_ms?: number; 
+/**
+ * Stop recording after this many seconds without speech once speech was
+ * heard, 0 to never
+ */
+auto_stop_silence_secs?: number; 
+/**
+ * Stop recording after this many seconds if no speech has been heard at
+ * all, 0 to never
+ */
+no_speech_timeout_secs?: number; 
+/**
+ * Stop recording after this many seconds, 0 for no limit
+ */
+max_recording_secs?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; streaming_transcription?: boolean; app_profiles?: AppProfile[]; api_server_enabled?: boolean; api_server_bind_address?: string; api_server_token?: string | null; control_socket_enabled?: boolean; remote_transcription_provider_id?: string; remote_transcription_providers?: PostProcessProvider[]; remote_transcription_api_keys?: Partial<{ [key in string]: string }>; remote_transcription_models?: Partial<{ [key in string]: string }>; 
+/**
+ * Signed model catalog to refresh from; empty keeps the bundled catalog
+ */
+model_catalog_url?: string; model_download_concurrency?: number; 
+/**
+ * Bandwidth shared by all model downloads; 0 means unlimited
+ */
+model_download_max_kib_per_sec?: number; 
+/**
+ * Base URLs tried in order before a model's own download URL
+ */
+model_download_mirrors?: string[]; 
+/**
+ * How long transcribing the benchmark clip may take for a model to be
+ * recommended
+ */
+benchmark_target_latency_ms?: number }
 export type AudioDevice = { index: string; name: string; is_default: boolean }
+export type BenchmarkReport = { results: ModelBenchmark[]; target_latency_ms: number; 
+/**
+ * The installed model with the lowest word error rate that transcribes
+ * the clip within the target latency, or the fastest one if none does
+ */
+recommended_model: string | null; 
+/**
+ * Some results were measured on the synthetic fallback clip. They have
+ * no word error rate, and are ranked by the catalog's accuracy score.
+ */
+synthetic_clip: boolean }
 export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
 export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
+/**
+ * How a correction can be learned
+ */
+export type CorrectionKind = "custom_word" | "replacement"
+/**
+ * A word or phrase the user keeps correcting in their history, offered to
+ * be learned as a custom word or replacement rule
+ */
+export type CorrectionProposal = { id: number; 
+/**
+ * What the transcription said, lowercase
+ */
+heard: string; 
+/**
+ * What the user typed instead
+ */
+corrected: string; 
+/**
+ * How many history entries the user made this correction in
+ */
+occurrences: number; last_seen: number; suggested_kind: CorrectionKind }
+/**
+ * A model registered by the user, persisted in `custom_models.json` in the
+ * models directory. Exactly one of `path` (a local GGML file or Parakeet
+ * directory, used in place) and `url` (downloaded like a built-in model) is
+ * set.
+ */
+export type CustomModel = { id: string; name: string; description?: string; engine_type: EngineType; path?: string | null; url?: string | null; size_mb?: number; sha256?: string | null }
 export type CustomSounds = { start: boolean; stop: boolean }
-export type EngineType = "Whisper" | "Parakeet"
-export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
+/**
+ * How custom words are used
+ */
+export type CustomWordsMode = "correction" | "biasing" | "both"
+export type DownloadProgress = { model_id: string; downloaded: number; total: number; percentage: number; state: DownloadState; queue_downloaded: number; queue_total: number }
+export type DownloadState = "queued" | "downloading" | "paused" | "retrying" | "completed" | "failed" | "cancelled"
+export type EngineType = "Whisper" | "Parakeet" | "Remote"
+export type ExportArchive = "none" | "zip" | "tar"
+export type ExportFormat = "srt" | "vtt" | "markdown" | "jsonl" | "csv"
+/**
+ * Which history entries to export
+ */
+export type ExportSelection = { type: "entry"; id: number } | { type: "date_range"; from: number; to: number } | { type: "saved" }
+/**
+ * Hands-free listening, emitted as `hands-free-state-changed` when it changes
+ */
+export type HandsFreeState = { enabled: boolean; paused: boolean }
+export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; 
+/**
+ * The output of Chinese variant conversion or LLM post-processing
+ */
+post_processed_text: string | null; post_process_prompt: string | null; 
+/**
+ * The text that was pasted, when voice commands or replacement rules
+ * changed it after any post-processing
+ */
+final_text: string | null; 
+/**
+ * The text as corrected by the user. The transcription itself is kept
+ * unchanged.
+ */
+edited_text: string | null }
+export type HistoryPage = { entries: HistoryEntry[]; total: number }
+/**
+ * A history entry matching a search, with the matched text highlighted in
+ * `snippet` using `<mark>` tags. Lower `rank` is a better match.
+ */
+export type HistorySearchResult = { entry: HistoryEntry; snippet: string; rank: number }
 export type LLMPrompt = { id: string; name: string; prompt: string }
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
-export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number }
+/**
+ * Measurements of one model on this machine
+ */
+export type ModelBenchmark = { model_id: string; load_ms: number; 
+/**
+ * Time to transcribe the reference clip
+ */
+transcribe_ms: number; 
+/**
+ * Transcription time divided by the clip length; below 1.0 is faster
+ * than real time
+ */
+real_time_factor: number; 
+/**
+ * Memory the model added to the process while loading and transcribing
+ */
+peak_memory_mb: number; 
+/**
+ * Share of the reference transcript's words the model got wrong. None
+ * when the model was measured on the synthetic fallback clip.
+ */
+word_error_rate?: number | null; 
+/**
+ * Unix timestamp in seconds
+ */
+measured_at: number }
+export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; is_custom: boolean; sha256: string | null; version: number; languages: string[] | null; update_available: boolean }
 export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
+/**
+ * Result of checking a model's files, also the payload of the
+ * `model-verification-failed` event
+ */
+export type ModelVerification = { model_id: string; status: VerificationStatus; problems: string[]; 
+/**
+ * Whether `repair_model` can restore the model by downloading it again
+ */
+can_repair: boolean }
+/**
+ * Where a binding delivers its transcription
+ */
+export type OutputTarget = "paste" | "clipboard" | "history"
 export type OverlayPosition = "none" | "top" | "bottom"
 export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v"
 export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null }
 export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
-export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string }
+export type ReplacementKind = "exact" | "regex"
+/**
+ * A rewrite applied to transcriptions. Rules run in list order, each on the
+ * output of the ones before it.
+ */
+export type ReplacementRule = { id: string; enabled?: boolean; kind: ReplacementKind; pattern: string; 
+/**
+ * The inserted text, which may span several lines (for snippets)
+ */
+replacement: string; case_sensitive?: boolean }
+export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string; transcribe_options?: TranscribeOptions | null }
 export type SoundTheme = "marimba" | "pop" | "custom"
+/**
+ * Per-binding overrides for the transcribe action. `None` fields fall back
+ * to the global settings (or the focused app's profile).
+ */
+export type TranscribeOptions = { post_process_prompt_id?: string | null; language?: string | null; translate_to_english?: boolean | null; model?: string | null; output_target?: OutputTarget | null; custom_words_mode?: CustomWordsMode | null; inverse_text_normalization?: boolean | null }
+/**
+ * A timed span of the transcript. `words` is empty for engines that only
+ * report segment-level timestamps.
+ */
+export type TranscriptSegment = { start: number; end: number; text: string; words: TranscriptWord[] }
+/**
+ * A single recognised word. Times are in seconds from the start of the recording.
+ */
+export type TranscriptWord = { start: number; end: number; text: string; confidence: number | null }
+/**
+ * A re-transcription of a history entry's recording. The original
+ * transcription stays on the entry itself; revisions are kept alongside it.
+ */
+export type TranscriptionRevision = { id: number; history_id: number; created_at: number; model_id: string; language: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null; final_text: string | null; segments: TranscriptSegment[] }
+export type VerificationStatus = "verified" | "unverified" | "damaged"
+/**
+ * A command as shown in settings
+ */
+export type VoiceCommandInfo = { id: string; 
+/**
+ * What to say, in the command set's language
+ */
+phrases: string[]; description: string; enabled: boolean }
+export type VoiceCommandSet = { 
+/**
+ * The language the commands are spoken in, or `None` if the current
+ * language has no command set
+ */
+language: string | null; commands: VoiceCommandInfo[] }
 
 /** tauri-specta globals **/
 
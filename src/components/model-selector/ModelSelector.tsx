@@ -1,6 +1,7 @@
 import React, { useState, useRef, useEffect } from "react";
 import { useTranslation } from "react-i18next";
 import { listen } from "@tauri-apps/api/event";
+import { toast } from "sonner";
 import { commands, type ModelInfo } from "@/bindings";
 import { getTranslatedModelName } from "../../lib/utils/modelTranslation";
 import ModelStatusButton from "./ModelStatusButton";
@@ -88,6 +89,13 @@ const ModelSelector: React.FC<ModelSelectorProps> = ({ onError }) => {
             setModelStatus("unloaded");
             setModelError(null);
             break;
+          case "fallback":
+            toast.warning(
+              t("modelSelector.fallback", {
+                modelName: model_name ?? model_id,
+              }),
+            );
+            break;
         }
       },
     );
@@ -0,0 +1,139 @@
+import React, { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Dropdown } from "../ui/Dropdown";
+import { SettingContainer } from "../ui/SettingContainer";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+import { LANGUAGES } from "../../lib/constants/languages";
+import { commands, type VoiceCommandInfo } from "@/bindings";
+
+// Languages the backend ships a voice command set for.
+const VOICE_COMMAND_LANGUAGES = ["en", "de", "es", "fr"];
+
+interface VoiceCommandsProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const VoiceCommands: React.FC<VoiceCommandsProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const [voiceCommands, setVoiceCommands] = useState<VoiceCommandInfo[]>([]);
+    // Undefined until loaded; null when no command set matches the language.
+    const [resolvedLanguage, setResolvedLanguage] = useState<
+      string | null | undefined
+    >(undefined);
+
+    const enabled = getSetting("voice_commands_enabled") ?? false;
+    const language = getSetting("voice_commands_language") || "auto";
+    const selectedLanguage = getSetting("selected_language");
+
+    const loadVoiceCommands = useCallback(async () => {
+      try {
+        const result = await commands.getVoiceCommands();
+        if (result.status === "ok") {
+          setVoiceCommands(result.data.commands);
+          setResolvedLanguage(result.data.language);
+        }
+      } catch (error) {
+        console.error("Failed to load voice commands:", error);
+      }
+    }, []);
+
+    // The active command set follows the transcription language when set to
+    // auto, so reload whenever either language changes.
+    useEffect(() => {
+      if (enabled) {
+        loadVoiceCommands();
+      }
+    }, [enabled, language, selectedLanguage, loadVoiceCommands]);
+
+    const handleCommandToggle = async (id: string, commandEnabled: boolean) => {
+      try {
+        const result = await commands.setVoiceCommandEnabled(
+          id,
+          commandEnabled,
+        );
+        if (result.status === "ok") {
+          setVoiceCommands((current) =>
+            current.map((command) =>
+              command.id === id
+                ? { ...command, enabled: commandEnabled }
+                : command,
+            ),
+          );
+        }
+      } catch (error) {
+        console.error("Failed to update voice command:", error);
+      }
+    };
+
+    const languageOptions = [
+      {
+        value: "auto",
+        label: t("settings.advanced.voiceCommands.language.auto"),
+      },
+      ...VOICE_COMMAND_LANGUAGES.map((code) => ({
+        value: code,
+        label: LANGUAGES.find((lang) => lang.value === code)?.label || code,
+      })),
+    ];
+
+    return (
+      <>
+        <ToggleSwitch
+          checked={enabled}
+          onChange={(value) => updateSetting("voice_commands_enabled", value)}
+          isUpdating={isUpdating("voice_commands_enabled")}
+          label={t("settings.advanced.voiceCommands.label")}
+          description={t("settings.advanced.voiceCommands.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        />
+        {enabled && (
+          <>
+            <SettingContainer
+              title={t("settings.advanced.voiceCommands.language.title")}
+              description={t(
+                "settings.advanced.voiceCommands.language.description",
+              )}
+              descriptionMode={descriptionMode}
+              grouped={grouped}
+            >
+              <Dropdown
+                options={languageOptions}
+                selectedValue={language}
+                onSelect={(value) =>
+                  updateSetting("voice_commands_language", value)
+                }
+                disabled={isUpdating("voice_commands_language")}
+              />
+            </SettingContainer>
+            {resolvedLanguage === null ? (
+              <div
+                className={`px-4 p-2 ${grouped ? "" : "rounded-lg border border-mid-gray/20"} text-sm text-mid-gray`}
+              >
+                {t("settings.advanced.voiceCommands.unsupported")}
+              </div>
+            ) : (
+              voiceCommands.map((command) => (
+                <ToggleSwitch
+                  key={command.id}
+                  checked={command.enabled}
+                  onChange={(value) => handleCommandToggle(command.id, value)}
+                  label={command.phrases
+                    .map((phrase) => `"${phrase}"`)
+                    .join(", ")}
+                  description={command.description}
+                  descriptionMode="tooltip"
+                  grouped={grouped}
+                />
+              ))
+            )}
+          </>
+        )}
+      </>
+    );
+  },
+);
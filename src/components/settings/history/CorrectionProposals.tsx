@@ -0,0 +1,119 @@
+import React, { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { listen } from "@tauri-apps/api/event";
+import { Button } from "../../ui/Button";
+import { useSettings } from "../../../hooks/useSettings";
+import {
+  commands,
+  type CorrectionKind,
+  type CorrectionProposal,
+} from "@/bindings";
+
+export const CorrectionProposals: React.FC = () => {
+  const { t } = useTranslation();
+  const { refreshSettings } = useSettings();
+  const [proposals, setProposals] = useState<CorrectionProposal[]>([]);
+
+  const loadProposals = useCallback(async () => {
+    try {
+      const result = await commands.getCorrectionProposals();
+      if (result.status === "ok") {
+        setProposals(result.data);
+      }
+    } catch (error) {
+      console.error("Failed to load correction proposals:", error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadProposals();
+
+    const unlistenPromise = listen("correction-proposals-updated", () => {
+      loadProposals();
+    });
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [loadProposals]);
+
+  const acceptProposal = async (id: number, kind: CorrectionKind) => {
+    try {
+      const result = await commands.acceptCorrectionProposal(id, kind);
+      if (result.status === "ok" && kind === "custom_word") {
+        // Custom words live in the settings store
+        await refreshSettings();
+      }
+    } catch (error) {
+      console.error("Failed to accept correction proposal:", error);
+    }
+  };
+
+  const dismissProposal = async (id: number) => {
+    try {
+      await commands.dismissCorrectionProposal(id);
+    } catch (error) {
+      console.error("Failed to dismiss correction proposal:", error);
+    }
+  };
+
+  if (proposals.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="space-y-2">
+      <div className="px-4">
+        <h2 className="text-xs font-medium text-mid-gray uppercase tracking-wide">
+          {t("settings.history.corrections.title")}
+        </h2>
+      </div>
+      <div className="bg-background border border-mid-gray/20 rounded-lg overflow-visible">
+        <div className="divide-y divide-mid-gray/20">
+          {proposals.map((proposal) => (
+            <div key={proposal.id} className="px-4 py-3 flex flex-col gap-2">
+              <p className="text-sm">
+                {t("settings.history.corrections.summary", {
+                  corrected: proposal.corrected,
+                  heard: proposal.heard,
+                  count: proposal.occurrences,
+                })}
+              </p>
+              <div className="flex items-center gap-2">
+                <Button
+                  onClick={() => acceptProposal(proposal.id, "custom_word")}
+                  variant={
+                    proposal.suggested_kind === "custom_word"
+                      ? "primary"
+                      : "secondary"
+                  }
+                  size="sm"
+                >
+                  {t("settings.history.corrections.addCustomWord")}
+                </Button>
+                <Button
+                  onClick={() => acceptProposal(proposal.id, "replacement")}
+                  variant={
+                    proposal.suggested_kind === "replacement"
+                      ? "primary"
+                      : "secondary"
+                  }
+                  size="sm"
+                >
+                  {t("settings.history.corrections.addReplacement")}
+                </Button>
+                <Button
+                  onClick={() => dismissProposal(proposal.id)}
+                  variant="ghost"
+                  size="sm"
+                >
+                  {t("settings.history.corrections.dismiss")}
+                </Button>
+              </div>
+            </div>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+};
@@ -2,12 +2,33 @@ import React, { useState, useEffect, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { AudioPlayer } from "../../ui/AudioPlayer";
 import { Button } from "../../ui/Button";
-import { Copy, Star, Check, Trash2, FolderOpen } from "lucide-react";
+import { Textarea } from "../../ui/Textarea";
+import { CorrectionProposals } from "./CorrectionProposals";
+import {
+  Copy,
+  Star,
+  Check,
+  Trash2,
+  FolderOpen,
+  Clock,
+  Pencil,
+} from "lucide-react";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { commands, type HistoryEntry } from "@/bindings";
+import {
+  commands,
+  type HistoryEntry,
+  type TranscriptSegment,
+} from "@/bindings";
 import { formatDateTime } from "@/utils/dateFormat";
 
+// The text that was delivered for an entry, before any edit by the user
+const deliveredText = (entry: HistoryEntry): string =>
+  entry.final_text ?? entry.post_processed_text ?? entry.transcription_text;
+
+const displayedText = (entry: HistoryEntry): string =>
+  entry.edited_text ?? deliveredText(entry);
+
 interface OpenRecordingsButtonProps {
   onClick: () => void;
   label: string;
@@ -171,6 +192,7 @@ export const HistorySettings: React.FC = () => {
 
   return (
     <div className="max-w-3xl w-full mx-auto space-y-6">
+      <CorrectionProposals />
       <div className="space-y-2">
         <div className="px-4 flex items-center justify-between">
           <div>
@@ -190,7 +212,7 @@ export const HistorySettings: React.FC = () => {
                 key={entry.id}
                 entry={entry}
                 onToggleSaved={() => toggleSaved(entry.id)}
-                onCopyText={() => copyToClipboard(entry.transcription_text)}
+                onCopyText={() => copyToClipboard(displayedText(entry))}
                 getAudioUrl={getAudioUrl}
                 deleteAudio={deleteAudioEntry}
               />
@@ -202,6 +224,14 @@ export const HistorySettings: React.FC = () => {
   );
 };
 
+// Formats a segment offset in seconds as m:ss
+const formatOffset = (seconds: number): string => {
+  const total = Math.floor(seconds);
+  const minutes = Math.floor(total / 60);
+  const secs = total % 60;
+  return `${minutes}:${secs.toString().padStart(2, "0")}`;
+};
+
 interface HistoryEntryProps {
   entry: HistoryEntry;
   onToggleSaved: () => void;
@@ -220,6 +250,10 @@ const HistoryEntryComponent: React.FC<HistoryEntryProps> = ({
   const { t, i18n } = useTranslation();
   const [audioUrl, setAudioUrl] = useState<string | null>(null);
   const [showCopied, setShowCopied] = useState(false);
+  const [segments, setSegments] = useState<TranscriptSegment[] | null>(null);
+  const [showSegments, setShowSegments] = useState(false);
+  const [isEditing, setIsEditing] = useState(false);
+  const [draftText, setDraftText] = useState("");
 
   useEffect(() => {
     const loadAudio = async () => {
@@ -244,6 +278,36 @@ const HistoryEntryComponent: React.FC<HistoryEntryProps> = ({
     }
   };
 
+  const toggleSegments = async () => {
+    if (!showSegments && segments === null) {
+      try {
+        const result = await commands.getHistoryEntrySegments(entry.id);
+        setSegments(result.status === "ok" ? result.data : []);
+      } catch (error) {
+        console.error("Failed to load segments:", error);
+        setSegments([]);
+      }
+    }
+    setShowSegments(!showSegments);
+  };
+
+  const startEditing = () => {
+    setDraftText(displayedText(entry));
+    setIsEditing(true);
+  };
+
+  // Passing null reverts the entry to the delivered text
+  const saveText = async (text: string | null) => {
+    try {
+      const result = await commands.updateHistoryEntryText(entry.id, text);
+      if (result.status === "ok") {
+        setIsEditing(false);
+      }
+    } catch (error) {
+      console.error("Failed to update entry text:", error);
+    }
+  };
+
   const formattedDate = formatDateTime(String(entry.timestamp), i18n.language);
 
   return (
@@ -262,6 +326,29 @@ const HistoryEntryComponent: React.FC<HistoryEntryProps> = ({
               <Copy width={16} height={16} />
             )}
           </button>
+          <button
+            onClick={startEditing}
+            disabled={isEditing}
+            className="p-2 rounded text-text/50 hover:text-logo-primary transition-colors cursor-pointer disabled:cursor-not-allowed"
+            title={t("settings.history.edit")}
+          >
+            <Pencil width={16} height={16} />
+          </button>
+          <button
+            onClick={toggleSegments}
+            className={`p-2 rounded transition-colors cursor-pointer ${
+              showSegments
+                ? "text-logo-primary hover:text-logo-primary/80"
+                : "text-text/50 hover:text-logo-primary"
+            }`}
+            title={
+              showSegments
+                ? t("settings.history.hideSegments")
+                : t("settings.history.showSegments")
+            }
+          >
+            <Clock width={16} height={16} />
+          </button>
           <button
             onClick={onToggleSaved}
             className={`p-2 rounded  transition-colors cursor-pointer ${
@@ -290,9 +377,62 @@ const HistoryEntryComponent: React.FC<HistoryEntryProps> = ({
           </button>
         </div>
       </div>
-      <p className="italic text-text/90 text-sm pb-2">
-        {entry.transcription_text}
-      </p>
+      {isEditing ? (
+        <div className="flex flex-col gap-2">
+          <Textarea
+            value={draftText}
+            onChange={(e) => setDraftText(e.target.value)}
+            variant="compact"
+          />
+          <div className="flex items-center gap-2">
+            <Button
+              onClick={() => saveText(draftText)}
+              disabled={!draftText.trim()}
+              variant="primary"
+              size="sm"
+            >
+              {t("settings.history.saveEdit")}
+            </Button>
+            <Button
+              onClick={() => setIsEditing(false)}
+              variant="secondary"
+              size="sm"
+            >
+              {t("settings.history.cancelEdit")}
+            </Button>
+            {entry.edited_text !== null && (
+              <Button onClick={() => saveText(null)} variant="ghost" size="sm">
+                {t("settings.history.revertEdit")}
+              </Button>
+            )}
+          </div>
+        </div>
+      ) : (
+        <p className="italic text-text/90 text-sm pb-2">
+          {displayedText(entry)}
+        </p>
+      )}
+      {entry.edited_text !== null && !isEditing && (
+        <p className="text-xs text-mid-gray">
+          {t("settings.history.original", { text: deliveredText(entry) })}
+        </p>
+      )}
+      {showSegments && segments !== null && (
+        <div className="flex flex-col gap-1 text-sm">
+          {segments.length === 0 ? (
+            <p className="text-text/60">{t("settings.history.noSegments")}</p>
+          ) : (
+            segments.map((segment, index) => (
+              <div key={index} className="flex gap-3">
+                <span className="text-mid-gray font-mono text-xs pt-0.5 shrink-0">
+                  {formatOffset(segment.start)}–{formatOffset(segment.end)}
+                </span>
+                <span className="text-text/90">{segment.text.trim()}</span>
+              </div>
+            ))
+          )}
+        </div>
+      )}
       {audioUrl && <AudioPlayer src={audioUrl} className="w-full" />}
     </div>
   );
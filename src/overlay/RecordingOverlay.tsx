@@ -17,6 +17,7 @@ const RecordingOverlay: React.FC = () => {
   const [isVisible, setIsVisible] = useState(false);
   const [state, setState] = useState<OverlayState>("recording");
   const [levels, setLevels] = useState<number[]>(Array(16).fill(0));
+  const [partialText, setPartialText] = useState("");
   const smoothedLevelsRef = useRef<number[]>(Array(16).fill(0));
 
   useEffect(() => {
@@ -26,6 +27,9 @@ const RecordingOverlay: React.FC = () => {
         // Sync language from settings each time overlay is shown
         await syncLanguageFromSettings();
         const overlayState = event.payload as OverlayState;
+        if (overlayState === "recording") {
+          setPartialText("");
+        }
         setState(overlayState);
         setIsVisible(true);
       });
@@ -35,6 +39,14 @@ const RecordingOverlay: React.FC = () => {
         setIsVisible(false);
       });
 
+      // Listen for the text transcribed so far while streaming
+      const unlistenPartial = await listen<string>(
+        "partial-transcript",
+        (event) => {
+          setPartialText(event.payload.trim());
+        },
+      );
+
       // Listen for mic-level updates
       const unlistenLevel = await listen<number[]>("mic-level", (event) => {
         const newLevels = event.payload as number[];
@@ -54,6 +66,7 @@ const RecordingOverlay: React.FC = () => {
         unlistenShow();
         unlistenHide();
         unlistenLevel();
+        unlistenPartial();
       };
     };
 
@@ -73,7 +86,12 @@ const RecordingOverlay: React.FC = () => {
       <div className="overlay-left">{getIcon()}</div>
 
       <div className="overlay-middle">
-        {state === "recording" && (
+        {state === "recording" && partialText && (
+          <div className="partial-transcript" title={partialText}>
+            <span>{partialText}</span>
+          </div>
+        )}
+        {state === "recording" && !partialText && (
           <div className="bars-container">
             {levels.map((v, i) => (
               <div
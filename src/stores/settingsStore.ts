@@ -125,6 +125,10 @@ const settingUpdaters: {
     commands.changeAppendTrailingSpaceSetting(value as boolean),
   log_level: (value) => commands.setLogLevel(value as any),
   app_language: (value) => commands.changeAppLanguageSetting(value as string),
+  voice_commands_enabled: (value) =>
+    commands.changeVoiceCommandsEnabledSetting(value as boolean),
+  voice_commands_language: (value) =>
+    commands.changeVoiceCommandsLanguageSetting(value as string),
 };
 
 export const useSettingsStore = create<SettingsStore>()(